@@ -0,0 +1,169 @@
+//! Builds Dispatch distribution trees off-chain
+//!
+//! Leaves are hashed with `DistributionTree::hash_leaf` and nodes with `utils::hash_node`, the
+//! sorted-pair hashing of the on-chain `utils::verify`, so the roots and proofs produced here are
+//! the ones the program accepts. Trees are laid out like the TypeScript `MerkleTree`: leaves are
+//! sorted, and the last node of an odd layer is carried up unchanged.
+
+use std::{collections::HashMap, fmt};
+
+use anchor_lang::prelude::Pubkey;
+use cash_dispatch::{state::DistributionTree, utils::hash_node, HashAlgorithm};
+
+/// A recipient of the distribution
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PaymentRow {
+    /// Index of the leaf, its bit in the tree's recipients_distributed_bitmap
+    pub index: u64,
+    pub recipient: Pubkey,
+    /// Amount paid to the recipient (shares on pro-rata trees)
+    pub amount: u64,
+}
+
+/// Hashing settings of the DistributionTree the tree is built for
+#[derive(Clone, Copy)]
+pub struct TreeOptions {
+    pub hash_algorithm: HashAlgorithm,
+    /// False only for trees created as v1, which always use Keccak
+    pub domain_separated: bool,
+}
+
+impl Default for TreeOptions {
+    fn default() -> Self {
+        TreeOptions {
+            hash_algorithm: HashAlgorithm::Keccak,
+            domain_separated: true,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum MerkleError {
+    /// The tree has no rows
+    EmptyTree,
+    /// Two rows share the same index
+    DuplicateIndex(u64),
+    /// Hashing failed (Poseidon inputs outside the BN254 field)
+    Hash(String),
+}
+
+impl fmt::Display for MerkleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MerkleError::EmptyTree => write!(f, "Tree has no rows"),
+            MerkleError::DuplicateIndex(index) => write!(f, "Duplicate leaf index {index}"),
+            MerkleError::Hash(error) => write!(f, "Hashing failed: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for MerkleError {}
+
+impl From<anchor_lang::error::Error> for MerkleError {
+    fn from(error: anchor_lang::error::Error) -> Self {
+        MerkleError::Hash(error.to_string())
+    }
+}
+
+/// A distribution tree with the proof of every leaf
+pub struct PaymentTree {
+    options: TreeOptions,
+    /// Leaf index => position of its leaf in the bottom layer
+    positions: HashMap<u64, usize>,
+    /// Layers from the sorted leaves up to the root
+    layers: Vec<Vec<[u8; 32]>>,
+}
+
+impl PaymentTree {
+    /// Builds a tree for a domain separated Keccak DistributionTree
+    pub fn new(rows: &[PaymentRow]) -> Result<PaymentTree, MerkleError> {
+        Self::with_options(rows, TreeOptions::default())
+    }
+
+    pub fn with_options(rows: &[PaymentRow], options: TreeOptions) -> Result<PaymentTree, MerkleError> {
+        if rows.is_empty() {
+            return Err(MerkleError::EmptyTree);
+        }
+
+        let mut leaves = Vec::with_capacity(rows.len());
+        for row in rows {
+            let leaf = DistributionTree::hash_leaf(
+                options.hash_algorithm,
+                options.domain_separated,
+                row.recipient,
+                row.amount,
+                row.index,
+            )?;
+            leaves.push((leaf, row.index));
+        }
+        leaves.sort();
+
+        let mut positions = HashMap::with_capacity(leaves.len());
+        for (position, (_, index)) in leaves.iter().enumerate() {
+            if positions.insert(*index, position).is_some() {
+                return Err(MerkleError::DuplicateIndex(*index));
+            }
+        }
+
+        let mut layers = vec![leaves.into_iter().map(|(leaf, _)| leaf).collect::<Vec<_>>()];
+        while layers[layers.len() - 1].len() > 1 {
+            let next_layer = Self::next_layer(&layers[layers.len() - 1], options)?;
+            layers.push(next_layer);
+        }
+
+        Ok(PaymentTree {
+            options,
+            positions,
+            layers,
+        })
+    }
+
+    fn next_layer(layer: &[[u8; 32]], options: TreeOptions) -> Result<Vec<[u8; 32]>, MerkleError> {
+        let (_, node_prefix) = DistributionTree::prefixes(options.domain_separated);
+        layer
+            .chunks(2)
+            .map(|pair| match pair {
+                [first, second] => Ok(hash_node(options.hash_algorithm, node_prefix, first, second)?),
+                [odd] => Ok(*odd),
+                _ => unreachable!(),
+            })
+            .collect()
+    }
+
+    /// The merkle root passed to `initialize` or `new_epoch`
+    pub fn root(&self) -> [u8; 32] {
+        self.layers[self.layers.len() - 1][0]
+    }
+
+    /// The proof of the leaf at `index`, passed to `distribute` or `claim`
+    pub fn proof(&self, index: u64) -> Option<Vec<[u8; 32]>> {
+        let mut position = *self.positions.get(&index)?;
+        let mut proof = Vec::with_capacity(self.layers.len());
+        for layer in &self.layers[..self.layers.len() - 1] {
+            let sibling = position ^ 1;
+            if let Some(node) = layer.get(sibling) {
+                proof.push(*node);
+            }
+            position /= 2;
+        }
+        Some(proof)
+    }
+
+    /// The leaf at `index`
+    pub fn leaf(&self, index: u64) -> Option<[u8; 32]> {
+        self.positions.get(&index).map(|position| self.layers[0][*position])
+    }
+
+    pub fn options(&self) -> TreeOptions {
+        self.options
+    }
+
+    /// Number of leaves in the tree
+    pub fn len(&self) -> usize {
+        self.layers[0].len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.layers[0].is_empty()
+    }
+}
@@ -0,0 +1,99 @@
+use anchor_lang::prelude::Pubkey;
+use cash_dispatch::{state::DistributionTree, utils::verify, HashAlgorithm};
+use cash_dispatch_merkle::{MerkleError, PaymentRow, PaymentTree, TreeOptions};
+
+const HASH_ALGORITHMS: [HashAlgorithm; 3] = [
+    HashAlgorithm::Keccak,
+    HashAlgorithm::Sha256,
+    HashAlgorithm::Poseidon,
+];
+
+fn rows(count: u64) -> Vec<PaymentRow> {
+    (0..count)
+        .map(|index| PaymentRow {
+            index,
+            recipient: Pubkey::new_unique(),
+            amount: (index + 1) * 1_000_000,
+        })
+        .collect()
+}
+
+fn verify_row(tree: &PaymentTree, row: &PaymentRow, amount: u64) -> bool {
+    let options = tree.options();
+    let (_, node_prefix) = DistributionTree::prefixes(options.domain_separated);
+    let leaf = DistributionTree::hash_leaf(
+        options.hash_algorithm,
+        options.domain_separated,
+        row.recipient,
+        amount,
+        row.index,
+    )
+    .unwrap();
+    let proof = tree.proof(row.index).unwrap();
+    verify(&proof, tree.root(), leaf, node_prefix, options.hash_algorithm).unwrap()
+}
+
+#[test]
+fn every_proof_verifies_on_chain() {
+    for hash_algorithm in HASH_ALGORITHMS {
+        for domain_separated in [true, false] {
+            // Trees without domain separation predate Poseidon
+            if hash_algorithm == HashAlgorithm::Poseidon && !domain_separated {
+                continue;
+            }
+            for count in 1..=9 {
+                let rows = rows(count);
+                let tree = PaymentTree::with_options(
+                    &rows,
+                    TreeOptions {
+                        hash_algorithm,
+                        domain_separated,
+                    },
+                )
+                .unwrap();
+                assert_eq!(tree.len(), rows.len());
+                for row in &rows {
+                    assert!(verify_row(&tree, row, row.amount));
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn proof_of_a_different_amount_fails() {
+    let rows = rows(5);
+    let tree = PaymentTree::new(&rows).unwrap();
+    for row in &rows {
+        assert!(!verify_row(&tree, row, row.amount + 1));
+    }
+}
+
+#[test]
+fn rows_order_does_not_change_the_root() {
+    let rows = rows(7);
+    let mut reversed = rows.clone();
+    reversed.reverse();
+    assert_eq!(
+        PaymentTree::new(&rows).unwrap().root(),
+        PaymentTree::new(&reversed).unwrap().root()
+    );
+}
+
+#[test]
+fn single_leaf_tree_has_an_empty_proof() {
+    let rows = rows(1);
+    let tree = PaymentTree::new(&rows).unwrap();
+    assert_eq!(tree.root(), tree.leaf(0).unwrap());
+    assert!(tree.proof(0).unwrap().is_empty());
+    assert!(tree.proof(1).is_none());
+}
+
+#[test]
+fn empty_and_duplicate_rows_are_rejected() {
+    assert_eq!(PaymentTree::new(&[]).err(), Some(MerkleError::EmptyTree));
+
+    let mut rows = rows(3);
+    rows[2].index = 1;
+    assert_eq!(PaymentTree::new(&rows).err(), Some(MerkleError::DuplicateIndex(1)));
+}
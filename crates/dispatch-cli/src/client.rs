@@ -0,0 +1,114 @@
+use anchor_lang::{prelude::Pubkey, solana_program::instruction::Instruction, AccountDeserialize};
+use anchor_spl::{
+    associated_token::spl_associated_token_account::instruction::create_associated_token_account_idempotent,
+    token_interface::Mint,
+};
+use anyhow::{Context, Result};
+use cash_dispatch::state::{DistributionTree, MintRegistry};
+use solana_rpc_client::rpc_client::RpcClient;
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    signature::{Keypair, Signature, Signer},
+    transaction::Transaction,
+};
+
+use crate::instructions::{mint_registry_address, RegisteredMint};
+
+/// Sends the authority's transactions and reads the program's accounts
+pub struct DispatchClient {
+    pub rpc: RpcClient,
+    pub authority: Keypair,
+}
+
+impl DispatchClient {
+    pub fn new(url: String, authority: Keypair) -> DispatchClient {
+        DispatchClient {
+            rpc: RpcClient::new_with_commitment(url, CommitmentConfig::confirmed()),
+            authority,
+        }
+    }
+
+    pub fn authority(&self) -> Pubkey {
+        self.authority.pubkey()
+    }
+
+    /// Signs the instructions with the authority and sends them in one transaction
+    pub fn send(&self, instructions: &[Instruction]) -> Result<Signature> {
+        let blockhash = self.rpc.get_latest_blockhash()?;
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.authority.pubkey()),
+            &[&self.authority],
+            blockhash,
+        );
+        Ok(self.rpc.send_and_confirm_transaction(&transaction)?)
+    }
+
+    pub fn fetch_distribution_tree(&self, address: &Pubkey) -> Result<DistributionTree> {
+        let data = self
+            .rpc
+            .get_account_data(address)
+            .with_context(|| format!("Distribution tree {address} not found"))?;
+        Ok(DistributionTree::try_deserialize(&mut data.as_slice())?)
+    }
+
+    /// Whether the account exists
+    pub fn exists(&self, address: &Pubkey) -> Result<bool> {
+        Ok(self
+            .rpc
+            .get_account_with_commitment(address, self.rpc.commitment())?
+            .value
+            .is_some())
+    }
+
+    /// Returns the decimals and token program (owner) of a mint
+    pub fn fetch_mint(&self, mint: &Pubkey) -> Result<(u8, Pubkey)> {
+        let account = self
+            .rpc
+            .get_account(mint)
+            .with_context(|| format!("Mint {mint} not found"))?;
+        let decimals = Mint::try_deserialize(&mut account.data.as_slice())?.decimals;
+        Ok((decimals, account.owner))
+    }
+
+    /// Returns the mints registered with `add_mint`, in mint index order
+    pub fn fetch_registered_mints(&self, distribution_tree: &Pubkey) -> Result<Vec<RegisteredMint>> {
+        let address = mint_registry_address(distribution_tree);
+        let Some(account) = self
+            .rpc
+            .get_account_with_commitment(&address, self.rpc.commitment())?
+            .value
+        else {
+            return Ok(Vec::new());
+        };
+        let mint_registry = MintRegistry::try_deserialize(&mut account.data.as_slice())?;
+        mint_registry
+            .mints
+            .iter()
+            .map(|mint| {
+                let (_, token_program) = self.fetch_mint(mint)?;
+                Ok(RegisteredMint {
+                    mint: *mint,
+                    token_program,
+                })
+            })
+            .collect()
+    }
+
+    /// Instructions creating the authority's token account of every registered mint
+    /// `cancel` and `close` sweep the registered vaults into these accounts
+    pub fn create_authority_token_accounts(&self, registered_mints: &[RegisteredMint]) -> Vec<Instruction> {
+        let authority = self.authority();
+        registered_mints
+            .iter()
+            .map(|registered| {
+                create_associated_token_account_idempotent(
+                    &authority,
+                    &authority,
+                    &registered.mint,
+                    &registered.token_program,
+                )
+            })
+            .collect()
+    }
+}
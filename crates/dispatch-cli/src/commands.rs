@@ -0,0 +1,337 @@
+use std::{
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anchor_lang::{prelude::Pubkey, solana_program::instruction::Instruction};
+use anyhow::{bail, Result};
+use cash_dispatch::{
+    state::{DistributionStatus, DistributionTree, HashAlgorithm},
+    DistributeParams, InitializeParams,
+};
+use cash_dispatch_merkle::{PaymentRow, PaymentTree, TreeOptions};
+use clap::{Args, ValueEnum};
+use serde_json::json;
+
+use crate::{
+    client::DispatchClient,
+    instructions::{self, distribution_tree_address, RegisteredMint},
+    output::{hex, Report},
+    recipients,
+};
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum HashAlgorithmArg {
+    Keccak,
+    Sha256,
+    Poseidon,
+}
+
+impl From<HashAlgorithmArg> for HashAlgorithm {
+    fn from(hash_algorithm: HashAlgorithmArg) -> Self {
+        match hash_algorithm {
+            HashAlgorithmArg::Keccak => HashAlgorithm::Keccak,
+            HashAlgorithmArg::Sha256 => HashAlgorithm::Sha256,
+            HashAlgorithmArg::Poseidon => HashAlgorithm::Poseidon,
+        }
+    }
+}
+
+#[derive(Args)]
+pub struct BatchArgs {
+    /// Batch ID of the distribution tree
+    #[arg(long)]
+    pub batch_id: String,
+}
+
+#[derive(Args)]
+pub struct InitializeArgs {
+    /// Batch ID of the new distribution tree (8 to 15 characters)
+    #[arg(long)]
+    pub batch_id: String,
+    /// Recipients CSV with an `index,recipient,amount` header (index optional)
+    #[arg(long)]
+    pub recipients: PathBuf,
+    /// Mint distributed by the tree
+    #[arg(long, default_value = cash_dispatch::PYUSD_MINT)]
+    pub mint: Pubkey,
+    /// Time the distribution starts (Unix timestamp, defaults to now)
+    #[arg(long)]
+    pub start_ts: Option<i64>,
+    /// Time the distribution ends (Unix timestamp)
+    #[arg(long)]
+    pub end_ts: Option<i64>,
+    /// Lets recipients claim their own leaves
+    #[arg(long)]
+    pub allow_claims: bool,
+    #[arg(long, value_enum, default_value = "keccak")]
+    pub hash_algorithm: HashAlgorithmArg,
+    /// Wallet receiving declined allocations
+    #[arg(long)]
+    pub decline_destination: Option<Pubkey>,
+    /// Acknowledges the mint's permanent delegate, default-frozen accounts or freeze authority
+    #[arg(long)]
+    pub acknowledge_mint_risks: bool,
+}
+
+#[derive(Args)]
+pub struct DistributeArgs {
+    /// Batch ID of the distribution tree
+    #[arg(long)]
+    pub batch_id: String,
+    /// Recipients CSV the tree was initialized with
+    #[arg(long)]
+    pub recipients: PathBuf,
+    /// Leaf indices to distribute, one transaction each
+    #[arg(long, required = true, num_args = 1..)]
+    pub index: Vec<u64>,
+}
+
+#[derive(Args)]
+pub struct CloseArgs {
+    /// Batch ID of the distribution tree
+    #[arg(long)]
+    pub batch_id: String,
+    /// Acknowledges that closing the tree is irreversible
+    #[arg(long)]
+    pub yes: bool,
+}
+
+fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs() as i64)
+}
+
+fn tree_options(distribution_tree: &DistributionTree) -> TreeOptions {
+    TreeOptions {
+        hash_algorithm: distribution_tree.hash_algorithm,
+        domain_separated: distribution_tree.domain_separated,
+    }
+}
+
+/// Builds the tree of the recipients file and checks it matches the tree's merkle root
+pub fn load_tree(path: &Path, distribution_tree: &DistributionTree) -> Result<(Vec<PaymentRow>, PaymentTree)> {
+    let rows = recipients::load(path)?;
+    let payment_tree = PaymentTree::with_options(&rows, tree_options(distribution_tree))?;
+    if payment_tree.root() != distribution_tree.merkle_root {
+        bail!(
+            "Recipients file root {} does not match the tree's merkle root {}",
+            hex(&payment_tree.root()),
+            hex(&distribution_tree.merkle_root)
+        );
+    }
+    Ok((rows, payment_tree))
+}
+
+/// Builds the `distribute` instruction of a standard leaf of the recipients file
+pub fn distribute_instruction(
+    authority: &Pubkey,
+    batch_id: &str,
+    distribution_tree: &DistributionTree,
+    token_program: &Pubkey,
+    payment_tree: &PaymentTree,
+    row: &PaymentRow,
+) -> Instruction {
+    let params = DistributeParams {
+        amount: row.amount,
+        proof: payment_tree.proof(row.index).unwrap_or_default(),
+        batch_id: batch_id.to_string(),
+        index: row.index,
+        epoch: distribution_tree.epoch,
+        splits: None,
+        schedule: None,
+        installments: None,
+        mint_index: None,
+    };
+    instructions::distribute(authority, &distribution_tree.mint, token_program, &row.recipient, params)
+}
+
+/// Creates and funds a distribution tree for the recipients file
+/// The authority funds the vault with the total amount, plus the fee
+pub fn initialize(client: &DispatchClient, args: InitializeArgs) -> Result<Report> {
+    let authority = client.authority();
+    let rows = recipients::load(&args.recipients)?;
+    let options = TreeOptions {
+        hash_algorithm: args.hash_algorithm.into(),
+        domain_separated: true,
+    };
+    let payment_tree = PaymentTree::with_options(&rows, options)?;
+    let total_amount = recipients::total_amount(&rows)?;
+    let (mint_decimals, token_program) = client.fetch_mint(&args.mint)?;
+
+    let params = InitializeParams {
+        merkle_root: payment_tree.root(),
+        batch_id: args.batch_id.clone(),
+        allow_claims: args.allow_claims,
+        total_number_recipients: rows.len() as u64,
+        transfer_to_vault_amount: total_amount,
+        mint_decimals,
+        start_ts: args.start_ts.unwrap_or_else(now),
+        end_ts: args.end_ts,
+        gatekeeper_network: None,
+        hash_algorithm: Some(options.hash_algorithm),
+        total_shares: None,
+        open_claim_amount: None,
+        decline_destination: args.decline_destination,
+        acknowledge_mint_risks: args.acknowledge_mint_risks,
+    };
+    let signature = client.send(&[instructions::initialize(&authority, &args.mint, &token_program, params)])?;
+
+    let address = distribution_tree_address(&authority, &args.batch_id);
+    let distribution_tree = client.fetch_distribution_tree(&address)?;
+    Ok(Report::new("initialize")
+        .field("signature", signature.to_string())
+        .field("distribution_tree", address.to_string())
+        .field("token_vault", distribution_tree.token_vault.to_string())
+        .field("transfer_to_vault_amount", total_amount)
+        .tree(&distribution_tree))
+}
+
+/// Expands the recipients bitmap until the tree is active
+pub fn expand(client: &DispatchClient, args: BatchArgs) -> Result<Report> {
+    let authority = client.authority();
+    let address = distribution_tree_address(&authority, &args.batch_id);
+    let mut distribution_tree = client.fetch_distribution_tree(&address)?;
+    if distribution_tree.status != DistributionStatus::InsufficientBitmapSpace {
+        bail!("Distribution tree {address} does not need to be expanded");
+    }
+
+    let mut signatures = Vec::new();
+    while distribution_tree.status == DistributionStatus::InsufficientBitmapSpace {
+        signatures.push(client.send(&[instructions::expand(&authority, &args.batch_id)])?.to_string());
+        distribution_tree = client.fetch_distribution_tree(&address)?;
+    }
+    Ok(Report::new("expand")
+        .field("signatures", signatures)
+        .field("distribution_tree", address.to_string())
+        .tree(&distribution_tree))
+}
+
+/// Distributes the given leaves of the recipients file
+/// Leaves already marked in the recipients bitmap are skipped
+pub fn distribute(client: &DispatchClient, args: DistributeArgs) -> Result<Report> {
+    let authority = client.authority();
+    let address = distribution_tree_address(&authority, &args.batch_id);
+    let distribution_tree = client.fetch_distribution_tree(&address)?;
+    let (rows, payment_tree) = load_tree(&args.recipients, &distribution_tree)?;
+    let (_, token_program) = client.fetch_mint(&distribution_tree.mint)?;
+
+    let mut distributed = Vec::new();
+    let mut skipped = Vec::new();
+    for index in args.index {
+        let row = recipients::find(&rows, index)?;
+        if distribution_tree.is_claimed(index)? {
+            skipped.push(index);
+            continue;
+        }
+        let signature = client.send(&[distribute_instruction(
+            &authority,
+            &args.batch_id,
+            &distribution_tree,
+            &token_program,
+            &payment_tree,
+            row,
+        )])?;
+        distributed.push(json!({
+            "index": index,
+            "recipient": row.recipient.to_string(),
+            "amount": row.amount,
+            "signature": signature.to_string(),
+        }));
+    }
+
+    let distribution_tree = client.fetch_distribution_tree(&address)?;
+    Ok(Report::new("distribute")
+        .field("distribution_tree", address.to_string())
+        .field("distributed", distributed)
+        .field("skipped", skipped)
+        .tree(&distribution_tree))
+}
+
+pub fn pause(client: &DispatchClient, args: BatchArgs) -> Result<Report> {
+    let authority = client.authority();
+    let signature = client.send(&[instructions::pause(&authority, &args.batch_id)])?;
+    report_tree(client, "pause", &args.batch_id, Some(signature.to_string()))
+}
+
+pub fn resume(client: &DispatchClient, args: BatchArgs) -> Result<Report> {
+    let authority = client.authority();
+    let signature = client.send(&[instructions::resume(&authority, &args.batch_id)])?;
+    report_tree(client, "resume", &args.batch_id, Some(signature.to_string()))
+}
+
+/// Cancels the distribution, refunding the vault of the tree's mint and of every registered mint
+pub fn cancel(client: &DispatchClient, args: BatchArgs) -> Result<Report> {
+    let authority = client.authority();
+    let address = distribution_tree_address(&authority, &args.batch_id);
+    let distribution_tree = client.fetch_distribution_tree(&address)?;
+    let (_, token_program) = client.fetch_mint(&distribution_tree.mint)?;
+    let registered_mints = client.fetch_registered_mints(&address)?;
+
+    let mut cancel_instructions = client.create_authority_token_accounts(&registered_mints);
+    cancel_instructions.push(instructions::cancel(
+        &authority,
+        &args.batch_id,
+        &distribution_tree.mint,
+        &token_program,
+        &registered_mints,
+    ));
+    let signature = client.send(&cancel_instructions)?;
+    report_tree(client, "cancel", &args.batch_id, Some(signature.to_string()))
+}
+
+/// Shrinks a completed or cancelled tree to its minimum size, returning the rent to the authority
+pub fn reclaim(client: &DispatchClient, args: BatchArgs) -> Result<Report> {
+    let authority = client.authority();
+    let signature = client.send(&[instructions::reclaim(&authority, &args.batch_id)])?;
+    report_tree(client, "reclaim", &args.batch_id, Some(signature.to_string()))
+}
+
+/// Closes a completed or cancelled tree, sweeping the token vault, every registered vault and the SOL vault
+pub fn close(client: &DispatchClient, args: CloseArgs) -> Result<Report> {
+    if !args.yes {
+        bail!("Closing a distribution tree is irreversible, pass --yes to confirm");
+    }
+    let authority = client.authority();
+    let address = distribution_tree_address(&authority, &args.batch_id);
+    let distribution_tree = client.fetch_distribution_tree(&address)?;
+    let tree_mint = if distribution_tree.is_native() {
+        None
+    } else {
+        let (_, token_program) = client.fetch_mint(&distribution_tree.mint)?;
+        Some(RegisteredMint {
+            mint: distribution_tree.mint,
+            token_program,
+        })
+    };
+    let registered_mints = client.fetch_registered_mints(&address)?;
+
+    let swept_mints: Vec<RegisteredMint> = tree_mint.into_iter().chain(registered_mints.iter().copied()).collect();
+    let mut close_instructions = client.create_authority_token_accounts(&swept_mints);
+    close_instructions.push(instructions::close(&authority, &args.batch_id, tree_mint, &registered_mints));
+    let signature = client.send(&close_instructions)?;
+    Ok(Report::new("close")
+        .field("signature", signature.to_string())
+        .field("distribution_tree", address.to_string())
+        .field("closed", !client.exists(&address)?))
+}
+
+pub fn show(client: &DispatchClient, args: BatchArgs) -> Result<Report> {
+    report_tree(client, "show", &args.batch_id, None)
+}
+
+fn report_tree(client: &DispatchClient, command: &str, batch_id: &str, signature: Option<String>) -> Result<Report> {
+    let address = distribution_tree_address(&client.authority(), batch_id);
+    let distribution_tree = client.fetch_distribution_tree(&address)?;
+    let mut report = Report::new(command);
+    if let Some(signature) = signature {
+        report = report.field("signature", signature);
+    }
+    report = report.field("distribution_tree", address.to_string());
+    if !distribution_tree.is_native() {
+        let vault_balance = client.rpc.get_token_account_balance(&distribution_tree.token_vault)?;
+        report = report.field("vault_balance", vault_balance.amount);
+    }
+    Ok(report.tree(&distribution_tree))
+}
@@ -0,0 +1,276 @@
+//! Distributes every leaf of a recipients file, resuming from a local state file
+//!
+//! The on-chain `recipients_distributed_bitmap` is the source of truth: leaves marked in it are
+//! skipped, and leaves the state file records as distributed but the bitmap does not are queued again.
+
+use std::{
+    collections::{BTreeMap, BTreeSet, VecDeque},
+    fs,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    thread,
+    time::Duration,
+};
+
+use anchor_lang::prelude::Pubkey;
+use anyhow::{bail, Context, Result};
+use cash_dispatch::state::{DistributionStatus, DistributionTree};
+use cash_dispatch_merkle::{PaymentRow, PaymentTree};
+use clap::Args;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use solana_rpc_client_api::client_error::Error as ClientError;
+use solana_sdk::transaction::TransactionError;
+
+use crate::{
+    client::DispatchClient,
+    commands::{distribute_instruction, load_tree},
+    instructions::distribution_tree_address,
+    output::{hex, status_name, Report},
+};
+
+#[derive(Args)]
+pub struct CrankArgs {
+    /// Batch ID of the distribution tree
+    #[arg(long)]
+    pub batch_id: String,
+    /// Recipients CSV the tree was initialized with
+    #[arg(long)]
+    pub recipients: PathBuf,
+    /// State file recording the crank's progress, created if missing
+    #[arg(long)]
+    pub state: PathBuf,
+    /// Number of distribute transactions in flight at once
+    #[arg(long, default_value_t = 8)]
+    pub concurrency: usize,
+    /// Attempts per leaf after the first, for transactions that fail to land
+    #[arg(long, default_value_t = 3)]
+    pub max_retries: u32,
+    /// Number of distributed leaves between writes of the state file
+    #[arg(long, default_value_t = 100)]
+    pub checkpoint_interval: usize,
+}
+
+/// Progress of the crank on one epoch of a distribution tree
+#[derive(Serialize, Deserialize, Default)]
+pub struct CrankState {
+    pub distribution_tree: String,
+    pub epoch: u64,
+    pub merkle_root: String,
+    /// Leaves distributed by the crank or found marked in the recipients bitmap
+    pub distributed: BTreeSet<u64>,
+    /// Leaves that failed on the last run, with their error
+    pub failed: BTreeMap<u64, String>,
+}
+
+impl CrankState {
+    pub fn new(address: &Pubkey, epoch: u64, merkle_root: &[u8; 32]) -> CrankState {
+        CrankState {
+            distribution_tree: address.to_string(),
+            epoch,
+            merkle_root: hex(merkle_root),
+            ..CrankState::default()
+        }
+    }
+
+    /// Loads the state file, or starts from `expected` if it does not exist
+    /// A state file of another tree, epoch or merkle root is rejected
+    pub fn load(path: &Path, expected: CrankState) -> Result<CrankState> {
+        if !path.exists() {
+            return Ok(expected);
+        }
+        let data = fs::read(path).with_context(|| format!("Failed to read state file {}", path.display()))?;
+        let state: CrankState =
+            serde_json::from_slice(&data).with_context(|| format!("Invalid state file {}", path.display()))?;
+        if (&state.distribution_tree, state.epoch, &state.merkle_root)
+            != (&expected.distribution_tree, expected.epoch, &expected.merkle_root)
+        {
+            bail!(
+                "State file {} belongs to tree {} epoch {}, use a new state file",
+                path.display(),
+                state.distribution_tree,
+                state.epoch
+            );
+        }
+        Ok(state)
+    }
+
+    /// Writes the state to a temporary file and renames it over the state file, so a crash
+    /// never leaves a partially written state
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let temporary = path.with_extension("tmp");
+        fs::write(&temporary, serde_json::to_vec_pretty(self)?)?;
+        fs::rename(&temporary, path).with_context(|| format!("Failed to write state file {}", path.display()))
+    }
+
+    /// Reconciles the state with the recipients bitmap and returns the leaves left to distribute
+    ///     1. Leaves marked in the bitmap are recorded as distributed
+    ///     2. Leaves recorded as distributed but not marked in the bitmap are queued again
+    pub fn reconcile(&mut self, rows: &[PaymentRow], is_distributed: impl Fn(u64) -> Result<bool>) -> Result<Plan> {
+        let mut plan = Plan::default();
+        for row in rows {
+            if is_distributed(row.index)? {
+                self.distributed.insert(row.index);
+                self.failed.remove(&row.index);
+                plan.already_distributed += 1;
+                continue;
+            }
+            if self.distributed.remove(&row.index) {
+                plan.reconciled.push(row.index);
+            }
+            plan.pending.push(row.index);
+        }
+        Ok(plan)
+    }
+}
+
+/// Leaves of the recipients file left to distribute
+#[derive(Default)]
+pub struct Plan {
+    pub pending: Vec<u64>,
+    pub already_distributed: usize,
+    /// Leaves the state file recorded as distributed that the bitmap does not mark
+    pub reconciled: Vec<u64>,
+}
+
+/// Whether a failed send should be retried
+/// Transactions rejected by the program fail the same way every time
+fn is_retryable(error: &anyhow::Error) -> bool {
+    !matches!(
+        error
+            .downcast_ref::<ClientError>()
+            .and_then(ClientError::get_transaction_error),
+        Some(TransactionError::InstructionError(..))
+    )
+}
+
+struct Crank<'a> {
+    client: &'a DispatchClient,
+    args: &'a CrankArgs,
+    address: Pubkey,
+    distribution_tree: DistributionTree,
+    token_program: Pubkey,
+    payment_tree: PaymentTree,
+    rows: BTreeMap<u64, PaymentRow>,
+    queue: Mutex<VecDeque<u64>>,
+    state: Mutex<CrankState>,
+    distributed: AtomicUsize,
+}
+
+impl Crank<'_> {
+    /// Sends the leaf's distribute transaction, retrying transactions that fail to land
+    /// Before each retry the bitmap is checked, in case the previous attempt landed unconfirmed
+    fn distribute(&self, index: u64) -> Result<()> {
+        let row = &self.rows[&index];
+        let instruction = distribute_instruction(
+            &self.client.authority(),
+            &self.args.batch_id,
+            &self.distribution_tree,
+            &self.token_program,
+            &self.payment_tree,
+            row,
+        );
+        let mut attempt = 0;
+        loop {
+            let error = match self.client.send(std::slice::from_ref(&instruction)) {
+                Ok(_) => return Ok(()),
+                Err(error) => error,
+            };
+            if attempt == self.args.max_retries || !is_retryable(&error) {
+                return Err(error);
+            }
+            attempt += 1;
+            thread::sleep(Duration::from_millis(500 * attempt as u64));
+            if self.client.fetch_distribution_tree(&self.address)?.is_claimed(index)? {
+                return Ok(());
+            }
+        }
+    }
+
+    fn record(&self, index: u64, result: Result<()>) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        match result {
+            Ok(()) => {
+                state.distributed.insert(index);
+                state.failed.remove(&index);
+                let distributed = self.distributed.fetch_add(1, Ordering::Relaxed) + 1;
+                if distributed.is_multiple_of(self.args.checkpoint_interval.max(1)) {
+                    state.save(&self.args.state)?;
+                    eprintln!("Distributed {distributed} leaves");
+                }
+            }
+            Err(error) => {
+                eprintln!("Failed to distribute leaf {index}: {error:#}");
+                state.failed.insert(index, format!("{error:#}"));
+            }
+        }
+        Ok(())
+    }
+
+    fn work(&self) -> Result<()> {
+        loop {
+            let Some(index) = self.queue.lock().unwrap().pop_front() else {
+                return Ok(());
+            };
+            let result = self.distribute(index);
+            self.record(index, result)?;
+        }
+    }
+}
+
+/// Distributes every leaf of the recipients file not yet marked in the recipients bitmap
+pub fn crank(client: &DispatchClient, args: CrankArgs) -> Result<Report> {
+    let address = distribution_tree_address(&client.authority(), &args.batch_id);
+    let distribution_tree = client.fetch_distribution_tree(&address)?;
+    if distribution_tree.status != DistributionStatus::Active {
+        bail!(
+            "Distribution tree {address} is {}, only active trees can be cranked",
+            status_name(&distribution_tree.status)
+        );
+    }
+    let (rows, payment_tree) = load_tree(&args.recipients, &distribution_tree)?;
+    let (_, token_program) = client.fetch_mint(&distribution_tree.mint)?;
+
+    let mut state = CrankState::load(
+        &args.state,
+        CrankState::new(&address, distribution_tree.epoch, &distribution_tree.merkle_root),
+    )?;
+    let plan = state.reconcile(&rows, |index| distribution_tree.is_claimed(index).map_err(Into::into))?;
+    state.save(&args.state)?;
+
+    let crank = Crank {
+        client,
+        args: &args,
+        address,
+        distribution_tree,
+        token_program,
+        payment_tree,
+        rows: rows.into_iter().map(|row| (row.index, row)).collect(),
+        queue: Mutex::new(plan.pending.iter().copied().collect()),
+        state: Mutex::new(state),
+        distributed: AtomicUsize::new(0),
+    };
+    thread::scope(|scope| {
+        let workers: Vec<_> = (0..args.concurrency.max(1))
+            .map(|_| scope.spawn(|| crank.work()))
+            .collect();
+        workers
+            .into_iter()
+            .try_for_each(|worker| worker.join().expect("crank worker panicked"))
+    })?;
+
+    let state = crank.state.into_inner().unwrap();
+    state.save(&args.state)?;
+    let distribution_tree = client.fetch_distribution_tree(&address)?;
+    Ok(Report::new("crank")
+        .field("distribution_tree", address.to_string())
+        .field("state", args.state.display().to_string())
+        .field("already_distributed", plan.already_distributed)
+        .field("reconciled", plan.reconciled)
+        .field("distributed", crank.distributed.into_inner())
+        .field("failed", json!(state.failed))
+        .tree(&distribution_tree))
+}
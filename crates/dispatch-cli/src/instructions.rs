@@ -0,0 +1,265 @@
+//! Builds the program's instructions from its own Anchor account and argument types
+
+use std::str::FromStr;
+
+use anchor_lang::{
+    prelude::{AccountMeta, Pubkey},
+    solana_program::{instruction::Instruction, system_program},
+    InstructionData, ToAccountMetas,
+};
+use anchor_spl::associated_token::{self, get_associated_token_address_with_program_id};
+use cash_dispatch::{
+    accounts, instruction, CancelParams, CloseParams, DistributeParams, ExpandParams, InitializeParams,
+    PauseResumeParams, ReclaimParams, DISPUTE_SEED, DISTRIBUTION_TREE_SEED, FEES_WALLET,
+    INSTALLMENT_RECORD_SEED, LEAF_REDIRECT_SEED, MINT_REGISTRY_SEED, SOL_VAULT_SEED,
+};
+
+/// Mint and token program of a token vault (the tree's mint, or a mint registered with `add_mint`)
+#[derive(Clone, Copy)]
+pub struct RegisteredMint {
+    pub mint: Pubkey,
+    pub token_program: Pubkey,
+}
+
+pub fn distribution_tree_address(authority: &Pubkey, batch_id: &str) -> Pubkey {
+    Pubkey::find_program_address(
+        &[DISTRIBUTION_TREE_SEED, authority.as_ref(), batch_id.as_bytes()],
+        &cash_dispatch::ID,
+    )
+    .0
+}
+
+pub fn mint_registry_address(distribution_tree: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[MINT_REGISTRY_SEED, distribution_tree.as_ref()], &cash_dispatch::ID).0
+}
+
+fn leaf_address(seed: &[u8], distribution_tree: &Pubkey, epoch: u64, index: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            seed,
+            distribution_tree.as_ref(),
+            &epoch.to_le_bytes(),
+            &index.to_le_bytes(),
+        ],
+        &cash_dispatch::ID,
+    )
+    .0
+}
+
+fn build(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: cash_dispatch::ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+pub fn initialize(authority: &Pubkey, mint: &Pubkey, token_program: &Pubkey, params: InitializeParams) -> Instruction {
+    let distribution_tree = distribution_tree_address(authority, &params.batch_id);
+    let fees_wallet = Pubkey::from_str(FEES_WALLET).unwrap();
+    build(
+        accounts::Initialize {
+            authority: *authority,
+            distribution_tree,
+            mint: *mint,
+            token_source: get_associated_token_address_with_program_id(authority, mint, token_program),
+            token_vault: get_associated_token_address_with_program_id(&distribution_tree, mint, token_program),
+            fees_token_account: get_associated_token_address_with_program_id(&fees_wallet, mint, token_program),
+            associated_token_program: associated_token::ID,
+            token_program: *token_program,
+            system_program: system_program::ID,
+        },
+        instruction::Initialize { params },
+    )
+}
+
+pub fn expand(authority: &Pubkey, batch_id: &str) -> Instruction {
+    build(
+        accounts::ExpandDistributionTree {
+            authority: *authority,
+            distribution_tree: distribution_tree_address(authority, batch_id),
+            system_program: system_program::ID,
+        },
+        instruction::ExpandDistributionTree {
+            params: ExpandParams {
+                batch_id: batch_id.to_string(),
+            },
+        },
+    )
+}
+
+/// Distributes a standard leaf (no splits, schedule, installments or registered mint)
+pub fn distribute(
+    authority: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+    recipient: &Pubkey,
+    params: DistributeParams,
+) -> Instruction {
+    let distribution_tree = distribution_tree_address(authority, &params.batch_id);
+    build(
+        accounts::Distribute {
+            authority: *authority,
+            recipient: *recipient,
+            distribution_tree,
+            mint: *mint,
+            token_vault: get_associated_token_address_with_program_id(&distribution_tree, mint, token_program),
+            recipient_token_account: Some(get_associated_token_address_with_program_id(
+                recipient,
+                mint,
+                token_program,
+            )),
+            mint_registry: mint_registry_address(&distribution_tree),
+            leaf_redirect: leaf_address(LEAF_REDIRECT_SEED, &distribution_tree, params.epoch, params.index),
+            dispute: leaf_address(DISPUTE_SEED, &distribution_tree, params.epoch, params.index),
+            installment_record: params
+                .installments
+                .map(|_| leaf_address(INSTALLMENT_RECORD_SEED, &distribution_tree, params.epoch, params.index)),
+            associated_token_program: associated_token::ID,
+            token_program: *token_program,
+            system_program: system_program::ID,
+            gateway_token: None,
+        },
+        instruction::Distribute { params },
+    )
+}
+
+pub fn pause(authority: &Pubkey, batch_id: &str) -> Instruction {
+    build(
+        accounts::PauseResume {
+            authority: *authority,
+            distribution_tree: distribution_tree_address(authority, batch_id),
+        },
+        instruction::Pause {
+            params: PauseResumeParams {
+                batch_id: batch_id.to_string(),
+            },
+        },
+    )
+}
+
+pub fn resume(authority: &Pubkey, batch_id: &str) -> Instruction {
+    build(
+        accounts::PauseResume {
+            authority: *authority,
+            distribution_tree: distribution_tree_address(authority, batch_id),
+        },
+        instruction::Resume {
+            params: PauseResumeParams {
+                batch_id: batch_id.to_string(),
+            },
+        },
+    )
+}
+
+/// The `[mint, token_vault, authority_token_account, token_program]` remaining accounts of every
+/// registered mint, swept by `cancel` and `close` (the mint is writable so `close` can harvest
+/// withheld transfer fees before closing the vault)
+pub fn registered_vault_accounts(
+    authority: &Pubkey,
+    distribution_tree: &Pubkey,
+    registered_mints: &[RegisteredMint],
+) -> Vec<AccountMeta> {
+    registered_mints
+        .iter()
+        .flat_map(|registered| {
+            let RegisteredMint { mint, token_program } = registered;
+            [
+                AccountMeta::new(*mint, false),
+                AccountMeta::new(
+                    get_associated_token_address_with_program_id(distribution_tree, mint, token_program),
+                    false,
+                ),
+                AccountMeta::new(
+                    get_associated_token_address_with_program_id(authority, mint, token_program),
+                    false,
+                ),
+                AccountMeta::new_readonly(*token_program, false),
+            ]
+        })
+        .collect()
+}
+
+pub fn cancel(
+    authority: &Pubkey,
+    batch_id: &str,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+    registered_mints: &[RegisteredMint],
+) -> Instruction {
+    let distribution_tree = distribution_tree_address(authority, batch_id);
+    let mut instruction = build(
+        accounts::Cancel {
+            authority: *authority,
+            distribution_tree,
+            mint: *mint,
+            token_vault: get_associated_token_address_with_program_id(&distribution_tree, mint, token_program),
+            authority_token_account: get_associated_token_address_with_program_id(authority, mint, token_program),
+            mint_registry: mint_registry_address(&distribution_tree),
+            token_program: *token_program,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::Cancel {
+            params: CancelParams {
+                batch_id: batch_id.to_string(),
+            },
+        },
+    );
+    instruction
+        .accounts
+        .extend(registered_vault_accounts(authority, &distribution_tree, registered_mints));
+    instruction
+}
+
+pub fn reclaim(authority: &Pubkey, batch_id: &str) -> Instruction {
+    build(
+        accounts::Reclaim {
+            authority: *authority,
+            distribution_tree: distribution_tree_address(authority, batch_id),
+            system_program: system_program::ID,
+        },
+        instruction::Reclaim {
+            params: ReclaimParams {
+                batch_id: batch_id.to_string(),
+            },
+        },
+    )
+}
+
+/// `tree_mint` is the tree's own mint, whose vault is swept and closed (`None` for native SOL trees)
+pub fn close(
+    authority: &Pubkey,
+    batch_id: &str,
+    tree_mint: Option<RegisteredMint>,
+    registered_mints: &[RegisteredMint],
+) -> Instruction {
+    let distribution_tree = distribution_tree_address(authority, batch_id);
+    let mut instruction = build(
+        accounts::Close {
+            authority: *authority,
+            distribution_tree,
+            mint_registry: mint_registry_address(&distribution_tree),
+            mint: tree_mint.map(|tree_mint| tree_mint.mint),
+            token_vault: tree_mint.map(|RegisteredMint { mint, token_program }| {
+                get_associated_token_address_with_program_id(&distribution_tree, &mint, &token_program)
+            }),
+            authority_token_account: tree_mint.map(|RegisteredMint { mint, token_program }| {
+                get_associated_token_address_with_program_id(authority, &mint, &token_program)
+            }),
+            token_program: tree_mint.map(|tree_mint| tree_mint.token_program),
+            sol_vault: Pubkey::find_program_address(&[SOL_VAULT_SEED, distribution_tree.as_ref()], &cash_dispatch::ID).0,
+            system_program: system_program::ID,
+        },
+        instruction::Close {
+            params: CloseParams {
+                batch_id: batch_id.to_string(),
+                acknowledge_irreversible: true,
+            },
+        },
+    );
+    instruction
+        .accounts
+        .extend(registered_vault_accounts(authority, &distribution_tree, registered_mints));
+    instruction
+}
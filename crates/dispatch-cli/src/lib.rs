@@ -0,0 +1,11 @@
+//! Runs the Dispatch distribution lifecycle from the command line
+//!
+//! Instructions are built from the program's own Anchor account and argument types, and merkle
+//! roots and proofs come from `cash-dispatch-merkle`, so they always match the deployed program.
+
+pub mod client;
+pub mod commands;
+pub mod crank;
+pub mod instructions;
+pub mod output;
+pub mod recipients;
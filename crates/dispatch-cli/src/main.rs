@@ -0,0 +1,81 @@
+use std::path::PathBuf;
+
+use anyhow::{anyhow, Result};
+use clap::{Parser, Subcommand};
+use dispatch_cli::{
+    client::DispatchClient,
+    commands::{self, BatchArgs, CloseArgs, DistributeArgs, InitializeArgs},
+    crank::{self, CrankArgs},
+    output::OutputFormat,
+};
+use solana_sdk::signature::read_keypair_file;
+
+#[derive(Parser)]
+#[command(name = "dispatch", version, about = "Runs Dispatch distributions")]
+struct Cli {
+    /// RPC URL of the cluster
+    #[arg(long, short = 'u', global = true, default_value = "http://127.0.0.1:8899")]
+    url: String,
+    /// Keypair of the distribution tree authority
+    #[arg(long, short = 'k', global = true, default_value = "~/.config/solana/id.json")]
+    keypair: String,
+    /// Format of the results
+    #[arg(long, short = 'o', global = true, value_enum, default_value = "human")]
+    output: OutputFormat,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Creates and funds a distribution tree from a recipients CSV
+    Initialize(InitializeArgs),
+    /// Expands the recipients bitmap of a large tree until it is active
+    Expand(BatchArgs),
+    /// Distributes leaves of the recipients CSV
+    Distribute(DistributeArgs),
+    /// Distributes every leaf not yet marked in the recipients bitmap, resuming from a state file
+    Crank(CrankArgs),
+    /// Pauses the distribution
+    Pause(BatchArgs),
+    /// Resumes a paused distribution
+    Resume(BatchArgs),
+    /// Cancels the distribution and refunds its vaults to the authority
+    Cancel(BatchArgs),
+    /// Shrinks a completed or cancelled tree, returning its rent to the authority
+    Reclaim(BatchArgs),
+    /// Closes a completed or cancelled tree
+    Close(CloseArgs),
+    /// Shows the state of a distribution tree
+    Show(BatchArgs),
+}
+
+fn keypair_path(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), std::env::var_os("HOME")) {
+        (Some(path), Some(home)) => PathBuf::from(home).join(path),
+        _ => PathBuf::from(path),
+    }
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let keypair_path = keypair_path(&cli.keypair);
+    let authority = read_keypair_file(&keypair_path)
+        .map_err(|error| anyhow!("Failed to read keypair {}: {error}", keypair_path.display()))?;
+    let client = DispatchClient::new(cli.url, authority);
+
+    let report = match cli.command {
+        Command::Initialize(args) => commands::initialize(&client, args),
+        Command::Expand(args) => commands::expand(&client, args),
+        Command::Distribute(args) => commands::distribute(&client, args),
+        Command::Crank(args) => crank::crank(&client, args),
+        Command::Pause(args) => commands::pause(&client, args),
+        Command::Resume(args) => commands::resume(&client, args),
+        Command::Cancel(args) => commands::cancel(&client, args),
+        Command::Reclaim(args) => commands::reclaim(&client, args),
+        Command::Close(args) => commands::close(&client, args),
+        Command::Show(args) => commands::show(&client, args),
+    }?;
+    report.print(cli.output);
+    Ok(())
+}
@@ -0,0 +1,77 @@
+use cash_dispatch::state::{DistributionStatus, DistributionTree, HashAlgorithm};
+use clap::ValueEnum;
+use serde_json::{json, Map, Value};
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// One line per field
+    Human,
+    /// A single JSON object
+    Json,
+}
+
+/// Result of a command, printed as human-readable lines or a JSON object
+pub struct Report {
+    fields: Map<String, Value>,
+}
+
+impl Report {
+    pub fn new(command: &str) -> Report {
+        let mut fields = Map::new();
+        fields.insert("command".to_string(), json!(command));
+        Report { fields }
+    }
+
+    pub fn field(mut self, key: &str, value: impl Into<Value>) -> Report {
+        self.fields.insert(key.to_string(), value.into());
+        self
+    }
+
+    /// Adds the state of the distribution tree
+    pub fn tree(self, distribution_tree: &DistributionTree) -> Report {
+        self.field("status", status_name(&distribution_tree.status))
+            .field("epoch", distribution_tree.epoch)
+            .field("mint", distribution_tree.mint.to_string())
+            .field("merkle_root", hex(&distribution_tree.merkle_root))
+            .field("hash_algorithm", hash_algorithm_name(distribution_tree.hash_algorithm))
+            .field("total_number_recipients", distribution_tree.total_number_recipients)
+            .field("number_distributed", distribution_tree.number_distributed)
+    }
+
+    pub fn print(&self, format: OutputFormat) {
+        match format {
+            OutputFormat::Json => println!("{}", Value::Object(self.fields.clone())),
+            OutputFormat::Human => {
+                let width = self.fields.keys().map(String::len).max().unwrap_or(0);
+                for (key, value) in &self.fields {
+                    match value {
+                        Value::String(value) => println!("{key:width$}  {value}"),
+                        value => println!("{key:width$}  {value}"),
+                    }
+                }
+            }
+        }
+    }
+}
+
+pub fn status_name(status: &DistributionStatus) -> &'static str {
+    match status {
+        DistributionStatus::InsufficientBitmapSpace => "InsufficientBitmapSpace",
+        DistributionStatus::Active => "Active",
+        DistributionStatus::Complete => "Complete",
+        DistributionStatus::Paused => "Paused",
+        DistributionStatus::Cancelled => "Cancelled",
+    }
+}
+
+pub fn hash_algorithm_name(hash_algorithm: HashAlgorithm) -> &'static str {
+    match hash_algorithm {
+        HashAlgorithm::Keccak => "keccak",
+        HashAlgorithm::Sha256 => "sha256",
+        HashAlgorithm::Poseidon => "poseidon",
+    }
+}
+
+pub fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
@@ -0,0 +1,61 @@
+use std::{path::Path, str::FromStr};
+
+use anchor_lang::prelude::Pubkey;
+use anyhow::{bail, Context, Result};
+use cash_dispatch_merkle::PaymentRow;
+use serde::Deserialize;
+
+/// A row of the recipients CSV
+/// The index column is optional, rows without one take their position in the file
+#[derive(Deserialize)]
+struct RecipientRecord {
+    index: Option<u64>,
+    recipient: String,
+    amount: u64,
+}
+
+/// Loads the recipients CSV, with an `index,recipient,amount` or `recipient,amount` header
+pub fn load(path: &Path) -> Result<Vec<PaymentRow>> {
+    let reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_path(path)
+        .with_context(|| format!("Failed to open recipients file {}", path.display()))?;
+    parse(reader)
+}
+
+/// Parses recipients from any CSV source
+pub fn parse<R: std::io::Read>(mut reader: csv::Reader<R>) -> Result<Vec<PaymentRow>> {
+    let mut rows = Vec::new();
+    for (position, record) in reader.deserialize::<RecipientRecord>().enumerate() {
+        let line = position + 2;
+        let record = record.with_context(|| format!("Invalid recipient on line {line}"))?;
+        let recipient = Pubkey::from_str(&record.recipient)
+            .with_context(|| format!("Invalid recipient address on line {line}"))?;
+        if record.amount == 0 {
+            bail!("Recipient on line {line} has a zero amount");
+        }
+        rows.push(PaymentRow {
+            index: record.index.unwrap_or(position as u64),
+            recipient,
+            amount: record.amount,
+        });
+    }
+    if rows.is_empty() {
+        bail!("Recipients file has no rows");
+    }
+    Ok(rows)
+}
+
+/// Finds the row of a leaf index
+pub fn find(rows: &[PaymentRow], index: u64) -> Result<&PaymentRow> {
+    rows.iter()
+        .find(|row| row.index == index)
+        .with_context(|| format!("No recipient with index {index}"))
+}
+
+/// Sum of the amounts of every row
+pub fn total_amount(rows: &[PaymentRow]) -> Result<u64> {
+    rows.iter()
+        .try_fold(0u64, |total, row| total.checked_add(row.amount))
+        .context("Total amount overflows")
+}
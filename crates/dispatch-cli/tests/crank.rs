@@ -0,0 +1,69 @@
+use std::{collections::BTreeSet, fs};
+
+use anchor_lang::prelude::Pubkey;
+use cash_dispatch_merkle::PaymentRow;
+use dispatch_cli::crank::CrankState;
+
+fn rows(count: u64) -> Vec<PaymentRow> {
+    (0..count)
+        .map(|index| PaymentRow {
+            index,
+            recipient: Pubkey::new_unique(),
+            amount: (index + 1) * 1_000_000,
+        })
+        .collect()
+}
+
+fn state_path(name: &str) -> std::path::PathBuf {
+    let directory = std::env::temp_dir().join(format!("dispatch-crank-{}", std::process::id()));
+    fs::create_dir_all(&directory).unwrap();
+    directory.join(name)
+}
+
+#[test]
+fn reconcile_trusts_the_recipients_bitmap() {
+    let mut state = CrankState::new(&Pubkey::new_unique(), 0, &[1; 32]);
+    // The state recorded leaf 3, but its transaction never landed
+    state.distributed.extend([0, 3]);
+    state.failed.insert(1, "blockhash expired".to_string());
+    let on_chain = BTreeSet::from([0, 1]);
+
+    let plan = state.reconcile(&rows(5), |index| Ok(on_chain.contains(&index))).unwrap();
+    assert_eq!(plan.already_distributed, 2);
+    assert_eq!(plan.reconciled, vec![3]);
+    assert_eq!(plan.pending, vec![2, 3, 4]);
+    assert_eq!(state.distributed, BTreeSet::from([0, 1]));
+    assert!(state.failed.is_empty());
+}
+
+#[test]
+fn state_file_round_trips() {
+    let path = state_path("round-trip.json");
+    let address = Pubkey::new_unique();
+    let mut state = CrankState::new(&address, 2, &[7; 32]);
+    state.distributed.extend([4, 5]);
+    state.failed.insert(6, "InvalidProof".to_string());
+    state.save(&path).unwrap();
+
+    let loaded = CrankState::load(&path, CrankState::new(&address, 2, &[7; 32])).unwrap();
+    assert_eq!(loaded.distributed, state.distributed);
+    assert_eq!(loaded.failed, state.failed);
+}
+
+#[test]
+fn missing_state_file_starts_a_new_state() {
+    let path = state_path("missing.json");
+    let _ = fs::remove_file(&path);
+    let state = CrankState::load(&path, CrankState::new(&Pubkey::new_unique(), 0, &[0; 32])).unwrap();
+    assert!(state.distributed.is_empty());
+}
+
+#[test]
+fn state_file_of_another_epoch_is_rejected() {
+    let path = state_path("other-epoch.json");
+    let address = Pubkey::new_unique();
+    CrankState::new(&address, 0, &[7; 32]).save(&path).unwrap();
+
+    assert!(CrankState::load(&path, CrankState::new(&address, 1, &[8; 32])).is_err());
+    assert!(CrankState::load(&path, CrankState::new(&Pubkey::new_unique(), 0, &[7; 32])).is_err());
+}
@@ -0,0 +1,108 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_spl::{associated_token::get_associated_token_address_with_program_id, token};
+use dispatch_cli::instructions::{self, distribution_tree_address, RegisteredMint};
+
+const BATCH_ID: &str = "2024-01-01-ab12";
+
+#[test]
+fn distribution_tree_address_matches_the_program_seeds() {
+    let authority = Pubkey::new_unique();
+    let (expected, _) = Pubkey::find_program_address(
+        &[b"DISTRIBUTION_TREE", authority.as_ref(), BATCH_ID.as_bytes()],
+        &cash_dispatch::ID,
+    );
+    assert_eq!(distribution_tree_address(&authority, BATCH_ID), expected);
+}
+
+#[test]
+fn distribute_pays_the_recipient_token_account() {
+    let (authority, mint, recipient) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+    let instruction = instructions::distribute(
+        &authority,
+        &mint,
+        &token::ID,
+        &recipient,
+        cash_dispatch::DistributeParams {
+            amount: 100,
+            proof: vec![],
+            batch_id: BATCH_ID.to_string(),
+            index: 0,
+            epoch: 0,
+            splits: None,
+            schedule: None,
+            installments: None,
+            mint_index: None,
+        },
+    );
+    let recipient_token_account = get_associated_token_address_with_program_id(&recipient, &mint, &token::ID);
+    assert_eq!(instruction.program_id, cash_dispatch::ID);
+    assert!(instruction.accounts[0].is_signer);
+    assert!(instruction
+        .accounts
+        .iter()
+        .any(|meta| meta.pubkey == recipient_token_account && meta.is_writable));
+}
+
+#[test]
+fn cancel_and_close_sweep_every_registered_vault() {
+    let authority = Pubkey::new_unique();
+    let registered_mints = [
+        RegisteredMint {
+            mint: Pubkey::new_unique(),
+            token_program: token::ID,
+        },
+        RegisteredMint {
+            mint: Pubkey::new_unique(),
+            token_program: anchor_spl::token_2022::ID,
+        },
+    ];
+
+    let without_mints = instructions::close(&authority, BATCH_ID, None, &[]);
+    let with_mints = instructions::close(&authority, BATCH_ID, None, &registered_mints);
+    assert_eq!(with_mints.accounts.len(), without_mints.accounts.len() + 8);
+
+    let distribution_tree = distribution_tree_address(&authority, BATCH_ID);
+    let vault_accounts = &with_mints.accounts[without_mints.accounts.len()..];
+    for (registered, accounts) in registered_mints.iter().zip(vault_accounts.chunks(4)) {
+        assert_eq!(accounts[0].pubkey, registered.mint);
+        assert!(accounts[0].is_writable);
+        assert_eq!(
+            accounts[1].pubkey,
+            get_associated_token_address_with_program_id(&distribution_tree, &registered.mint, &registered.token_program)
+        );
+        assert_eq!(
+            accounts[2].pubkey,
+            get_associated_token_address_with_program_id(&authority, &registered.mint, &registered.token_program)
+        );
+        assert_eq!(accounts[3].pubkey, registered.token_program);
+    }
+
+    let cancel = instructions::cancel(&authority, BATCH_ID, &Pubkey::new_unique(), &token::ID, &registered_mints);
+    assert!(cancel.accounts.ends_with(vault_accounts));
+}
+
+#[test]
+fn close_sweeps_the_token_vault_of_token_trees() {
+    let authority = Pubkey::new_unique();
+    let tree_mint = RegisteredMint {
+        mint: Pubkey::new_unique(),
+        token_program: anchor_spl::token_2022::ID,
+    };
+    let distribution_tree = distribution_tree_address(&authority, BATCH_ID);
+    let token_vault =
+        get_associated_token_address_with_program_id(&distribution_tree, &tree_mint.mint, &tree_mint.token_program);
+    let authority_token_account =
+        get_associated_token_address_with_program_id(&authority, &tree_mint.mint, &tree_mint.token_program);
+
+    let token_tree = instructions::close(&authority, BATCH_ID, Some(tree_mint), &[]);
+    for writable in [tree_mint.mint, token_vault, authority_token_account] {
+        assert!(token_tree
+            .accounts
+            .iter()
+            .any(|meta| meta.pubkey == writable && meta.is_writable));
+    }
+
+    // Native trees omit the optional token accounts
+    let native_tree = instructions::close(&authority, BATCH_ID, None, &[]);
+    assert!(!native_tree.accounts.iter().any(|meta| meta.pubkey == token_vault));
+}
@@ -0,0 +1,44 @@
+use anchor_lang::prelude::Pubkey;
+use dispatch_cli::recipients::{find, parse, total_amount};
+
+fn reader(csv: &str) -> csv::Reader<&[u8]> {
+    csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_reader(csv.as_bytes())
+}
+
+#[test]
+fn rows_without_an_index_take_their_position() {
+    let (first, second) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let rows = parse(reader(&format!("recipient,amount\n{first},100\n{second}, 250\n"))).unwrap();
+    assert_eq!(rows.len(), 2);
+    assert_eq!((rows[1].index, rows[1].recipient, rows[1].amount), (1, second, 250));
+    assert_eq!(total_amount(&rows).unwrap(), 350);
+}
+
+#[test]
+fn rows_keep_their_index_column() {
+    let recipient = Pubkey::new_unique();
+    let rows = parse(reader(&format!("index,recipient,amount\n7,{recipient},100\n"))).unwrap();
+    assert_eq!(find(&rows, 7).unwrap().recipient, recipient);
+    assert!(find(&rows, 0).is_err());
+}
+
+#[test]
+fn invalid_rows_are_rejected() {
+    assert!(parse(reader("recipient,amount\nnot-a-pubkey,100\n")).is_err());
+    assert!(parse(reader(&format!("recipient,amount\n{},0\n", Pubkey::new_unique()))).is_err());
+    assert!(parse(reader(&format!("recipient,amount\n{},-5\n", Pubkey::new_unique()))).is_err());
+    assert!(parse(reader("recipient,amount\n")).is_err());
+}
+
+#[test]
+fn total_amount_overflow_is_rejected() {
+    let csv = format!(
+        "recipient,amount\n{},{}\n{},1\n",
+        Pubkey::new_unique(),
+        u64::MAX,
+        Pubkey::new_unique()
+    );
+    assert!(total_amount(&parse(reader(&csv)).unwrap()).is_err());
+}
@@ -4,7 +4,37 @@ use anchor_lang::prelude::*;
 pub const DISTRIBUTION_TREE_SEED: &'static [u8] = b"DISTRIBUTION_TREE";
 
 #[constant]
-pub const CURRENT_VERSION: u64 = 1;
+pub const CLAIM_RECORD_SEED: &'static [u8] = b"CLAIM_RECORD";
+
+#[constant]
+pub const LEAF_REDIRECT_SEED: &'static [u8] = b"LEAF_REDIRECT";
+
+#[constant]
+pub const DECLINE_RECORD_SEED: &'static [u8] = b"DECLINE_RECORD";
+
+#[constant]
+pub const DISPUTE_SEED: &'static [u8] = b"DISPUTE";
+
+#[constant]
+pub const DELEGATION_SEED: &'static [u8] = b"DELEGATION";
+
+#[constant]
+pub const INSTALLMENT_RECORD_SEED: &'static [u8] = b"INSTALLMENT_RECORD";
+
+#[constant]
+pub const MINT_REGISTRY_SEED: &'static [u8] = b"MINT_REGISTRY";
+
+#[constant]
+pub const SOL_VAULT_SEED: &'static [u8] = b"SOL_VAULT";
+
+#[constant]
+pub const CURRENT_VERSION: u64 = 2;
+
+/// Prefix prepended to leaf pre-images (v2+)
+pub const LEAF_HASH_PREFIX: &[u8] = &[0x00];
+
+/// Prefix prepended to internal node pre-images (v2+)
+pub const NODE_HASH_PREFIX: &[u8] = &[0x01];
 
 #[cfg(feature = "mainnet")]
 #[constant]
@@ -24,8 +54,20 @@ pub const FEES_WALLET: &'static str = "FEESqUnJ5LEZgNpChDAbq2bDf5na3HTECAZhLUBMo
 
 pub const MAX_FEE_AMOUNT: u64 = 5_000_000_000; // $5,000
 
+/// Decimals of the amounts MAX_FEE_AMOUNT and the FeeTier thresholds are expressed in (PYUSD)
+pub const FEE_REFERENCE_DECIMALS: u8 = 6;
+
 pub const BATCH_ID_MINIMUM_LENGTH: usize = 8;
 
 pub const BATCH_ID_MAXIMUM_LENGTH: usize = 15;
 
-pub const BITMAP_ARRAY_STEP: usize = 1000;
\ No newline at end of file
+pub const BITMAP_ARRAY_STEP: usize = 1000;
+
+/// Maximum number of destinations a split leaf can commit to
+pub const MAX_PAYOUT_SPLITS: usize = 8;
+
+/// The bps of a split leaf's destinations must add up to 100%
+pub const TOTAL_SPLIT_BPS: u16 = 10_000;
+
+/// Maximum number of mints a DistributionTree can register in addition to its own mint
+pub const MAX_ADDITIONAL_MINTS: usize = 4;
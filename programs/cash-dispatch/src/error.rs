@@ -54,5 +54,87 @@ pub enum DistributionError {
     MissingGatekeeperNetwork,
     #[msg("Must acknowledge that closing the Distribution Tree is irreversible")]
     MustAcknowledgeIrreversible,
+    #[msg("Invalid hash input")]
+    InvalidHashInput,
+    #[msg("Unsupported Distribution Tree version, migrate the Distribution Tree first")]
+    UnsupportedVersion,
+    #[msg("Distribution Tree is already on the current version")]
+    AlreadyCurrentVersion,
+    #[msg("Epoch does not match the current epoch")]
+    InvalidEpoch,
+    #[msg("Current epoch is still in progress")]
+    EpochInProgress,
+    #[msg("Invalid shares")]
+    InvalidShares,
+    #[msg("Open-enrollment trees require a claim amount, claims, a gatekeeper network and no merkle root or shares")]
+    InvalidOpenEnrollment,
+    #[msg("Distribution Tree is not open for enrollment")]
+    NotOpenEnrollment,
+    #[msg("Open-enrollment Distribution Trees must be claimed with claim_open")]
+    OpenEnrollmentTree,
+    #[msg("Leaf has been redirected, pay the redirect destination")]
+    PayeeNotRedirectDestination,
+    #[msg("Invalid redirect destination")]
+    InvalidRedirectDestination,
+    #[msg("Invalid decline destination")]
+    InvalidDeclineDestination,
+    #[msg("Leaf is frozen by an open dispute")]
+    LeafDisputed,
+    #[msg("Dispute is not open")]
+    DisputeNotOpen,
+    #[msg("Disputes are only supported on fixed amount merkle trees")]
+    DisputesNotSupported,
+    #[msg("Invalid delegate")]
+    InvalidDelegate,
+    #[msg("Payee must be the claimant, or the payee of the claimant's delegation")]
+    InvalidPayee,
+    #[msg("Invalid destination token account")]
+    InvalidDestinationTokenAccount,
+    #[msg("Payout splits must have 1 to 8 destinations whose bps add up to 10,000")]
+    InvalidPayoutSplits,
+    #[msg("Missing or invalid token account for a payout split")]
+    InvalidSplitTokenAccount,
+    #[msg("Leaf has not unlocked yet")]
+    LeafLocked,
+    #[msg("Leaf has expired")]
+    LeafExpired,
+    #[msg("Installment plans must have a non-zero amount, count and interval adding up to the leaf amount")]
+    InvalidInstallmentPlan,
+    #[msg("Installments are only supported on fixed amount merkle trees")]
+    InstallmentsNotSupported,
+    #[msg("Installment leaves require their InstallmentRecord account")]
+    MissingInstallmentRecord,
+    #[msg("No installments are due")]
+    NoInstallmentsDue,
+    #[msg("Invalid mint index")]
+    InvalidMintIndex,
+    #[msg("Mint is already paid by this distribution tree")]
+    MintAlreadyRegistered,
+    #[msg("A distribution tree can register at most 4 additional mints")]
+    TooManyMints,
+    #[msg("Additional mints are only supported on fixed amount merkle trees")]
+    MultiMintNotSupported,
+    #[msg("Missing or invalid accounts to sweep the vault of a registered mint")]
+    InvalidMintVaultAccounts,
+    #[msg("Native SOL distribution trees must use the native instructions")]
+    NativeTree,
+    #[msg("Distribution tree does not distribute native SOL")]
+    NotNativeTree,
+    #[msg("SOL vault balance would fall below the rent-exempt minimum")]
+    InsufficientVaultBalance,
+    #[msg("Recipient would not be rent-exempt after the payout")]
+    RecipientNotRentExempt,
+    #[msg("Token vault did not receive the expected amount")]
+    VaultUnderfunded,
+    #[msg("Mint has a permanent delegate, default-frozen accounts or a freeze authority that was not acknowledged")]
+    UnacknowledgedMintRisks,
+    #[msg("Mint decimals do not match the mint account")]
+    InvalidMintDecimals,
+    #[msg("Leaf extension is not supported by this instruction")]
+    UnsupportedLeafExtension,
+    #[msg("Last installment is due after the distribution ends or the leaf expires")]
+    InstallmentsPastEnd,
+    #[msg("Token trees must pass their mint, token vault, the authority's token account and the token program")]
+    InvalidTokenVaultAccounts,
 }
 
@@ -0,0 +1,105 @@
+use anchor_lang::prelude::*;
+
+/// Emitted whenever a merkle root is posted for an epoch (including epoch 0 at initialize)
+/// Indexers can rebuild the root history of a tree from these events
+#[event]
+pub struct EpochStarted {
+    pub distribution_tree: Pubkey,
+    pub batch_id: String,
+    pub epoch: u64,
+    pub merkle_root: [u8; 32],
+    pub total_number_recipients: u64,
+    pub funded_amount: u64,
+    pub start_ts: i64,
+    pub end_ts: i64,
+}
+
+/// Emitted when the payout of an unpaid leaf is redirected to a new destination
+#[event]
+pub struct LeafRedirected {
+    pub distribution_tree: Pubkey,
+    pub epoch: u64,
+    pub index: u64,
+    pub original_recipient: Pubkey,
+    pub destination: Pubkey,
+    pub signer: Pubkey,
+}
+
+/// Emitted when the authority revokes an unpaid leaf
+#[event]
+pub struct LeafRevoked {
+    pub distribution_tree: Pubkey,
+    pub epoch: u64,
+    pub index: u64,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub clawback_amount: u64,
+}
+
+/// Emitted when a recipient declines their allocation
+#[event]
+pub struct LeafDeclined {
+    pub distribution_tree: Pubkey,
+    pub epoch: u64,
+    pub index: u64,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub destination: Option<Pubkey>,
+    pub forwarded_amount: u64,
+}
+
+/// Emitted when a recipient disputes the amount committed in their leaf
+#[event]
+pub struct DisputeOpened {
+    pub distribution_tree: Pubkey,
+    pub epoch: u64,
+    pub index: u64,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub reason_hash: [u8; 32],
+}
+
+/// Emitted when the authority resolves or rejects a dispute
+#[event]
+pub struct DisputeClosed {
+    pub distribution_tree: Pubkey,
+    pub epoch: u64,
+    pub index: u64,
+    pub resolved: bool,
+    pub resolved_amount: u64,
+    pub resolution_hash: [u8; 32],
+}
+
+/// Emitted when a delegate is allowed to claim a leaf on behalf of its recipient
+#[event]
+pub struct ClaimDelegated {
+    pub distribution_tree: Pubkey,
+    pub epoch: u64,
+    pub index: u64,
+    pub recipient: Pubkey,
+    pub delegate: Pubkey,
+    pub payee: Pubkey,
+    pub signer: Pubkey,
+}
+
+/// Emitted when the due installments of an installment leaf are paid
+#[event]
+pub struct InstallmentsPaid {
+    pub distribution_tree: Pubkey,
+    pub epoch: u64,
+    pub index: u64,
+    pub installments: u16,
+    pub installments_paid: u16,
+    pub count: u16,
+    pub amount: u64,
+}
+
+/// Emitted when a DistributionTree registers an additional mint
+#[event]
+pub struct MintAdded {
+    pub distribution_tree: Pubkey,
+    pub mint: Pubkey,
+    pub mint_index: u8,
+    pub token_vault: Pubkey,
+    pub amount: u64,
+}
@@ -0,0 +1,206 @@
+use std::str::FromStr;
+
+use crate::{
+    constants::{CURRENT_VERSION, DISTRIBUTION_TREE_SEED, FEES_WALLET, MAX_ADDITIONAL_MINTS, MINT_REGISTRY_SEED},
+    error::DistributionError,
+    events::MintAdded,
+    state::{DistributionStatus, DistributionTree, MintRegistry, MintRisks},
+    utils::{calculate_fee, gross_up_transfer_fee, require_vault_received, transfer_checked_with_hook},
+};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+#[derive(Accounts)]
+#[instruction(params: AddMintParams)]
+pub struct AddMint<'info> {
+    /// Authority of the DistributionTree
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// DistributionTree account
+    #[account(
+        seeds = [
+            DISTRIBUTION_TREE_SEED.as_ref(),
+            distribution_tree.authority.as_ref(),
+            params.batch_id.as_bytes(),
+        ],
+        bump = distribution_tree.bump,
+        constraint = distribution_tree.version == CURRENT_VERSION @ DistributionError::UnsupportedVersion,
+        has_one = authority @ DistributionError::SignerNotAuthorized,
+    )]
+    pub distribution_tree: Account<'info, DistributionTree>,
+
+    /// MintRegistry account
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + MintRegistry::INIT_SPACE,
+        seeds = [
+            MINT_REGISTRY_SEED.as_ref(),
+            distribution_tree.key().as_ref(),
+        ],
+        bump
+    )]
+    pub mint_registry: Account<'info, MintRegistry>,
+
+    /// The additional mint
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// Authority's token account of the additional mint
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = authority,
+        associated_token::token_program = token_program
+    )]
+    pub token_source: InterfaceAccount<'info, TokenAccount>,
+
+    /// Token Vault account of the additional mint
+    #[account(
+        init,
+        payer = authority,
+        associated_token::mint = mint,
+        associated_token::authority = distribution_tree,
+        associated_token::token_program = token_program
+    )]
+    pub token_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// Fees Wallet Token Account of the additional mint
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = Pubkey::from_str(FEES_WALLET).unwrap(),
+        associated_token::token_program = token_program
+    )]
+    pub fees_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// System & Token programs
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct AddMintParams {
+    pub batch_id: String,
+    /// Amount of the additional mint transferred to its vault
+    pub transfer_to_vault_amount: u64,
+    /// Acknowledges that the mint has a permanent delegate, default-frozen accounts or a freeze
+    /// authority, any of which can drain or lock its vault
+    pub acknowledge_mint_risks: bool,
+}
+
+impl<'info> AddMint<'info> {
+    fn transfer_to_vault(&self, amount: u64, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
+        transfer_checked_with_hook(
+            &self.token_program.to_account_info(),
+            &self.token_source.to_account_info(),
+            &self.mint.to_account_info(),
+            &self.token_vault.to_account_info(),
+            &self.authority.to_account_info(),
+            remaining_accounts,
+            amount,
+            self.mint.decimals,
+            &[],
+        )
+    }
+
+    fn pay_fees(&self, amount: u64, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
+        transfer_checked_with_hook(
+            &self.token_program.to_account_info(),
+            &self.token_source.to_account_info(),
+            &self.mint.to_account_info(),
+            &self.fees_token_account.to_account_info(),
+            &self.authority.to_account_info(),
+            remaining_accounts,
+            amount,
+            self.mint.decimals,
+            &[],
+        )
+    }
+}
+
+/// Validates the mint parameters
+///     1. The distribution has not been completed or cancelled
+///     2. The distribution is a fixed amount merkle tree (not open-enrollment, pro-rata or native SOL)
+///     3. The mint is not the tree's own mint and has not been registered
+///     4. Fewer than MAX_ADDITIONAL_MINTS mints have been registered
+///     5. The mint's risks (if any) are acknowledged
+pub fn validate(ctx: &Context<AddMint>, params: &AddMintParams) -> Result<()> {
+    let distribution_tree = &ctx.accounts.distribution_tree;
+    let mint = ctx.accounts.mint.key();
+    require!(
+        !matches!(
+            distribution_tree.status,
+            DistributionStatus::Complete | DistributionStatus::Cancelled
+        ),
+        DistributionError::InvalidDistributionStatus
+    );
+    require!(
+        !distribution_tree.is_open_enrollment()
+            && distribution_tree.total_shares.is_none()
+            && !distribution_tree.is_native(),
+        DistributionError::MultiMintNotSupported
+    );
+    require!(
+        mint != distribution_tree.mint && !ctx.accounts.mint_registry.mints.contains(&mint),
+        DistributionError::MintAlreadyRegistered
+    );
+    require_gt!(
+        MAX_ADDITIONAL_MINTS,
+        ctx.accounts.mint_registry.mints.len(),
+        DistributionError::TooManyMints
+    );
+    let mint_risks = MintRisks::inspect(&ctx.accounts.mint.to_account_info())?;
+    require!(
+        !mint_risks.any() || params.acknowledge_mint_risks,
+        DistributionError::UnacknowledgedMintRisks
+    );
+    Ok(())
+}
+
+/// Registers an additional mint
+///     1. Appends the mint and its acknowledged risks to the MintRegistry, its mint index is its position + 1
+///     2. Transfers the funding to the mint's vault, grossed up by the mint's transfer fee (if any),
+///        and checks the vault received transfer_to_vault_amount
+///     3. Pays fees on the funding
+pub fn handler<'info>(ctx: Context<'_, '_, 'info, 'info, AddMint<'info>>, params: AddMintParams) -> Result<()> {
+    let distribution_tree = ctx.accounts.distribution_tree.key();
+    let mint = ctx.accounts.mint.key();
+    let mint_risks = MintRisks::inspect(&ctx.accounts.mint.to_account_info())?;
+
+    let mint_registry = &mut ctx.accounts.mint_registry;
+    mint_registry.bump = ctx.bumps.mint_registry;
+    mint_registry.distribution_tree = distribution_tree;
+    mint_registry.mints.push(mint);
+    mint_registry.mint_risks.push(mint_risks);
+    let mint_index = mint_registry.mints.len() as u8;
+
+    if params.transfer_to_vault_amount > 0 {
+        let transfer_amount = gross_up_transfer_fee(
+            &ctx.accounts.mint.to_account_info(),
+            params.transfer_to_vault_amount,
+        )?;
+        ctx.accounts.transfer_to_vault(transfer_amount, ctx.remaining_accounts)?;
+        require_vault_received(&mut ctx.accounts.token_vault, 0, params.transfer_to_vault_amount)?;
+
+        let fee_amount = calculate_fee(params.transfer_to_vault_amount, ctx.accounts.mint.decimals)?;
+
+        if fee_amount > 0 {
+            ctx.accounts.pay_fees(fee_amount, ctx.remaining_accounts)?;
+        }
+    }
+
+    emit!(MintAdded {
+        distribution_tree,
+        mint,
+        mint_index,
+        token_vault: ctx.accounts.token_vault.key(),
+        amount: params.transfer_to_vault_amount,
+    });
+
+    Ok(())
+}
@@ -1,10 +1,11 @@
-use crate::constants::DISTRIBUTION_TREE_SEED;
+use crate::constants::{CURRENT_VERSION, DISTRIBUTION_TREE_SEED, MINT_REGISTRY_SEED};
 use crate::error::DistributionError;
-use crate::state::{DistributionStatus, DistributionTree};
+use crate::state::{DistributionStatus, DistributionTree, MintRegistry};
+use crate::utils::{sweep_registered_vaults, transfer_checked_with_hook};
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
+    token_interface::{Mint, TokenAccount, TokenInterface},
 };
 
 #[derive(Accounts)]
@@ -26,7 +27,8 @@ pub struct Cancel<'info> {
             distribution_tree.authority.as_ref(),
             params.batch_id.as_bytes()
         ],
-        bump = distribution_tree.bump
+        bump = distribution_tree.bump,
+        constraint = distribution_tree.version == CURRENT_VERSION @ DistributionError::UnsupportedVersion
     )]
     pub distribution_tree: Account<'info, DistributionTree>,
 
@@ -53,6 +55,17 @@ pub struct Cancel<'info> {
     )]
     pub authority_token_account: InterfaceAccount<'info, TokenAccount>,
 
+    /// MintRegistry PDA of the tree (uninitialized unless additional mints have been registered)
+    /// CHECK: Address verified by seeds, loaded in the handler when initialized
+    #[account(
+        seeds = [
+            MINT_REGISTRY_SEED.as_ref(),
+            distribution_tree.key().as_ref(),
+        ],
+        bump
+    )]
+    pub mint_registry: UncheckedAccount<'info>,
+
     pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
@@ -64,22 +77,19 @@ pub struct CancelParams {
 }
 
 impl<'info> Cancel<'info> {
-    fn transfer_to_authority(&self, amount: u64) -> Result<()> {
+    fn transfer_to_authority(&self, amount: u64, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
         let signer_seeds = &[&self.distribution_tree.signer_seeds()[..]];
 
-        transfer_checked(
-            CpiContext::new_with_signer(
-                self.token_program.to_account_info(),
-                TransferChecked {
-                    from: self.token_vault.to_account_info(),
-                    mint: self.mint.to_account_info(),
-                    to: self.authority_token_account.to_account_info(),
-                    authority: self.distribution_tree.to_account_info(),
-                },
-                signer_seeds,
-            ),
+        transfer_checked_with_hook(
+            &self.token_program.to_account_info(),
+            &self.token_vault.to_account_info(),
+            &self.mint.to_account_info(),
+            &self.authority_token_account.to_account_info(),
+            &self.distribution_tree.to_account_info(),
+            remaining_accounts,
             amount,
             self.mint.decimals,
+            signer_seeds,
         )
     }
 }
@@ -93,9 +103,25 @@ pub fn validate(ctx: &Context<Cancel>, _params: &CancelParams) -> Result<()> {
     Ok(())
 }
 
-pub fn handler(ctx: Context<Cancel>, _params: CancelParams) -> Result<()> {
+/// Cancels the distribution
+///     1. Refunds the token vault to the authority
+///     2. Refunds the vault of every registered mint, passed in the remaining accounts as
+///        `[mint, token_vault, authority_token_account, token_program]` per mint
+/// Remaining accounts after the registered vaults are forwarded to the mints' transfer hooks
+pub fn handler<'info>(ctx: Context<'_, '_, 'info, 'info, Cancel<'info>>, _params: CancelParams) -> Result<()> {
     let refund_amount = ctx.accounts.token_vault.amount;
-    ctx.accounts.transfer_to_authority(refund_amount)?;
+    ctx.accounts.transfer_to_authority(refund_amount, ctx.remaining_accounts)?;
+
+    if let Some(mint_registry) = MintRegistry::load(&ctx.accounts.mint_registry.to_account_info())? {
+        let signer_seeds = &[&ctx.accounts.distribution_tree.signer_seeds()[..]];
+        sweep_registered_vaults(
+            &ctx.accounts.distribution_tree.to_account_info(),
+            ctx.accounts.authority.key(),
+            &mint_registry,
+            ctx.remaining_accounts,
+            signer_seeds,
+        )?;
+    }
 
     let distribution_tree: &mut Account<'_, DistributionTree> = &mut ctx.accounts.distribution_tree;
     distribution_tree.cancel();
@@ -0,0 +1,73 @@
+use crate::constants::{CURRENT_VERSION, DISTRIBUTION_TREE_SEED, SOL_VAULT_SEED};
+use crate::error::DistributionError;
+use crate::state::{DistributionStatus, DistributionTree};
+use crate::utils::transfer_from_sol_vault;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(params: CancelNativeParams)]
+pub struct CancelNative<'info> {
+    /// The authority of the DistributionTree
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// The native SOL DistributionTree to be cancelled
+    #[account(
+        mut,
+        has_one = authority @ DistributionError::SignerNotAuthorized,
+        constraint = distribution_tree.is_native() @ DistributionError::NotNativeTree,
+        constraint = distribution_tree.status == DistributionStatus::Active @ DistributionError::DistributionNotActive,
+        seeds = [
+            DISTRIBUTION_TREE_SEED.as_ref(),
+            distribution_tree.authority.as_ref(),
+            params.batch_id.as_bytes()
+        ],
+        bump = distribution_tree.bump,
+        constraint = distribution_tree.version == CURRENT_VERSION @ DistributionError::UnsupportedVersion
+    )]
+    pub distribution_tree: Account<'info, DistributionTree>,
+
+    /// SOL Vault of the DistributionTree
+    #[account(
+        mut,
+        seeds = [
+            SOL_VAULT_SEED.as_ref(),
+            distribution_tree.key().as_ref(),
+        ],
+        bump
+    )]
+    pub sol_vault: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct CancelNativeParams {
+    pub batch_id: String,
+}
+
+/// Cancels the native SOL distribution
+///     1. Refunds every lamport of the SOL vault (including its rent reserve) to the authority
+///     2. Marks the distribution as cancelled
+pub fn handler(ctx: Context<CancelNative>, _params: CancelNativeParams) -> Result<()> {
+    let refund_amount = ctx.accounts.sol_vault.lamports();
+    transfer_from_sol_vault(
+        &ctx.accounts.system_program.to_account_info(),
+        &ctx.accounts.sol_vault.to_account_info(),
+        &ctx.accounts.authority.to_account_info(),
+        &ctx.accounts.distribution_tree.key(),
+        ctx.bumps.sol_vault,
+        refund_amount,
+    )?;
+
+    let distribution_tree = &mut ctx.accounts.distribution_tree;
+    distribution_tree.cancel();
+
+    msg!(
+        "Native SOL distribution cancelled for batch ID: {} after {} distributions.",
+        distribution_tree.batch_id,
+        distribution_tree.number_distributed
+    );
+
+    Ok(())
+}
@@ -0,0 +1,258 @@
+use crate::{
+    constants::{CURRENT_VERSION, DISPUTE_SEED, DISTRIBUTION_TREE_SEED, LEAF_REDIRECT_SEED, MINT_REGISTRY_SEED},
+    error::DistributionError,
+    state::{Dispute, DistributionTree, InstallmentPlan, LeafExtensions, LeafRedirect, LeafSchedule, MintRegistry, PayoutSplit},
+    utils::{check_gateway_token, transfer_checked_with_hook},
+    DistributionStatus,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+#[derive(Accounts)]
+#[instruction(params: ClaimLinkParams)]
+pub struct ClaimLink<'info> {
+    /// Recipient's wallet, pays for the transaction and the destination token account
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// Ephemeral keypair committed in the leaf and shared in the claim link
+    /// Its signature authorizes the redemption without revealing the link secret on-chain
+    pub link_signer: Signer<'info>,
+
+    /// DistributionTree account
+    #[account(
+        mut,
+        seeds = [
+            DISTRIBUTION_TREE_SEED.as_ref(),
+            distribution_tree.authority.as_ref(),
+            params.batch_id.as_bytes(),
+        ],
+        bump = distribution_tree.bump,
+        constraint = distribution_tree.version == CURRENT_VERSION @ DistributionError::UnsupportedVersion,
+    )]
+    pub distribution_tree: Account<'info, DistributionTree>,
+
+    /// Mint account (PYUSD, or the registered mint committed in the leaf)
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// Token Vault account of the mint
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = distribution_tree,
+        associated_token::token_program = token_program
+    )]
+    pub token_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// Destination wallet chosen by the link holder
+    /// CHECK: Any wallet may receive the funds, the link_signer signature binds it to this transaction
+    pub destination: UncheckedAccount<'info>,
+
+    /// Destination's token account
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = mint,
+        associated_token::authority = destination,
+        associated_token::token_program = token_program
+    )]
+    pub destination_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// MintRegistry PDA of the tree (uninitialized unless additional mints have been registered)
+    /// CHECK: Address verified by seeds, loaded in validate when initialized
+    #[account(
+        seeds = [
+            MINT_REGISTRY_SEED.as_ref(),
+            distribution_tree.key().as_ref(),
+        ],
+        bump
+    )]
+    pub mint_registry: UncheckedAccount<'info>,
+
+    /// LeafRedirect PDA of the leaf (uninitialized unless the leaf has been redirected)
+    /// CHECK: Address verified by seeds, loaded in validate when initialized
+    #[account(
+        seeds = [
+            LEAF_REDIRECT_SEED.as_ref(),
+            distribution_tree.key().as_ref(),
+            params.epoch.to_le_bytes().as_ref(),
+            params.index.to_le_bytes().as_ref(),
+        ],
+        bump
+    )]
+    pub leaf_redirect: UncheckedAccount<'info>,
+
+    /// Dispute PDA of the leaf (uninitialized unless the leaf has been disputed)
+    /// CHECK: Address verified by seeds, checked in validate when initialized
+    #[account(
+        seeds = [
+            DISPUTE_SEED.as_ref(),
+            distribution_tree.key().as_ref(),
+            params.epoch.to_le_bytes().as_ref(),
+            params.index.to_le_bytes().as_ref(),
+        ],
+        bump
+    )]
+    pub dispute: UncheckedAccount<'info>,
+
+    /// System & Token programs
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+
+    /// Optional Civic Pass (issued to the destination)
+    /// CHECK: Verified by the solana-gateway program
+    pub gateway_token: Option<UncheckedAccount<'info>>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct ClaimLinkParams {
+    /// Amount committed in the leaf (shares for pro-rata trees)
+    pub amount: u64,
+    pub proof: Vec<[u8; 32]>,
+    pub batch_id: String,
+    pub index: u64,
+    pub epoch: u64,
+    /// (optional) Destinations committed in a split leaf (not supported, split leaves are paid with `claim`)
+    pub splits: Option<Vec<PayoutSplit>>,
+    /// (optional) Unlock and expiry timestamps committed in a scheduled leaf
+    pub schedule: Option<LeafSchedule>,
+    /// (optional) Installment plan committed in an installment leaf (not supported, a claim link is redeemed once)
+    pub installments: Option<InstallmentPlan>,
+    /// (optional) Index of the registered mint committed in the leaf (the tree's mint when omitted)
+    pub mint_index: Option<u8>,
+}
+
+impl<'info> ClaimLink<'info> {
+    fn transfer_to_destination(&self, amount: u64, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
+        let signer_seeds = &[&self.distribution_tree.signer_seeds()[..]];
+
+        transfer_checked_with_hook(
+            &self.token_program.to_account_info(),
+            &self.token_vault.to_account_info(),
+            &self.mint.to_account_info(),
+            &self.destination_token_account.to_account_info(),
+            &self.distribution_tree.to_account_info(),
+            remaining_accounts,
+            amount,
+            self.mint.decimals,
+            signer_seeds,
+        )
+    }
+}
+
+/// Validates the claim link parameters
+///     1. The distribution has started
+///     2. The distribution has not ended
+///     3. The distribution is active
+///     4. The epoch is the current epoch
+///     5. The distribution is not open-enrollment
+///     6. The leaf is not frozen by an open dispute
+///     7. The destination is the redirect destination when the leaf has been redirected
+///     8. The leaf is not a split or installment leaf
+///     9. The leaf has unlocked and has not expired (scheduled leaves)
+///     10. The mint is the tree's mint, or the registered mint at the leaf's mint index (fixed amount trees only)
+///     11. The proof is valid for the link_signer
+pub fn validate(ctx: &Context<ClaimLink>, params: &ClaimLinkParams) -> Result<()> {
+    let distribution_tree = &ctx.accounts.distribution_tree;
+    let current_ts = Clock::get()?.unix_timestamp;
+    require_gte!(
+        current_ts,
+        distribution_tree.start_ts,
+        DistributionError::DistributionNotStarted
+    );
+    require_gte!(
+        distribution_tree.end_ts,
+        current_ts,
+        DistributionError::DistributionEnded
+    );
+    require!(
+        distribution_tree.status == DistributionStatus::Active,
+        DistributionError::DistributionNotActive
+    );
+    require_eq!(
+        params.epoch,
+        distribution_tree.epoch,
+        DistributionError::InvalidEpoch
+    );
+    require!(
+        !distribution_tree.is_open_enrollment(),
+        DistributionError::OpenEnrollmentTree
+    );
+    require!(
+        !distribution_tree.is_claimed(params.index)?,
+        DistributionError::AlreadyClaimed
+    );
+    require!(
+        distribution_tree.allow_claims,
+        DistributionError::ClaimsNotAllowed
+    );
+
+    Dispute::require_not_open(&ctx.accounts.dispute.to_account_info())?;
+
+    LeafRedirect::require_redirect_destination(
+        &ctx.accounts.leaf_redirect.to_account_info(),
+        ctx.accounts.destination.key(),
+    )?;
+
+    require!(
+        params.splits.is_none() && params.installments.is_none(),
+        DistributionError::UnsupportedLeafExtension
+    );
+    if let Some(schedule) = &params.schedule {
+        schedule.validate(current_ts)?;
+    }
+
+    MintRegistry::require_leaf_mint(
+        &ctx.accounts.mint_registry.to_account_info(),
+        distribution_tree,
+        ctx.accounts.mint.key(),
+        params.mint_index,
+    )?;
+
+    distribution_tree.verify_extended_proof(
+        ctx.accounts.link_signer.key(),
+        params.amount,
+        LeafExtensions {
+            splits: params.splits.as_deref(),
+            schedule: params.schedule.as_ref(),
+            installments: params.installments.as_ref(),
+            mint_index: params.mint_index,
+        },
+        &params.proof,
+        params.index,
+    )?;
+
+    if let Some(gatekeeper_network) = distribution_tree.gatekeeper_network {
+        check_gateway_token(
+            ctx.accounts.gateway_token.as_ref().map(|token| token.to_account_info()).as_ref(),
+            &ctx.accounts.destination.key(),
+            &gatekeeper_network,
+            None,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Redeems a claim link to the destination
+///     1. Increments the total number distributed
+///     2. Calculates the payout (the leaf amount, or its pro-rata share of the vault)
+///     3. Transfers the tokens to the destination (fixed amount payouts are grossed up by the
+///        mint's transfer fee, if any)
+pub fn handler<'info>(ctx: Context<'_, '_, 'info, 'info, ClaimLink<'info>>, params: ClaimLinkParams) -> Result<()> {
+    let distribution_tree = &mut ctx.accounts.distribution_tree;
+
+    distribution_tree.increment_number_distributed()?;
+
+    distribution_tree.set_claimed(params.index)?;
+
+    let payout = distribution_tree.settle_payout(params.amount, ctx.accounts.token_vault.amount)?;
+
+    let transfer_amount = distribution_tree.payout_transfer_amount(&ctx.accounts.mint.to_account_info(), payout)?;
+    ctx.accounts.transfer_to_destination(transfer_amount, ctx.remaining_accounts)?;
+    Ok(())
+}
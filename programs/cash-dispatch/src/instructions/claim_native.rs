@@ -0,0 +1,169 @@
+use crate::{
+    constants::{CURRENT_VERSION, DISPUTE_SEED, DISTRIBUTION_TREE_SEED, LEAF_REDIRECT_SEED, SOL_VAULT_SEED},
+    error::DistributionError,
+    state::{Dispute, DistributionTree, LeafRedirect},
+    utils::{check_gateway_token, require_native_payout_rent_exempt, transfer_from_sol_vault},
+    DistributionStatus,
+};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(params: ClaimNativeParams)]
+pub struct ClaimNative<'info> {
+    /// Claimant of the distribution
+    #[account(mut)]
+    pub claimant: Signer<'info>,
+
+    /// DistributionTree account
+    #[account(
+        mut,
+        seeds = [
+            DISTRIBUTION_TREE_SEED.as_ref(),
+            distribution_tree.authority.as_ref(),
+            params.batch_id.as_bytes(),
+        ],
+        bump = distribution_tree.bump,
+        constraint = distribution_tree.version == CURRENT_VERSION @ DistributionError::UnsupportedVersion,
+        constraint = distribution_tree.is_native() @ DistributionError::NotNativeTree,
+    )]
+    pub distribution_tree: Account<'info, DistributionTree>,
+
+    /// SOL Vault of the DistributionTree
+    #[account(
+        mut,
+        seeds = [
+            SOL_VAULT_SEED.as_ref(),
+            distribution_tree.key().as_ref(),
+        ],
+        bump
+    )]
+    pub sol_vault: SystemAccount<'info>,
+
+    /// LeafRedirect PDA of the leaf (uninitialized unless the leaf has been redirected)
+    /// CHECK: Address verified by seeds, loaded in validate when initialized
+    #[account(
+        seeds = [
+            LEAF_REDIRECT_SEED.as_ref(),
+            distribution_tree.key().as_ref(),
+            params.epoch.to_le_bytes().as_ref(),
+            params.index.to_le_bytes().as_ref(),
+        ],
+        bump
+    )]
+    pub leaf_redirect: UncheckedAccount<'info>,
+
+    /// Dispute PDA of the leaf (uninitialized unless the leaf has been disputed)
+    /// CHECK: Address verified by seeds, checked in validate when initialized
+    #[account(
+        seeds = [
+            DISPUTE_SEED.as_ref(),
+            distribution_tree.key().as_ref(),
+            params.epoch.to_le_bytes().as_ref(),
+            params.index.to_le_bytes().as_ref(),
+        ],
+        bump
+    )]
+    pub dispute: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    /// Optional Civic Pass
+    /// CHECK: Verified by the solana-gateway program
+    pub gateway_token: Option<UncheckedAccount<'info>>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct ClaimNativeParams {
+    /// Lamports committed in the leaf
+    pub amount: u64,
+    pub proof: Vec<[u8; 32]>,
+    pub batch_id: String,
+    pub index: u64,
+    pub epoch: u64,
+}
+
+/// Validates the distribution parameters
+///     1. The distribution has started
+///     2. The distribution has not ended
+///     3. The distribution is active
+///     4. The epoch is the current epoch
+///     5. The leaf has not been claimed
+///     6. Claims are allowed
+///     7. The leaf is not frozen by an open dispute
+///     8. The SOL vault and the claimant remain rent-exempt after the payout
+///     9. The proof is valid (for the original key when the leaf has been redirected to the claimant)
+pub fn validate(ctx: &Context<ClaimNative>, params: &ClaimNativeParams) -> Result<()> {
+    let distribution_tree = &ctx.accounts.distribution_tree;
+    let current_ts = Clock::get()?.unix_timestamp;
+    require_gte!(
+        current_ts,
+        distribution_tree.start_ts,
+        DistributionError::DistributionNotStarted
+    );
+    require_gte!(
+        distribution_tree.end_ts,
+        current_ts,
+        DistributionError::DistributionEnded
+    );
+    require!(
+        distribution_tree.status == DistributionStatus::Active,
+        DistributionError::DistributionNotActive
+    );
+    require_eq!(
+        params.epoch,
+        distribution_tree.epoch,
+        DistributionError::InvalidEpoch
+    );
+    require!(
+        !distribution_tree.is_claimed(params.index)?,
+        DistributionError::AlreadyClaimed
+    );
+    require!(
+        distribution_tree.allow_claims,
+        DistributionError::ClaimsNotAllowed
+    );
+
+    Dispute::require_not_open(&ctx.accounts.dispute.to_account_info())?;
+
+    require_native_payout_rent_exempt(
+        &ctx.accounts.sol_vault.to_account_info(),
+        &ctx.accounts.claimant.to_account_info(),
+        params.amount,
+    )?;
+
+    let leaf_recipient = LeafRedirect::resolve_leaf_recipient(
+        &ctx.accounts.leaf_redirect.to_account_info(),
+        ctx.accounts.claimant.key(),
+    )?;
+    distribution_tree.verify_proof(leaf_recipient, params.amount, &params.proof, params.index)?;
+
+    if distribution_tree.gatekeeper_network.is_some() {
+        check_gateway_token(
+            ctx.accounts.gateway_token.as_ref().map(|token| token.to_account_info()).as_ref(),
+            &ctx.accounts.claimant.key(),
+            &distribution_tree.gatekeeper_network.ok_or(DistributionError::MissingGatekeeperNetwork)?,
+            None,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Claims lamports for the claimant
+///     1. Increments the number of recipients distributed
+///     2. Marks the leaf as claimed
+///     3. Transfers the lamports from the SOL vault to the claimant
+pub fn handler(ctx: Context<ClaimNative>, params: ClaimNativeParams) -> Result<()> {
+    let distribution_tree = &mut ctx.accounts.distribution_tree;
+    distribution_tree.increment_number_distributed()?;
+    distribution_tree.set_claimed(params.index)?;
+
+    transfer_from_sol_vault(
+        &ctx.accounts.system_program.to_account_info(),
+        &ctx.accounts.sol_vault.to_account_info(),
+        &ctx.accounts.claimant.to_account_info(),
+        &distribution_tree.key(),
+        ctx.bumps.sol_vault,
+        params.amount,
+    )
+}
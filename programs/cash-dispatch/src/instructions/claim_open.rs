@@ -0,0 +1,178 @@
+use crate::{
+    constants::{CLAIM_RECORD_SEED, CURRENT_VERSION, DISTRIBUTION_TREE_SEED},
+    error::DistributionError,
+    state::{ClaimRecord, DistributionTree},
+    utils::{check_gateway_token, gross_up_transfer_fee, transfer_checked_with_hook},
+    DistributionStatus,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+#[derive(Accounts)]
+#[instruction(params: ClaimOpenParams)]
+pub struct ClaimOpen<'info> {
+    /// Claimant of the distribution
+    #[account(mut)]
+    pub claimant: Signer<'info>,
+
+    /// DistributionTree account
+    #[account(
+        mut,
+        seeds = [
+            DISTRIBUTION_TREE_SEED.as_ref(),
+            distribution_tree.authority.as_ref(),
+            params.batch_id.as_bytes(),
+        ],
+        bump = distribution_tree.bump,
+        constraint = distribution_tree.version == CURRENT_VERSION @ DistributionError::UnsupportedVersion,
+        has_one = mint @ DistributionError::InvalidTokenMint,
+        has_one = token_vault @ DistributionError::InvalidTokenVault,
+    )]
+    pub distribution_tree: Account<'info, DistributionTree>,
+
+    /// ClaimRecord account (fails to initialize if the claimant already claimed this epoch)
+    #[account(
+        init,
+        payer = claimant,
+        space = 8 + ClaimRecord::INIT_SPACE,
+        seeds = [
+            CLAIM_RECORD_SEED.as_ref(),
+            distribution_tree.key().as_ref(),
+            claimant.key().as_ref(),
+            params.epoch.to_le_bytes().as_ref(),
+        ],
+        bump
+    )]
+    pub claim_record: Account<'info, ClaimRecord>,
+
+    /// Mint account (PYUSD)
+    #[account(
+        address = distribution_tree.mint,
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// Token Vault account
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = distribution_tree,
+        associated_token::token_program = token_program
+    )]
+    pub token_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// Claimant's token account
+    #[account(
+        init_if_needed,
+        payer = claimant,
+        associated_token::mint = mint,
+        associated_token::authority = claimant,
+        associated_token::token_program = token_program
+    )]
+    pub claimant_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// System & Token programs
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+
+    /// Civic Pass
+    /// CHECK: Verified by the solana-gateway program
+    pub gateway_token: UncheckedAccount<'info>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct ClaimOpenParams {
+    pub batch_id: String,
+    pub epoch: u64,
+}
+
+impl<'info> ClaimOpen<'info> {
+    fn transfer_to_claimant(&self, amount: u64, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
+        let signer_seeds = &[&self.distribution_tree.signer_seeds()[..]];
+
+        transfer_checked_with_hook(
+            &self.token_program.to_account_info(),
+            &self.token_vault.to_account_info(),
+            &self.mint.to_account_info(),
+            &self.claimant_token_account.to_account_info(),
+            &self.distribution_tree.to_account_info(),
+            remaining_accounts,
+            amount,
+            self.mint.decimals,
+            signer_seeds,
+        )
+    }
+}
+
+/// Validates the open-enrollment claim parameters
+///     1. The distribution is open for enrollment
+///     2. The distribution has started
+///     3. The distribution has not ended
+///     4. The distribution is active
+///     5. The epoch is the current epoch
+///     6. The claimant holds a valid gateway token
+pub fn validate(ctx: &Context<ClaimOpen>, params: &ClaimOpenParams) -> Result<()> {
+    let distribution_tree = &ctx.accounts.distribution_tree;
+    let current_ts = Clock::get()?.unix_timestamp;
+    require!(
+        distribution_tree.is_open_enrollment(),
+        DistributionError::NotOpenEnrollment
+    );
+    require_gte!(
+        current_ts,
+        distribution_tree.start_ts,
+        DistributionError::DistributionNotStarted
+    );
+    require_gte!(
+        distribution_tree.end_ts,
+        current_ts,
+        DistributionError::DistributionEnded
+    );
+    require!(
+        distribution_tree.status == DistributionStatus::Active,
+        DistributionError::DistributionNotActive
+    );
+    require_eq!(
+        params.epoch,
+        distribution_tree.epoch,
+        DistributionError::InvalidEpoch
+    );
+
+    check_gateway_token(
+        Some(&ctx.accounts.gateway_token.to_account_info()),
+        &ctx.accounts.claimant.key(),
+        &distribution_tree.gatekeeper_network.ok_or(DistributionError::MissingGatekeeperNetwork)?,
+        None,
+    )?;
+
+    Ok(())
+}
+
+/// Distributes the open-enrollment claim amount to the claimant
+///     1. Increments the total number distributed (enforcing the claimant cap)
+///     2. Records the claim in the claimant's ClaimRecord
+///     3. Transfers the tokens to the claimant, grossed up by the mint's transfer fee (if any)
+pub fn handler<'info>(ctx: Context<'_, '_, 'info, 'info, ClaimOpen<'info>>, params: ClaimOpenParams) -> Result<()> {
+    let distribution_tree = &mut ctx.accounts.distribution_tree;
+
+    distribution_tree.increment_number_distributed()?;
+
+    let amount = distribution_tree
+        .open_claim_amount
+        .ok_or(DistributionError::NotOpenEnrollment)?;
+
+    let claim_record = &mut ctx.accounts.claim_record;
+    claim_record.bump = ctx.bumps.claim_record;
+    claim_record.distribution_tree = distribution_tree.key();
+    claim_record.claimant = ctx.accounts.claimant.key();
+    claim_record.epoch = params.epoch;
+    claim_record.amount = amount;
+    claim_record.claimed_ts = Clock::get()?.unix_timestamp;
+
+    let transfer_amount = gross_up_transfer_fee(&ctx.accounts.mint.to_account_info(), amount)?;
+    ctx.accounts.transfer_to_claimant(transfer_amount, ctx.remaining_accounts)?;
+    Ok(())
+}
@@ -1,5 +1,15 @@
-use crate::{constants::DISTRIBUTION_TREE_SEED, error::DistributionError, state::DistributionTree, DistributionStatus};
+use crate::{
+    constants::{CURRENT_VERSION, DISTRIBUTION_TREE_SEED, MINT_REGISTRY_SEED, SOL_VAULT_SEED},
+    error::DistributionError,
+    state::{DistributionTree, MintRegistry},
+    utils::{
+        close_registered_vaults, close_token_vault, sweep_registered_vaults, transfer_checked_with_hook,
+        transfer_from_sol_vault,
+    },
+    DistributionStatus,
+};
 use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
 #[derive(Accounts)]
 #[instruction(params: CloseParams)]
@@ -16,10 +26,55 @@ pub struct Close<'info> {
             params.batch_id.as_bytes(),
         ],
         bump = distribution_tree.bump,
+        constraint = distribution_tree.version == CURRENT_VERSION @ DistributionError::UnsupportedVersion,
         has_one = authority @ DistributionError::SignerNotAuthorized,
     )]
     pub distribution_tree: Account<'info, DistributionTree>,
 
+    /// MintRegistry PDA of the tree (uninitialized unless additional mints have been registered)
+    /// CHECK: Address verified by seeds, loaded in the handler when initialized
+    #[account(
+        mut,
+        seeds = [
+            MINT_REGISTRY_SEED.as_ref(),
+            distribution_tree.key().as_ref(),
+        ],
+        bump
+    )]
+    pub mint_registry: UncheckedAccount<'info>,
+
+    /// Mint of the DistributionTree (token trees only)
+    #[account(
+        mut,
+        address = distribution_tree.mint @ DistributionError::InvalidTokenMint,
+    )]
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// Token Vault of the DistributionTree (token trees only)
+    /// CHECK: Address verified against the tree, swept and closed in the handler when initialized
+    #[account(
+        mut,
+        address = distribution_tree.token_vault @ DistributionError::InvalidTokenVault,
+    )]
+    pub token_vault: Option<UncheckedAccount<'info>>,
+
+    /// Authority's token account of the mint, receiving what is left in the token vault (token trees only)
+    #[account(mut)]
+    pub authority_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
+    /// SOL Vault of the DistributionTree (only funded for native SOL trees)
+    #[account(
+        mut,
+        seeds = [
+            SOL_VAULT_SEED.as_ref(),
+            distribution_tree.key().as_ref(),
+        ],
+        bump
+    )]
+    pub sol_vault: SystemAccount<'info>,
+
     pub system_program: Program<'info, System>,
 }
 
@@ -40,9 +95,108 @@ pub fn validate(ctx: &Context<Close>, params: &CloseParams) -> Result<()> {
         DistributionError::DistributionNotComplete
     );
 
+    if !ctx.accounts.distribution_tree.is_native() {
+        let (Some(mint), Some(token_vault), Some(authority_token_account), Some(token_program)) = (
+            &ctx.accounts.mint,
+            &ctx.accounts.token_vault,
+            &ctx.accounts.authority_token_account,
+            &ctx.accounts.token_program,
+        ) else {
+            return err!(DistributionError::InvalidTokenVaultAccounts);
+        };
+        require!(
+            mint.to_account_info().owner == token_program.key
+                && (token_vault.owner == token_program.key || token_vault.data_is_empty())
+                && authority_token_account.mint == mint.key()
+                && authority_token_account.owner == ctx.accounts.authority.key(),
+            DistributionError::InvalidTokenVaultAccounts
+        );
+    }
+
     Ok(())
 }
 
-pub fn handler(_ctx: Context<Close>, _params: CloseParams) -> Result<()> {
+/// Closes the distribution
+///     1. Sweeps what is left in the token vault (e.g. declined allocations kept as a residual pool)
+///        to the authority and closes the token vault
+///     2. Sweeps the vault of every registered mint to the authority and closes it, passed in the remaining
+///        accounts as `[mint, token_vault, authority_token_account, token_program]` per mint (the mint writable),
+///        followed by the accounts forwarded to the mints' transfer hooks
+///     3. Closes the MintRegistry
+///     4. Sweeps the SOL vault, including its rent reserve, to the authority
+pub fn handler<'info>(ctx: Context<'_, '_, 'info, 'info, Close<'info>>, _params: CloseParams) -> Result<()> {
+    if let (Some(mint), Some(token_vault), Some(authority_token_account), Some(token_program)) = (
+        &ctx.accounts.mint,
+        &ctx.accounts.token_vault,
+        &ctx.accounts.authority_token_account,
+        &ctx.accounts.token_program,
+    ) {
+        // Trees migrated from v1 may reference a token vault that was never created
+        if token_vault.owner == token_program.key {
+            let signer_seeds = &[&ctx.accounts.distribution_tree.signer_seeds()[..]];
+            let token_vault_balance = TokenAccount::try_deserialize(&mut &token_vault.try_borrow_data()?[..])?.amount;
+            if token_vault_balance > 0 {
+                transfer_checked_with_hook(
+                    &token_program.to_account_info(),
+                    &token_vault.to_account_info(),
+                    &mint.to_account_info(),
+                    &authority_token_account.to_account_info(),
+                    &ctx.accounts.distribution_tree.to_account_info(),
+                    ctx.remaining_accounts,
+                    token_vault_balance,
+                    mint.decimals,
+                    signer_seeds,
+                )?;
+            }
+            close_token_vault(
+                &token_program.to_account_info(),
+                &token_vault.to_account_info(),
+                &mint.to_account_info(),
+                &ctx.accounts.authority.to_account_info(),
+                &ctx.accounts.distribution_tree.to_account_info(),
+                signer_seeds,
+            )?;
+        }
+    }
+
+    let mint_registry_info = ctx.accounts.mint_registry.to_account_info();
+    if let Some(mint_registry) = MintRegistry::load(&mint_registry_info)? {
+        let signer_seeds = &[&ctx.accounts.distribution_tree.signer_seeds()[..]];
+        sweep_registered_vaults(
+            &ctx.accounts.distribution_tree.to_account_info(),
+            ctx.accounts.authority.key(),
+            &mint_registry,
+            ctx.remaining_accounts,
+            signer_seeds,
+        )?;
+        let authority_info = ctx.accounts.authority.to_account_info();
+        close_registered_vaults(
+            &ctx.accounts.distribution_tree.to_account_info(),
+            &authority_info,
+            &mint_registry,
+            ctx.remaining_accounts,
+            signer_seeds,
+        )?;
+        let refund = authority_info
+            .lamports()
+            .checked_add(mint_registry_info.lamports())
+            .ok_or(DistributionError::MathError)?;
+        **authority_info.lamports.borrow_mut() = refund;
+        **mint_registry_info.lamports.borrow_mut() = 0;
+        mint_registry_info.assign(&System::id());
+        mint_registry_info.realloc(0, false)?;
+    }
+
+    let sol_vault_balance = ctx.accounts.sol_vault.lamports();
+    if sol_vault_balance > 0 {
+        transfer_from_sol_vault(
+            &ctx.accounts.system_program.to_account_info(),
+            &ctx.accounts.sol_vault.to_account_info(),
+            &ctx.accounts.authority.to_account_info(),
+            &ctx.accounts.distribution_tree.key(),
+            ctx.bumps.sol_vault,
+            sol_vault_balance,
+        )?;
+    }
     Ok(())
 }
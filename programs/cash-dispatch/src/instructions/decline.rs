@@ -0,0 +1,286 @@
+use crate::{
+    constants::{CURRENT_VERSION, DECLINE_RECORD_SEED, DISPUTE_SEED, DISTRIBUTION_TREE_SEED, INSTALLMENT_RECORD_SEED, LEAF_REDIRECT_SEED, MINT_REGISTRY_SEED},
+    error::DistributionError,
+    events::LeafDeclined,
+    state::{DeclineRecord, Dispute, DistributionStatus, DistributionTree, InstallmentPlan, InstallmentRecord, LeafExtensions, LeafRedirect, LeafSchedule, MintRegistry, PayoutSplit},
+    utils::transfer_checked_with_hook,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+#[instruction(params: DeclineParams)]
+pub struct Decline<'info> {
+    /// Recipient of the leaf (or the redirect destination of a redirected leaf)
+    #[account(mut)]
+    pub recipient: Signer<'info>,
+
+    /// DistributionTree account
+    #[account(
+        mut,
+        seeds = [
+            DISTRIBUTION_TREE_SEED.as_ref(),
+            distribution_tree.authority.as_ref(),
+            params.batch_id.as_bytes(),
+        ],
+        bump = distribution_tree.bump,
+        constraint = distribution_tree.version == CURRENT_VERSION @ DistributionError::UnsupportedVersion,
+    )]
+    pub distribution_tree: Account<'info, DistributionTree>,
+
+    /// DeclineRecord account
+    #[account(
+        init,
+        payer = recipient,
+        space = 8 + DeclineRecord::INIT_SPACE,
+        seeds = [
+            DECLINE_RECORD_SEED.as_ref(),
+            distribution_tree.key().as_ref(),
+            params.epoch.to_le_bytes().as_ref(),
+            params.index.to_le_bytes().as_ref(),
+        ],
+        bump
+    )]
+    pub decline_record: Account<'info, DeclineRecord>,
+
+    /// LeafRedirect PDA of the leaf (uninitialized unless the leaf has been redirected)
+    /// CHECK: Address verified by seeds, loaded in validate when initialized
+    #[account(
+        seeds = [
+            LEAF_REDIRECT_SEED.as_ref(),
+            distribution_tree.key().as_ref(),
+            params.epoch.to_le_bytes().as_ref(),
+            params.index.to_le_bytes().as_ref(),
+        ],
+        bump
+    )]
+    pub leaf_redirect: UncheckedAccount<'info>,
+
+    /// MintRegistry PDA of the tree (uninitialized unless additional mints have been registered)
+    /// CHECK: Address verified by seeds, loaded in validate when initialized
+    #[account(
+        seeds = [
+            MINT_REGISTRY_SEED.as_ref(),
+            distribution_tree.key().as_ref(),
+        ],
+        bump
+    )]
+    pub mint_registry: UncheckedAccount<'info>,
+
+    /// Dispute PDA of the leaf (uninitialized unless the leaf has been disputed)
+    /// CHECK: Address verified by seeds, checked in validate when initialized
+    #[account(
+        seeds = [
+            DISPUTE_SEED.as_ref(),
+            distribution_tree.key().as_ref(),
+            params.epoch.to_le_bytes().as_ref(),
+            params.index.to_le_bytes().as_ref(),
+        ],
+        bump
+    )]
+    pub dispute: UncheckedAccount<'info>,
+
+    /// InstallmentRecord PDA of the leaf (uninitialized unless an installment has been paid)
+    /// CHECK: Address verified by seeds, loaded in the handler when initialized
+    #[account(
+        seeds = [
+            INSTALLMENT_RECORD_SEED.as_ref(),
+            distribution_tree.key().as_ref(),
+            params.epoch.to_le_bytes().as_ref(),
+            params.index.to_le_bytes().as_ref(),
+        ],
+        bump
+    )]
+    pub installment_record: UncheckedAccount<'info>,
+
+    /// Mint account (the tree's mint, or the registered mint committed in the leaf)
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// Token Vault account of the mint
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = distribution_tree,
+        associated_token::token_program = token_program
+    )]
+    pub token_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// Token account of the decline destination (required when the tree has a decline destination)
+    #[account(
+        mut,
+        token::mint = mint,
+        token::token_program = token_program
+    )]
+    pub decline_destination_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// System & Token programs
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct DeclineParams {
+    pub batch_id: String,
+    pub epoch: u64,
+    pub index: u64,
+    /// Amount committed in the leaf (shares for pro-rata trees)
+    pub amount: u64,
+    pub proof: Vec<[u8; 32]>,
+    /// (optional) Destinations committed in a split leaf
+    pub splits: Option<Vec<PayoutSplit>>,
+    /// (optional) Unlock and expiry timestamps committed in a scheduled leaf
+    pub schedule: Option<LeafSchedule>,
+    /// (optional) Installment plan committed in an installment leaf
+    pub installments: Option<InstallmentPlan>,
+    /// (optional) Index of the registered mint committed in the leaf (the tree's mint when omitted)
+    pub mint_index: Option<u8>,
+}
+
+impl<'info> Decline<'info> {
+    fn transfer_to_decline_destination(&self, amount: u64, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
+        let signer_seeds = &[&self.distribution_tree.signer_seeds()[..]];
+        let decline_destination_token_account = self
+            .decline_destination_token_account
+            .as_ref()
+            .ok_or(DistributionError::InvalidDeclineDestination)?;
+
+        transfer_checked_with_hook(
+            &self.token_program.to_account_info(),
+            &self.token_vault.to_account_info(),
+            &self.mint.to_account_info(),
+            &decline_destination_token_account.to_account_info(),
+            &self.distribution_tree.to_account_info(),
+            remaining_accounts,
+            amount,
+            self.mint.decimals,
+            signer_seeds,
+        )
+    }
+}
+
+/// Validates the decline parameters
+///     1. The distribution is active
+///     2. The epoch is the current epoch
+///     3. The distribution is not open-enrollment
+///     4. The leaf has not been paid, revoked or declined
+///     5. The leaf is not frozen by an open dispute (the authority resolves or rejects it first)
+///     6. The decline destination token account belongs to the tree's decline destination
+///     7. The installment plan is valid, on a fixed amount tree (installment leaves)
+///     8. The mint is the tree's mint, or the registered mint at the leaf's mint index (fixed amount trees only)
+///     9. The proof is valid for the signer (or the original key when the leaf has been redirected to the signer),
+///        including the splits, schedule, installment plan and mint index of an extended leaf
+pub fn validate(ctx: &Context<Decline>, params: &DeclineParams) -> Result<()> {
+    let distribution_tree = &ctx.accounts.distribution_tree;
+    require!(
+        distribution_tree.status == DistributionStatus::Active,
+        DistributionError::DistributionNotActive
+    );
+    require_eq!(
+        params.epoch,
+        distribution_tree.epoch,
+        DistributionError::InvalidEpoch
+    );
+    require!(
+        !distribution_tree.is_open_enrollment(),
+        DistributionError::OpenEnrollmentTree
+    );
+    require!(
+        !distribution_tree.is_claimed(params.index)?,
+        DistributionError::AlreadyClaimed
+    );
+
+    Dispute::require_not_open(&ctx.accounts.dispute.to_account_info())?;
+
+    if let Some(decline_destination) = distribution_tree.decline_destination {
+        let decline_destination_token_account = ctx
+            .accounts
+            .decline_destination_token_account
+            .as_ref()
+            .ok_or(DistributionError::InvalidDeclineDestination)?;
+        require_keys_eq!(
+            decline_destination_token_account.owner,
+            decline_destination,
+            DistributionError::InvalidDeclineDestination
+        );
+    }
+
+    let leaf_recipient = LeafRedirect::resolve_leaf_recipient(
+        &ctx.accounts.leaf_redirect.to_account_info(),
+        ctx.accounts.recipient.key(),
+    )?;
+
+    if let Some(installments) = &params.installments {
+        require!(
+            distribution_tree.total_shares.is_none(),
+            DistributionError::InstallmentsNotSupported
+        );
+        installments.validate(params.amount)?;
+    }
+
+    MintRegistry::require_leaf_mint(
+        &ctx.accounts.mint_registry.to_account_info(),
+        distribution_tree,
+        ctx.accounts.mint.key(),
+        params.mint_index,
+    )?;
+
+    distribution_tree.verify_extended_proof(
+        leaf_recipient,
+        params.amount,
+        LeafExtensions {
+            splits: params.splits.as_deref(),
+            schedule: params.schedule.as_ref(),
+            installments: params.installments.as_ref(),
+            mint_index: params.mint_index,
+        },
+        &params.proof,
+        params.index,
+    )?;
+
+    Ok(())
+}
+
+/// Declines the allocation of a leaf
+///     1. Marks the leaf as settled without payment
+///     2. Forwards the allocation to the decline destination, or leaves it in the residual pool
+///        (the installments that have not been paid, for installment leaves)
+///     3. Records the decline in a DeclineRecord for legal reporting
+pub fn handler<'info>(ctx: Context<'_, '_, 'info, 'info, Decline<'info>>, params: DeclineParams) -> Result<()> {
+    let unpaid_amount =
+        InstallmentRecord::unpaid_amount(&ctx.accounts.installment_record.to_account_info(), params.amount)?;
+    let distribution_tree = &mut ctx.accounts.distribution_tree;
+    let destination = distribution_tree.decline_destination;
+
+    let forwarded_amount =
+        distribution_tree.decline(params.index, unpaid_amount, ctx.accounts.token_vault.amount)?;
+
+    if forwarded_amount > 0 {
+        ctx.accounts.transfer_to_decline_destination(forwarded_amount, ctx.remaining_accounts)?;
+    }
+
+    let distribution_tree = ctx.accounts.distribution_tree.key();
+    let recipient = ctx.accounts.recipient.key();
+
+    let decline_record = &mut ctx.accounts.decline_record;
+    decline_record.bump = ctx.bumps.decline_record;
+    decline_record.distribution_tree = distribution_tree;
+    decline_record.epoch = params.epoch;
+    decline_record.index = params.index;
+    decline_record.recipient = recipient;
+    decline_record.amount = params.amount;
+    decline_record.destination = destination;
+    decline_record.forwarded_amount = forwarded_amount;
+    decline_record.declined_ts = Clock::get()?.unix_timestamp;
+
+    emit!(LeafDeclined {
+        distribution_tree,
+        epoch: params.epoch,
+        index: params.index,
+        recipient,
+        amount: params.amount,
+        destination,
+        forwarded_amount,
+    });
+
+    Ok(())
+}
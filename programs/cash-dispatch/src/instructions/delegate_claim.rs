@@ -0,0 +1,179 @@
+use crate::{
+    constants::{CURRENT_VERSION, DELEGATION_SEED, DISTRIBUTION_TREE_SEED, LEAF_REDIRECT_SEED},
+    error::DistributionError,
+    events::ClaimDelegated,
+    state::{Delegation, DistributionStatus, DistributionTree, InstallmentPlan, LeafExtensions, LeafRedirect, LeafSchedule, PayoutSplit},
+};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(params: DelegateClaimParams)]
+pub struct DelegateClaim<'info> {
+    /// Authority of the DistributionTree, or the recipient of the leaf
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    /// DistributionTree account
+    #[account(
+        seeds = [
+            DISTRIBUTION_TREE_SEED.as_ref(),
+            distribution_tree.authority.as_ref(),
+            params.batch_id.as_bytes(),
+        ],
+        bump = distribution_tree.bump,
+        constraint = distribution_tree.version == CURRENT_VERSION @ DistributionError::UnsupportedVersion,
+    )]
+    pub distribution_tree: Account<'info, DistributionTree>,
+
+    /// Delegation account
+    #[account(
+        init_if_needed,
+        payer = signer,
+        space = 8 + Delegation::INIT_SPACE,
+        seeds = [
+            DELEGATION_SEED.as_ref(),
+            distribution_tree.key().as_ref(),
+            params.epoch.to_le_bytes().as_ref(),
+            params.index.to_le_bytes().as_ref(),
+        ],
+        bump
+    )]
+    pub delegation: Account<'info, Delegation>,
+
+    /// LeafRedirect PDA of the leaf (uninitialized unless the leaf has been redirected)
+    /// CHECK: Address verified by seeds, loaded in validate when initialized
+    #[account(
+        seeds = [
+            LEAF_REDIRECT_SEED.as_ref(),
+            distribution_tree.key().as_ref(),
+            params.epoch.to_le_bytes().as_ref(),
+            params.index.to_le_bytes().as_ref(),
+        ],
+        bump
+    )]
+    pub leaf_redirect: UncheckedAccount<'info>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct DelegateClaimParams {
+    pub batch_id: String,
+    pub epoch: u64,
+    pub index: u64,
+    /// Wallet paid by the leaf (the key committed in the leaf, or its redirect destination)
+    pub recipient: Pubkey,
+    /// Amount committed in the leaf (shares for pro-rata trees)
+    pub amount: u64,
+    pub proof: Vec<[u8; 32]>,
+    pub delegate: Pubkey,
+    /// (optional) Wallet that receives delegated claims, defaults to the recipient
+    pub payee: Option<Pubkey>,
+    /// (optional) Destinations committed in a split leaf
+    pub splits: Option<Vec<PayoutSplit>>,
+    /// (optional) Unlock and expiry timestamps committed in a scheduled leaf
+    pub schedule: Option<LeafSchedule>,
+    /// (optional) Installment plan committed in an installment leaf
+    pub installments: Option<InstallmentPlan>,
+    /// (optional) Index of the registered mint committed in the leaf (the tree's mint when omitted)
+    pub mint_index: Option<u8>,
+}
+
+/// Validates the delegation parameters
+///     1. The signer is the authority or the recipient
+///     2. A delegation approved by the authority can only be changed by the authority
+///     3. The distribution has not been completed or cancelled
+///     4. The epoch is the current epoch
+///     5. The distribution is not open-enrollment
+///     6. The leaf has not been paid
+///     7. The delegate is neither the default pubkey nor the recipient
+///     8. The proof is valid (for the original key when the leaf has been redirected to the recipient),
+///        including the splits, schedule, installment plan and mint index of an extended leaf
+pub fn validate(ctx: &Context<DelegateClaim>, params: &DelegateClaimParams) -> Result<()> {
+    let distribution_tree = &ctx.accounts.distribution_tree;
+    let signer = ctx.accounts.signer.key();
+    let is_authority = signer == distribution_tree.authority;
+    require!(
+        is_authority || signer == params.recipient,
+        DistributionError::SignerNotAuthorized
+    );
+    require!(
+        is_authority || ctx.accounts.delegation.approved_by != distribution_tree.authority,
+        DistributionError::SignerNotAuthorized
+    );
+    require!(
+        !matches!(
+            distribution_tree.status,
+            DistributionStatus::Complete | DistributionStatus::Cancelled
+        ),
+        DistributionError::InvalidDistributionStatus
+    );
+    require_eq!(
+        params.epoch,
+        distribution_tree.epoch,
+        DistributionError::InvalidEpoch
+    );
+    require!(
+        !distribution_tree.is_open_enrollment(),
+        DistributionError::OpenEnrollmentTree
+    );
+    require!(
+        !distribution_tree.is_claimed(params.index)?,
+        DistributionError::AlreadyClaimed
+    );
+    require!(
+        params.delegate != Pubkey::default() && params.delegate != params.recipient,
+        DistributionError::InvalidDelegate
+    );
+
+    let leaf_recipient = LeafRedirect::resolve_leaf_recipient(
+        &ctx.accounts.leaf_redirect.to_account_info(),
+        params.recipient,
+    )?;
+
+    distribution_tree.verify_extended_proof(
+        leaf_recipient,
+        params.amount,
+        LeafExtensions {
+            splits: params.splits.as_deref(),
+            schedule: params.schedule.as_ref(),
+            installments: params.installments.as_ref(),
+            mint_index: params.mint_index,
+        },
+        &params.proof,
+        params.index,
+    )?;
+
+    Ok(())
+}
+
+/// Records the delegation of a leaf
+/// `claim` accepts the delegate's signature for the leaf and pays the delegation's payee
+pub fn handler(ctx: Context<DelegateClaim>, params: DelegateClaimParams) -> Result<()> {
+    let distribution_tree = ctx.accounts.distribution_tree.key();
+    let signer = ctx.accounts.signer.key();
+    let payee = params.payee.unwrap_or(params.recipient);
+    let delegation = &mut ctx.accounts.delegation;
+
+    delegation.bump = ctx.bumps.delegation;
+    delegation.distribution_tree = distribution_tree;
+    delegation.epoch = params.epoch;
+    delegation.index = params.index;
+    delegation.recipient = params.recipient;
+    delegation.delegate = params.delegate;
+    delegation.payee = payee;
+    delegation.approved_by = signer;
+
+    emit!(ClaimDelegated {
+        distribution_tree,
+        epoch: params.epoch,
+        index: params.index,
+        recipient: params.recipient,
+        delegate: params.delegate,
+        payee,
+        signer,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,166 @@
+use crate::{
+    constants::{CURRENT_VERSION, DISPUTE_SEED, DISTRIBUTION_TREE_SEED, LEAF_REDIRECT_SEED},
+    error::DistributionError,
+    events::DisputeOpened,
+    state::{Dispute, DisputeStatus, DistributionStatus, DistributionTree, InstallmentPlan, LeafExtensions, LeafRedirect, LeafSchedule, PayoutSplit},
+};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(params: DisputeLeafParams)]
+pub struct DisputeLeaf<'info> {
+    /// Recipient of the leaf (or the redirect destination of a redirected leaf)
+    #[account(mut)]
+    pub recipient: Signer<'info>,
+
+    /// DistributionTree account
+    #[account(
+        seeds = [
+            DISTRIBUTION_TREE_SEED.as_ref(),
+            distribution_tree.authority.as_ref(),
+            params.batch_id.as_bytes(),
+        ],
+        bump = distribution_tree.bump,
+        constraint = distribution_tree.version == CURRENT_VERSION @ DistributionError::UnsupportedVersion,
+    )]
+    pub distribution_tree: Account<'info, DistributionTree>,
+
+    /// Dispute account
+    #[account(
+        init,
+        payer = recipient,
+        space = 8 + Dispute::INIT_SPACE,
+        seeds = [
+            DISPUTE_SEED.as_ref(),
+            distribution_tree.key().as_ref(),
+            params.epoch.to_le_bytes().as_ref(),
+            params.index.to_le_bytes().as_ref(),
+        ],
+        bump
+    )]
+    pub dispute: Account<'info, Dispute>,
+
+    /// LeafRedirect PDA of the leaf (uninitialized unless the leaf has been redirected)
+    /// CHECK: Address verified by seeds, loaded in validate when initialized
+    #[account(
+        seeds = [
+            LEAF_REDIRECT_SEED.as_ref(),
+            distribution_tree.key().as_ref(),
+            params.epoch.to_le_bytes().as_ref(),
+            params.index.to_le_bytes().as_ref(),
+        ],
+        bump
+    )]
+    pub leaf_redirect: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct DisputeLeafParams {
+    pub batch_id: String,
+    pub epoch: u64,
+    pub index: u64,
+    /// Amount committed in the leaf
+    pub amount: u64,
+    pub proof: Vec<[u8; 32]>,
+    /// Hash of the recipient's reason for the dispute
+    pub reason_hash: [u8; 32],
+    /// (optional) Destinations committed in a split leaf (not supported, `resolve_dispute` pays the disputant)
+    pub splits: Option<Vec<PayoutSplit>>,
+    /// (optional) Unlock and expiry timestamps committed in a scheduled leaf
+    pub schedule: Option<LeafSchedule>,
+    /// (optional) Installment plan committed in an installment leaf (not supported, `resolve_dispute` pays the leaf at once)
+    pub installments: Option<InstallmentPlan>,
+    /// (optional) Index of the registered mint committed in the leaf (not supported, `resolve_dispute` pays the tree's mint)
+    pub mint_index: Option<u8>,
+}
+
+/// Validates the dispute parameters
+///     1. The distribution is active
+///     2. The epoch is the current epoch
+///     3. The distribution is a fixed amount merkle tree (not open-enrollment or pro-rata)
+///     4. The leaf has not been paid, revoked or declined
+///     5. The leaf is not a split or installment leaf, and pays the tree's mint
+///     6. The proof is valid for the signer (or the original key when the leaf has been redirected to the signer),
+///        including the schedule of a scheduled leaf
+pub fn validate(ctx: &Context<DisputeLeaf>, params: &DisputeLeafParams) -> Result<()> {
+    let distribution_tree = &ctx.accounts.distribution_tree;
+    require!(
+        distribution_tree.status == DistributionStatus::Active,
+        DistributionError::DistributionNotActive
+    );
+    require_eq!(
+        params.epoch,
+        distribution_tree.epoch,
+        DistributionError::InvalidEpoch
+    );
+    require!(
+        !distribution_tree.is_open_enrollment() && distribution_tree.total_shares.is_none(),
+        DistributionError::DisputesNotSupported
+    );
+    require!(
+        !distribution_tree.is_claimed(params.index)?,
+        DistributionError::AlreadyClaimed
+    );
+
+    require!(
+        params.splits.is_none() && params.installments.is_none() && params.mint_index.is_none(),
+        DistributionError::UnsupportedLeafExtension
+    );
+
+    let leaf_recipient = LeafRedirect::resolve_leaf_recipient(
+        &ctx.accounts.leaf_redirect.to_account_info(),
+        ctx.accounts.recipient.key(),
+    )?;
+
+    distribution_tree.verify_extended_proof(
+        leaf_recipient,
+        params.amount,
+        LeafExtensions {
+            splits: params.splits.as_deref(),
+            schedule: params.schedule.as_ref(),
+            installments: params.installments.as_ref(),
+            mint_index: params.mint_index,
+        },
+        &params.proof,
+        params.index,
+    )?;
+
+    Ok(())
+}
+
+/// Opens a dispute on a leaf
+///     1. Records the Dispute PDA, which freezes the leaf until the authority resolves or rejects it
+///     2. Emits the DisputeOpened event
+pub fn handler(ctx: Context<DisputeLeaf>, params: DisputeLeafParams) -> Result<()> {
+    let distribution_tree = ctx.accounts.distribution_tree.key();
+    let recipient = ctx.accounts.recipient.key();
+
+    let dispute = &mut ctx.accounts.dispute;
+    dispute.bump = ctx.bumps.dispute;
+    dispute.distribution_tree = distribution_tree;
+    dispute.epoch = params.epoch;
+    dispute.index = params.index;
+    dispute.recipient = recipient;
+    dispute.amount = params.amount;
+    dispute.reason_hash = params.reason_hash;
+    dispute.status = DisputeStatus::Open;
+    dispute.resolved_amount = 0;
+    dispute.resolution_hash = [0u8; 32];
+    dispute.opened_ts = Clock::get()?.unix_timestamp;
+    dispute.closed_ts = 0;
+
+    emit!(DisputeOpened {
+        distribution_tree,
+        epoch: params.epoch,
+        index: params.index,
+        recipient,
+        amount: params.amount,
+        reason_hash: params.reason_hash,
+    });
+
+    msg!("Dispute opened for leaf {}", params.index);
+
+    Ok(())
+}
@@ -1,11 +1,14 @@
 use crate::{
-    constants::DISTRIBUTION_TREE_SEED, error::DistributionError, state::DistributionTree,
-    utils::check_gateway_token, DistributionStatus
+    constants::{CURRENT_VERSION, DISPUTE_SEED, DISTRIBUTION_TREE_SEED, INSTALLMENT_RECORD_SEED, LEAF_REDIRECT_SEED, MINT_REGISTRY_SEED}, error::DistributionError,
+    state::{Dispute, DistributionTree, InstallmentPlan, InstallmentRecord, LeafExtensions, LeafRedirect, LeafSchedule, MintRegistry, PayoutSplit},
+    utils::{check_gateway_token, transfer_checked_with_hook, transfer_to_splits},
+    events::InstallmentsPaid,
+    DistributionStatus,
 };
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
+    token_interface::{Mint, TokenAccount, TokenInterface},
 };
 
 #[derive(Accounts)]
@@ -29,19 +32,15 @@ pub struct Distribute<'info> {
             params.batch_id.as_bytes(),
         ],
         bump = distribution_tree.bump,
-        has_one = mint @ DistributionError::InvalidTokenMint,
-        has_one = token_vault @ DistributionError::InvalidTokenVault,
+        constraint = distribution_tree.version == CURRENT_VERSION @ DistributionError::UnsupportedVersion,
         has_one = authority @ DistributionError::SignerNotAuthorized
     )]
     pub distribution_tree: Account<'info, DistributionTree>,
 
-    /// Mint account (PYUSD)
-    #[account(
-        address = distribution_tree.mint,
-    )]
+    /// Mint account (PYUSD, or the registered mint committed in the leaf)
     pub mint: InterfaceAccount<'info, Mint>,
 
-    /// Token Vault account
+    /// Token Vault account of the mint
     #[account(
         mut,
         associated_token::mint = mint,
@@ -50,7 +49,7 @@ pub struct Distribute<'info> {
     )]
     pub token_vault: InterfaceAccount<'info, TokenAccount>,
 
-    /// Recipient's token account
+    /// Recipient's token account (omitted for split leaves)
     #[account(
         init_if_needed,
         payer = authority,
@@ -58,7 +57,59 @@ pub struct Distribute<'info> {
         associated_token::authority = recipient,
         associated_token::token_program = token_program
     )]
-    pub recipient_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub recipient_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// MintRegistry PDA of the tree (uninitialized unless additional mints have been registered)
+    /// CHECK: Address verified by seeds, loaded in validate when initialized
+    #[account(
+        seeds = [
+            MINT_REGISTRY_SEED.as_ref(),
+            distribution_tree.key().as_ref(),
+        ],
+        bump
+    )]
+    pub mint_registry: UncheckedAccount<'info>,
+
+    /// LeafRedirect PDA of the leaf (uninitialized unless the leaf has been redirected)
+    /// CHECK: Address verified by seeds, loaded in validate when initialized
+    #[account(
+        seeds = [
+            LEAF_REDIRECT_SEED.as_ref(),
+            distribution_tree.key().as_ref(),
+            params.epoch.to_le_bytes().as_ref(),
+            params.index.to_le_bytes().as_ref(),
+        ],
+        bump
+    )]
+    pub leaf_redirect: UncheckedAccount<'info>,
+
+    /// Dispute PDA of the leaf (uninitialized unless the leaf has been disputed)
+    /// CHECK: Address verified by seeds, checked in validate when initialized
+    #[account(
+        seeds = [
+            DISPUTE_SEED.as_ref(),
+            distribution_tree.key().as_ref(),
+            params.epoch.to_le_bytes().as_ref(),
+            params.index.to_le_bytes().as_ref(),
+        ],
+        bump
+    )]
+    pub dispute: UncheckedAccount<'info>,
+
+    /// InstallmentRecord PDA of the leaf (installment leaves only)
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + InstallmentRecord::INIT_SPACE,
+        seeds = [
+            INSTALLMENT_RECORD_SEED.as_ref(),
+            distribution_tree.key().as_ref(),
+            params.epoch.to_le_bytes().as_ref(),
+            params.index.to_le_bytes().as_ref(),
+        ],
+        bump
+    )]
+    pub installment_record: Option<Account<'info, InstallmentRecord>>,
 
     /// System & Token programs
     pub associated_token_program: Program<'info, AssociatedToken>,
@@ -72,29 +123,64 @@ pub struct Distribute<'info> {
 
 #[derive(AnchorSerialize, AnchorDeserialize)]
 pub struct DistributeParams {
+    /// Amount committed in the leaf (shares for pro-rata trees)
     pub amount: u64,
     pub proof: Vec<[u8; 32]>,
     pub batch_id: String,
     pub index: u64,
+    pub epoch: u64,
+    /// (optional) Destinations committed in a split leaf, paid through the remaining accounts
+    pub splits: Option<Vec<PayoutSplit>>,
+    /// (optional) Unlock and expiry timestamps committed in a scheduled leaf
+    pub schedule: Option<LeafSchedule>,
+    /// (optional) Installment plan committed in an installment leaf
+    pub installments: Option<InstallmentPlan>,
+    /// (optional) Index of the registered mint committed in the leaf (the tree's mint when omitted)
+    pub mint_index: Option<u8>,
 }
 
 impl<'info> Distribute<'info> {
-    fn transfer_to_recipient(&self, amount: u64) -> Result<()> {
+    fn transfer_to_recipient(&self, amount: u64, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
         let signer_seeds = &[&self.distribution_tree.signer_seeds()[..]];
+        let recipient_token_account = self
+            .recipient_token_account
+            .as_ref()
+            .ok_or(DistributionError::InvalidDestinationTokenAccount)?;
+        let amount = self
+            .distribution_tree
+            .payout_transfer_amount(&self.mint.to_account_info(), amount)?;
 
-        transfer_checked(
-            CpiContext::new_with_signer(
-                self.token_program.to_account_info(),
-                TransferChecked {
-                    from: self.token_vault.to_account_info(),
-                    mint: self.mint.to_account_info(),
-                    to: self.recipient_token_account.to_account_info(),
-                    authority: self.distribution_tree.to_account_info(),
-                },
-                signer_seeds,
-            ),
+        transfer_checked_with_hook(
+            &self.token_program.to_account_info(),
+            &self.token_vault.to_account_info(),
+            &self.mint.to_account_info(),
+            &recipient_token_account.to_account_info(),
+            &self.distribution_tree.to_account_info(),
+            remaining_accounts,
             amount,
             self.mint.decimals,
+            signer_seeds,
+        )
+    }
+
+    fn transfer_to_splits(
+        &self,
+        amount: u64,
+        splits: &[PayoutSplit],
+        remaining_accounts: &'info [AccountInfo<'info>],
+    ) -> Result<()> {
+        let signer_seeds = &[&self.distribution_tree.signer_seeds()[..]];
+
+        transfer_to_splits(
+            &self.token_program.to_account_info(),
+            &self.token_vault.to_account_info(),
+            &self.mint,
+            &self.distribution_tree.to_account_info(),
+            splits,
+            remaining_accounts,
+            amount,
+            self.distribution_tree.total_shares.is_none(),
+            signer_seeds,
         )
     }
 }
@@ -103,7 +189,16 @@ impl<'info> Distribute<'info> {
 ///     1. The distribution has started
 ///     2. The distribution has not ended
 ///     3. The distribution is active
-///     4. The proof is valid
+///     4. The epoch is the current epoch
+///     5. The distribution is not open-enrollment or native SOL
+///     6. The leaf is not frozen by an open dispute
+///     7. The recipient's token account is provided, unless the leaf is split
+///     8. The payout splits are valid
+///     9. The leaf has unlocked and has not expired (scheduled leaves)
+///     10. The installment plan is valid, due before the distribution ends or the leaf expires, on a fixed
+///         amount tree, with its InstallmentRecord (installment leaves)
+///     11. The mint is the tree's mint, or the registered mint at the leaf's mint index (fixed amount trees only)
+///     12. The proof is valid (for the original key when the leaf has been redirected to the payee)
 pub fn validate(ctx: &Context<Distribute>, params: &DistributeParams) -> Result<()> {
     let distribution_tree = &ctx.accounts.distribution_tree;
     let current_ts = Clock::get()?.unix_timestamp;
@@ -121,14 +216,71 @@ pub fn validate(ctx: &Context<Distribute>, params: &DistributeParams) -> Result<
         distribution_tree.status == DistributionStatus::Active,
         DistributionError::DistributionNotActive
     );
+    require_eq!(
+        params.epoch,
+        distribution_tree.epoch,
+        DistributionError::InvalidEpoch
+    );
+    require!(
+        !distribution_tree.is_open_enrollment(),
+        DistributionError::OpenEnrollmentTree
+    );
+    require!(!distribution_tree.is_native(), DistributionError::NativeTree);
     require!(
         !distribution_tree.is_claimed(params.index)?,
         DistributionError::AlreadyClaimed
     );
 
-    distribution_tree.verify_proof(
+    Dispute::require_not_open(&ctx.accounts.dispute.to_account_info())?;
+
+    let leaf_recipient = LeafRedirect::resolve_leaf_recipient(
+        &ctx.accounts.leaf_redirect.to_account_info(),
         ctx.accounts.recipient.key(),
+    )?;
+
+    match &params.splits {
+        Some(splits) => PayoutSplit::validate(splits)?,
+        None => require!(
+            ctx.accounts.recipient_token_account.is_some(),
+            DistributionError::InvalidDestinationTokenAccount
+        ),
+    }
+    if let Some(schedule) = &params.schedule {
+        schedule.validate(current_ts)?;
+    }
+    if let Some(installments) = &params.installments {
+        require!(
+            distribution_tree.total_shares.is_none(),
+            DistributionError::InstallmentsNotSupported
+        );
+        installments.validate(params.amount)?;
+        installments.validate_due_dates(
+            distribution_tree.start_ts,
+            distribution_tree.end_ts,
+            params.schedule.as_ref(),
+        )?;
+        require!(
+            ctx.accounts.installment_record.is_some(),
+            DistributionError::MissingInstallmentRecord
+        );
+    }
+
+    MintRegistry::require_leaf_mint(
+        &ctx.accounts.mint_registry.to_account_info(),
+        distribution_tree,
+        ctx.accounts.mint.key(),
+        params.mint_index,
+    )?;
+
+    distribution_tree.verify_extended_proof(
+        leaf_recipient,
         params.amount,
+        LeafExtensions {
+            splits: params.splits.as_deref(),
+            schedule: params.schedule.as_ref(),
+            installments: params.installments.as_ref(),
+            mint_index: params.mint_index,
+        },
         &params.proof,
         params.index,
     )?;
@@ -139,7 +291,7 @@ pub fn validate(ctx: &Context<Distribute>, params: &DistributeParams) -> Result<
     if gateway_check_required {
         check_gateway_token(
             ctx.accounts.gateway_token.as_ref().map(|token| token.to_account_info()).as_ref(),
-            &ctx.accounts.recipient.to_account_info(),
+            &ctx.accounts.recipient.key(),
             &ctx.accounts.distribution_tree.gatekeeper_network.ok_or(DistributionError::MissingGatekeeperNetwork)?,
             None,
         )?;
@@ -150,14 +302,63 @@ pub fn validate(ctx: &Context<Distribute>, params: &DistributeParams) -> Result<
 
 /// Distributes the tokens to the recipient
 ///     1. Increments the number of recipients distributed
-///     2. Transfers the tokens to the recipient
-pub fn handler(ctx: Context<Distribute>, params: DistributeParams) -> Result<()> {
+///     2. Calculates the payout (the leaf amount, or its pro-rata share of the vault)
+///        Installment leaves pay every overdue installment instead, and are only marked as
+///        distributed once the last installment has been paid
+///     3. Transfers the tokens to the recipient, or fans them out to the split token accounts
+///        in the remaining accounts (any further remaining accounts are forwarded to the transfer hook)
+///        Fixed amount payouts are grossed up by the mint's transfer fee, if any
+pub fn handler<'info>(ctx: Context<'_, '_, 'info, 'info, Distribute<'info>>, params: DistributeParams) -> Result<()> {
     let distribution_tree = &mut ctx.accounts.distribution_tree;
 
-    distribution_tree.increment_number_distributed()?;
+    let payout = match &params.installments {
+        Some(installments) => {
+            let installment_record = ctx
+                .accounts
+                .installment_record
+                .as_mut()
+                .ok_or(DistributionError::MissingInstallmentRecord)?;
+            if installment_record.count == 0 {
+                installment_record.initialize(
+                    ctx.bumps.installment_record.ok_or(DistributionError::MissingInstallmentRecord)?,
+                    distribution_tree.key(),
+                    params.epoch,
+                    params.index,
+                    installments,
+                    InstallmentPlan::first_due_ts(distribution_tree.start_ts, params.schedule.as_ref()),
+                );
+            }
+
+            let installments_paid_before = installment_record.installments_paid;
+            let payout = distribution_tree.settle_installments(
+                params.index,
+                installment_record,
+                Clock::get()?.unix_timestamp,
+            )?;
+
+            emit!(InstallmentsPaid {
+                distribution_tree: distribution_tree.key(),
+                epoch: params.epoch,
+                index: params.index,
+                installments: installment_record.installments_paid - installments_paid_before,
+                installments_paid: installment_record.installments_paid,
+                count: installment_record.count,
+                amount: payout,
+            });
+            payout
+        }
+        None => {
+            distribution_tree.increment_number_distributed()?;
 
-    distribution_tree.set_claimed(params.index)?;
+            distribution_tree.set_claimed(params.index)?;
 
-    ctx.accounts.transfer_to_recipient(params.amount)?;
+            distribution_tree.settle_payout(params.amount, ctx.accounts.token_vault.amount)?
+        }
+    };
+
+    match &params.splits {
+        Some(splits) => ctx.accounts.transfer_to_splits(payout, splits, ctx.remaining_accounts)?,
+        None => ctx.accounts.transfer_to_recipient(payout, ctx.remaining_accounts)?,
+    }
     Ok(())
 }
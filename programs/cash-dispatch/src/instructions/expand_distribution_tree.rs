@@ -1,5 +1,5 @@
 use anchor_lang::prelude::*;
-use crate::{constants::DISTRIBUTION_TREE_SEED, error::DistributionError, state::DistributionTree, DistributionStatus};
+use crate::{constants::{CURRENT_VERSION, DISTRIBUTION_TREE_SEED}, error::DistributionError, state::DistributionTree, DistributionStatus};
 
 #[derive(Accounts)]
 #[instruction(params: ExpandParams)]
@@ -15,6 +15,7 @@ pub struct ExpandDistributionTree<'info> {
             params.batch_id.as_bytes()
         ],
         bump = distribution_tree.bump,
+        constraint = distribution_tree.version == CURRENT_VERSION @ DistributionError::UnsupportedVersion,
         has_one = authority @ DistributionError::SignerNotAuthorized,
         // Add space for more u64's based on BITMAP_ARRAY_STEP 
         realloc = distribution_tree.calculate_account_size() + (distribution_tree.calculate_expansion_required() * 8), 
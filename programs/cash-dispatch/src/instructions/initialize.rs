@@ -1,13 +1,17 @@
 use std::str::FromStr;
 
 use crate::{
-    constants::PYUSD_MINT, error::DistributionError, state::DistributionTree, utils::calculate_fee,
+    constants::PYUSD_MINT,
+    error::DistributionError,
+    events::EpochStarted,
+    state::{DistributionTree, HashAlgorithm, InitializeTreeParams, MintRisks},
+    utils::{calculate_fee, gross_up_transfer_fee, require_vault_received, transfer_checked_with_hook},
     BATCH_ID_MAXIMUM_LENGTH, BATCH_ID_MINIMUM_LENGTH, DISTRIBUTION_TREE_SEED, FEES_WALLET,
 };
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
+    token_interface::{Mint, TokenAccount, TokenInterface},
 };
 
 #[derive(Accounts)]
@@ -78,42 +82,46 @@ pub struct InitializeParams {
     pub allow_claims: bool,
     pub total_number_recipients: u64,
     pub transfer_to_vault_amount: u64,
+    /// Decimals of the mint, must match the mint account
     pub mint_decimals: u8,
     pub start_ts: i64,
     pub end_ts: Option<i64>,
     pub gatekeeper_network: Option<Pubkey>,
+    pub hash_algorithm: Option<HashAlgorithm>,
+    pub total_shares: Option<u64>,
+    pub open_claim_amount: Option<u64>,
+    pub decline_destination: Option<Pubkey>,
+    /// Acknowledges that the mint has a permanent delegate, default-frozen accounts or a freeze
+    /// authority, any of which can drain or lock the token vault
+    pub acknowledge_mint_risks: bool,
 }
 
 impl<'info> Initialize<'info> {
-    fn transfer_to_vault(&self, amount: u64, decimals: u8) -> Result<()> {
-        transfer_checked(
-            CpiContext::new(
-                self.token_program.to_account_info(),
-                TransferChecked {
-                    from: self.token_source.to_account_info(),
-                    mint: self.mint.to_account_info(),
-                    to: self.token_vault.to_account_info(),
-                    authority: self.authority.to_account_info(),
-                },
-            ),
+    fn transfer_to_vault(&self, amount: u64, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
+        transfer_checked_with_hook(
+            &self.token_program.to_account_info(),
+            &self.token_source.to_account_info(),
+            &self.mint.to_account_info(),
+            &self.token_vault.to_account_info(),
+            &self.authority.to_account_info(),
+            remaining_accounts,
             amount,
-            decimals,
+            self.mint.decimals,
+            &[],
         )
     }
 
-    fn pay_fees(&self, amount: u64, decimals: u8) -> Result<()> {
-        transfer_checked(
-            CpiContext::new(
-                self.token_program.to_account_info(),
-                TransferChecked {
-                    from: self.token_source.to_account_info(),
-                    mint: self.mint.to_account_info(),
-                    to: self.fees_token_account.to_account_info(),
-                    authority: self.authority.to_account_info(),
-                },
-            ),
+    fn pay_fees(&self, amount: u64, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
+        transfer_checked_with_hook(
+            &self.token_program.to_account_info(),
+            &self.token_source.to_account_info(),
+            &self.mint.to_account_info(),
+            &self.fees_token_account.to_account_info(),
+            &self.authority.to_account_info(),
+            remaining_accounts,
             amount,
-            decimals,
+            self.mint.decimals,
+            &[],
         )
     }
 }
@@ -122,9 +130,16 @@ impl<'info> Initialize<'info> {
 ///     1. The start timestamp is before the end timestamp
 ///     2. The end timestamps is in the future
 ///     3. The total number of recipients is greater than 0
-///     4. The transfer amount is greater than 0
+///     4. The transfer amount is greater than 0 (pro-rata trees may be funded later)
 ///     5. The batch_id is between 8 and 15 characters
-pub fn validate(_ctx: &Context<Initialize>, params: &InitializeParams) -> Result<()> {
+///     6. The total shares of a pro-rata tree is greater than 0
+///     7. Open-enrollment trees have a claim amount, allow claims, require a gatekeeper network
+///        and have no merkle root or shares
+///     8. The decline destination is not the default pubkey
+///     9. The mint decimals match the mint account
+///     10. The mint's risky capabilities (permanent delegate, default-frozen accounts, freeze authority)
+///        are acknowledged
+pub fn validate(ctx: &Context<Initialize>, params: &InitializeParams) -> Result<()> {
     let current_ts = Clock::get()?.unix_timestamp;
     require_gt!(
         params.end_ts.unwrap_or(i64::MAX),
@@ -141,11 +156,13 @@ pub fn validate(_ctx: &Context<Initialize>, params: &InitializeParams) -> Result
         0,
         DistributionError::NoRecipients
     );
-    require_gt!(
-        params.transfer_to_vault_amount,
-        0,
-        DistributionError::ZeroTransferAmount
-    );
+    if params.total_shares.is_none() {
+        require_gt!(
+            params.transfer_to_vault_amount,
+            0,
+            DistributionError::ZeroTransferAmount
+        );
+    }
 
     require_gte!(
         BATCH_ID_MAXIMUM_LENGTH,
@@ -157,40 +174,97 @@ pub fn validate(_ctx: &Context<Initialize>, params: &InitializeParams) -> Result
         BATCH_ID_MINIMUM_LENGTH,
         DistributionError::BatchIdTooShort
     );
+    if let Some(total_shares) = params.total_shares {
+        require_gt!(total_shares, 0, DistributionError::InvalidShares);
+    }
+    if let Some(open_claim_amount) = params.open_claim_amount {
+        require!(
+            open_claim_amount > 0
+                && params.allow_claims
+                && params.gatekeeper_network.is_some()
+                && params.merkle_root == [0u8; 32]
+                && params.total_shares.is_none(),
+            DistributionError::InvalidOpenEnrollment
+        );
+    }
+    if let Some(decline_destination) = params.decline_destination {
+        require_keys_neq!(
+            decline_destination,
+            Pubkey::default(),
+            DistributionError::InvalidDeclineDestination
+        );
+    }
+    require_eq!(
+        params.mint_decimals,
+        ctx.accounts.mint.decimals,
+        DistributionError::InvalidMintDecimals
+    );
+    let mint_risks = MintRisks::inspect(&ctx.accounts.mint.to_account_info())?;
+    require!(
+        !mint_risks.any() || params.acknowledge_mint_risks,
+        DistributionError::UnacknowledgedMintRisks
+    );
     Ok(())
 }
 
 /// Creates a new DistributionTree
-///     1. Initializes the DistributionTree PDA
-///     2. Transfers the tokens from the token_source to the token_vault
-pub fn handler(ctx: Context<Initialize>, params: InitializeParams) -> Result<()> {
+///     1. Initializes the DistributionTree PDA, recording the mint's acknowledged risks
+///     2. Transfers the tokens from the token_source to the token_vault, grossed up by the mint's
+///        transfer fee (if any), and checks the vault received transfer_to_vault_amount
+pub fn handler<'info>(ctx: Context<'_, '_, 'info, 'info, Initialize<'info>>, params: InitializeParams) -> Result<()> {
     let distribution_tree = &mut ctx.accounts.distribution_tree;
     let authority = &ctx.accounts.authority.key();
     let bump = ctx.bumps.distribution_tree;
+    let mint_risks = MintRisks::inspect(&ctx.accounts.mint.to_account_info())?;
 
-    distribution_tree.initialize(
+    distribution_tree.initialize(InitializeTreeParams {
         bump,
-        *authority,
-        params.batch_id,
-        params.allow_claims,
-        params.merkle_root,
-        ctx.accounts.mint.key(),
-        ctx.accounts.token_vault.key(),
-        params.total_number_recipients,
-        params.start_ts,
-        params.end_ts,
-        params.gatekeeper_network,
-    )?;
+        authority: *authority,
+        batch_id: params.batch_id,
+        allow_claims: params.allow_claims,
+        merkle_root: params.merkle_root,
+        mint: ctx.accounts.mint.key(),
+        token_vault: ctx.accounts.token_vault.key(),
+        total_number_recipients: params.total_number_recipients,
+        start_ts: params.start_ts,
+        end_ts: params.end_ts,
+        gatekeeper_network: params.gatekeeper_network,
+        hash_algorithm: params.hash_algorithm.unwrap_or(HashAlgorithm::Keccak),
+        total_shares: params.total_shares,
+        open_claim_amount: params.open_claim_amount,
+        decline_destination: params.decline_destination,
+        mint_risks,
+    })?;
 
-    ctx.accounts
-        .transfer_to_vault(params.transfer_to_vault_amount, params.mint_decimals)?;
+    if params.transfer_to_vault_amount > 0 {
+        let transfer_amount = gross_up_transfer_fee(
+            &ctx.accounts.mint.to_account_info(),
+            params.transfer_to_vault_amount,
+        )?;
+        ctx.accounts
+            .transfer_to_vault(transfer_amount, ctx.remaining_accounts)?;
+        require_vault_received(&mut ctx.accounts.token_vault, 0, params.transfer_to_vault_amount)?;
 
-    let fee_amount = calculate_fee(params.transfer_to_vault_amount)?;
+        let fee_amount = calculate_fee(params.transfer_to_vault_amount, ctx.accounts.mint.decimals)?;
 
-    if fee_amount > 0 {
-        ctx.accounts.pay_fees(fee_amount, params.mint_decimals)?;
+        if fee_amount > 0 {
+            ctx.accounts.pay_fees(fee_amount, ctx.remaining_accounts)?;
+        }
     }
 
+    let distribution_tree = &ctx.accounts.distribution_tree;
+
+    emit!(EpochStarted {
+        distribution_tree: distribution_tree.key(),
+        batch_id: distribution_tree.batch_id.clone(),
+        epoch: distribution_tree.epoch,
+        merkle_root: distribution_tree.merkle_root,
+        total_number_recipients: distribution_tree.total_number_recipients,
+        funded_amount: params.transfer_to_vault_amount,
+        start_ts: distribution_tree.start_ts,
+        end_ts: distribution_tree.end_ts,
+    });
+
     msg!(
         "Distribution tree initialized for {} recipients",
         params.total_number_recipients
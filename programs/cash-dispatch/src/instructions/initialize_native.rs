@@ -0,0 +1,202 @@
+use std::str::FromStr;
+
+use crate::{
+    constants::SOL_VAULT_SEED,
+    error::DistributionError,
+    events::EpochStarted,
+    state::{DistributionTree, HashAlgorithm, InitializeTreeParams},
+    utils::calculate_fee,
+    BATCH_ID_MAXIMUM_LENGTH, BATCH_ID_MINIMUM_LENGTH, DISTRIBUTION_TREE_SEED, FEES_WALLET,
+};
+use anchor_lang::{
+    prelude::*,
+    system_program::{transfer, Transfer},
+};
+use anchor_spl::token::spl_token::native_mint;
+
+#[derive(Accounts)]
+#[instruction(params: InitializeNativeParams)]
+pub struct InitializeNative<'info> {
+    /// Signer and Authority of the DistributionTree
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// DistributionTree account
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + DistributionTree::INIT_SPACE,
+        seeds = [
+            DISTRIBUTION_TREE_SEED.as_ref(),
+            authority.key().as_ref(),
+            params.batch_id.as_bytes(),
+        ],
+        bump
+    )]
+    pub distribution_tree: Account<'info, DistributionTree>,
+
+    /// SOL Vault (lamport-holding system account PDA of the DistributionTree)
+    #[account(
+        mut,
+        seeds = [
+            SOL_VAULT_SEED.as_ref(),
+            distribution_tree.key().as_ref(),
+        ],
+        bump
+    )]
+    pub sol_vault: SystemAccount<'info>,
+
+    /// Fees Wallet
+    #[account(
+        mut,
+        address = Pubkey::from_str(FEES_WALLET).unwrap()
+    )]
+    pub fees_wallet: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct InitializeNativeParams {
+    pub merkle_root: [u8; 32],
+    pub batch_id: String,
+    pub allow_claims: bool,
+    pub total_number_recipients: u64,
+    /// Lamports to distribute, the rent-exempt minimum of the SOL vault is transferred on top
+    pub transfer_to_vault_amount: u64,
+    pub start_ts: i64,
+    pub end_ts: Option<i64>,
+    pub gatekeeper_network: Option<Pubkey>,
+    pub hash_algorithm: Option<HashAlgorithm>,
+}
+
+impl<'info> InitializeNative<'info> {
+    fn transfer_to_vault(&self, amount: u64) -> Result<()> {
+        transfer(
+            CpiContext::new(
+                self.system_program.to_account_info(),
+                Transfer {
+                    from: self.authority.to_account_info(),
+                    to: self.sol_vault.to_account_info(),
+                },
+            ),
+            amount,
+        )
+    }
+
+    fn pay_fees(&self, amount: u64) -> Result<()> {
+        transfer(
+            CpiContext::new(
+                self.system_program.to_account_info(),
+                Transfer {
+                    from: self.authority.to_account_info(),
+                    to: self.fees_wallet.to_account_info(),
+                },
+            ),
+            amount,
+        )
+    }
+}
+
+/// Validates the initialization parameters
+///     1. The start timestamp is before the end timestamp
+///     2. The end timestamps is in the future
+///     3. The total number of recipients is greater than 0
+///     4. The transfer amount is greater than 0
+///     5. The batch_id is between 8 and 15 characters
+pub fn validate(_ctx: &Context<InitializeNative>, params: &InitializeNativeParams) -> Result<()> {
+    let current_ts = Clock::get()?.unix_timestamp;
+    require_gt!(
+        params.end_ts.unwrap_or(i64::MAX),
+        current_ts,
+        DistributionError::TimestampsNotInFuture
+    );
+    require_gt!(
+        params.end_ts.unwrap_or(i64::MAX),
+        params.start_ts,
+        DistributionError::StartTimestampAfterEnd
+    );
+    require_gt!(
+        params.total_number_recipients,
+        0,
+        DistributionError::NoRecipients
+    );
+    require_gt!(
+        params.transfer_to_vault_amount,
+        0,
+        DistributionError::ZeroTransferAmount
+    );
+    require_gte!(
+        BATCH_ID_MAXIMUM_LENGTH,
+        params.batch_id.len(),
+        DistributionError::BatchIdTooLong
+    );
+    require_gte!(
+        params.batch_id.len(),
+        BATCH_ID_MINIMUM_LENGTH,
+        DistributionError::BatchIdTooShort
+    );
+    Ok(())
+}
+
+/// Creates a new native SOL DistributionTree
+///     1. Initializes the DistributionTree PDA with the native mint, and the SOL vault as its vault
+///     2. Transfers the lamports, plus the SOL vault's rent-exempt minimum, from the authority to the SOL vault
+///     3. Pays fees on the lamports, with the fee tiers scaled to SOL's 9 decimals
+pub fn handler(ctx: Context<InitializeNative>, params: InitializeNativeParams) -> Result<()> {
+    let distribution_tree = &mut ctx.accounts.distribution_tree;
+    let authority = &ctx.accounts.authority.key();
+    let bump = ctx.bumps.distribution_tree;
+
+    distribution_tree.initialize(InitializeTreeParams {
+        bump,
+        authority: *authority,
+        batch_id: params.batch_id,
+        allow_claims: params.allow_claims,
+        merkle_root: params.merkle_root,
+        mint: native_mint::ID,
+        token_vault: ctx.accounts.sol_vault.key(),
+        total_number_recipients: params.total_number_recipients,
+        start_ts: params.start_ts,
+        end_ts: params.end_ts,
+        gatekeeper_network: params.gatekeeper_network,
+        hash_algorithm: params.hash_algorithm.unwrap_or(HashAlgorithm::Keccak),
+        // Native trees are fixed amount merkle trees without declines, on a mint without risks
+        ..Default::default()
+    })?;
+
+    let rent_reserve = Rent::get()?
+        .minimum_balance(0)
+        .saturating_sub(ctx.accounts.sol_vault.lamports());
+    let vault_amount = params
+        .transfer_to_vault_amount
+        .checked_add(rent_reserve)
+        .ok_or(DistributionError::MathError)?;
+    ctx.accounts.transfer_to_vault(vault_amount)?;
+
+    let fee_amount = calculate_fee(params.transfer_to_vault_amount, native_mint::DECIMALS)?;
+
+    if fee_amount > 0 {
+        ctx.accounts.pay_fees(fee_amount)?;
+    }
+
+    let distribution_tree = &ctx.accounts.distribution_tree;
+
+    emit!(EpochStarted {
+        distribution_tree: distribution_tree.key(),
+        batch_id: distribution_tree.batch_id.clone(),
+        epoch: distribution_tree.epoch,
+        merkle_root: distribution_tree.merkle_root,
+        total_number_recipients: distribution_tree.total_number_recipients,
+        funded_amount: params.transfer_to_vault_amount,
+        start_ts: distribution_tree.start_ts,
+        end_ts: distribution_tree.end_ts,
+    });
+
+    msg!(
+        "Native SOL distribution tree initialized for {} recipients",
+        params.total_number_recipients
+    );
+
+    Ok(())
+}
@@ -0,0 +1,97 @@
+use anchor_lang::{
+    prelude::*,
+    system_program::{transfer, Transfer},
+};
+
+use crate::{
+    constants::DISTRIBUTION_TREE_SEED, error::DistributionError, state::migrate_distribution_tree,
+};
+
+#[derive(Accounts)]
+#[instruction(params: MigrateParams)]
+pub struct Migrate<'info> {
+    /// Authority of the DistributionTree
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// DistributionTree account (any supported version)
+    /// CHECK: Deserialized in the handler, since older versions do not match the current layout
+    #[account(
+        mut,
+        seeds = [
+            DISTRIBUTION_TREE_SEED.as_ref(),
+            authority.key().as_ref(),
+            params.batch_id.as_bytes(),
+        ],
+        bump,
+        owner = crate::ID,
+    )]
+    pub distribution_tree: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct MigrateParams {
+    pub batch_id: String,
+}
+
+impl<'info> Migrate<'info> {
+    /// Grows the DistributionTree account (if necessary) and tops up rent from the authority
+    fn realloc(&self, new_size: usize) -> Result<()> {
+        let distribution_tree = self.distribution_tree.to_account_info();
+        if new_size <= distribution_tree.data_len() {
+            return Ok(());
+        }
+
+        let required_lamports = Rent::get()?
+            .minimum_balance(new_size)
+            .saturating_sub(distribution_tree.lamports());
+
+        if required_lamports > 0 {
+            transfer(
+                CpiContext::new(
+                    self.system_program.to_account_info(),
+                    Transfer {
+                        from: self.authority.to_account_info(),
+                        to: distribution_tree.clone(),
+                    },
+                ),
+                required_lamports,
+            )?;
+        }
+
+        distribution_tree.realloc(new_size, false)?;
+        Ok(())
+    }
+}
+
+/// Migrates a DistributionTree to the current version
+///     1. Deserializes the tree using the layout of its stored version
+///     2. Fills new fields with defaults that preserve the tree's behavior
+///     3. Reallocates the account for the current layout
+///     4. Writes the tree back with version set to CURRENT_VERSION
+pub fn handler(ctx: Context<Migrate>, _params: MigrateParams) -> Result<()> {
+    let distribution_tree_info = ctx.accounts.distribution_tree.to_account_info();
+    let distribution_tree = migrate_distribution_tree(&distribution_tree_info.try_borrow_data()?)?;
+
+    require_keys_eq!(
+        distribution_tree.authority,
+        ctx.accounts.authority.key(),
+        DistributionError::SignerNotAuthorized
+    );
+
+    ctx.accounts
+        .realloc(distribution_tree.calculate_account_size())?;
+
+    let mut data = distribution_tree_info.try_borrow_mut_data()?;
+    distribution_tree.try_serialize(&mut &mut data[..])?;
+
+    msg!(
+        "Distribution tree {} migrated to version {}",
+        distribution_tree.batch_id,
+        distribution_tree.version
+    );
+
+    Ok(())
+}
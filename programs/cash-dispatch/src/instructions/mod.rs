@@ -4,16 +4,50 @@ pub mod initialize;
 pub mod expand_distribution_tree;
 pub mod distribute;
 pub mod claim;
+pub mod claim_open;
+pub mod claim_link;
 pub mod cancel;
 pub mod pause_unpause;
 pub mod reclaim;
 pub mod close;
+pub mod migrate;
+pub mod new_epoch;
+pub mod top_up;
+pub mod redirect_leaf;
+pub mod revoke;
+pub mod decline;
+pub mod dispute_leaf;
+pub mod resolve_dispute;
+pub mod reject_dispute;
+pub mod delegate_claim;
+pub mod add_mint;
+pub mod initialize_native;
+pub mod distribute_native;
+pub mod claim_native;
+pub mod cancel_native;
 
 pub use initialize::*;
 pub use expand_distribution_tree::*;
 pub use distribute::*;
 pub use claim::*;
+pub use claim_open::*;
+pub use claim_link::*;
 pub use cancel::*;
 pub use pause_unpause::*;
 pub use reclaim::*;
-pub use close::*;
\ No newline at end of file
+pub use close::*;
+pub use migrate::*;
+pub use new_epoch::*;
+pub use top_up::*;
+pub use redirect_leaf::*;
+pub use revoke::*;
+pub use decline::*;
+pub use dispute_leaf::*;
+pub use resolve_dispute::*;
+pub use reject_dispute::*;
+pub use delegate_claim::*;
+pub use add_mint::*;
+pub use initialize_native::*;
+pub use distribute_native::*;
+pub use claim_native::*;
+pub use cancel_native::*;
\ No newline at end of file
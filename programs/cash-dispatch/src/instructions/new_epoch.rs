@@ -0,0 +1,225 @@
+use std::str::FromStr;
+
+use crate::{
+    constants::{CURRENT_VERSION, DISTRIBUTION_TREE_SEED, FEES_WALLET},
+    error::DistributionError,
+    events::EpochStarted,
+    state::DistributionTree,
+    utils::{calculate_fee, gross_up_transfer_fee, require_vault_received, transfer_checked_with_hook},
+};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+#[derive(Accounts)]
+#[instruction(params: NewEpochParams)]
+pub struct NewEpoch<'info> {
+    /// Signer and Authority of the DistributionTree
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// DistributionTree account
+    #[account(
+        mut,
+        seeds = [
+            DISTRIBUTION_TREE_SEED.as_ref(),
+            distribution_tree.authority.as_ref(),
+            params.batch_id.as_bytes(),
+        ],
+        bump = distribution_tree.bump,
+        constraint = distribution_tree.version == CURRENT_VERSION @ DistributionError::UnsupportedVersion,
+        has_one = authority @ DistributionError::SignerNotAuthorized,
+        has_one = mint @ DistributionError::InvalidTokenMint,
+        has_one = token_vault @ DistributionError::InvalidTokenVault,
+        // Resize the bitmap for the new epoch's recipients (open-enrollment trees have no bitmap)
+        // and the total_shares for the new epoch's distribution type
+        realloc = distribution_tree.calculate_next_epoch_account_size(
+            params.total_number_recipients,
+            params.total_shares,
+        ),
+        realloc::payer = authority,
+        realloc::zero = false,
+    )]
+    pub distribution_tree: Account<'info, DistributionTree>,
+
+    /// Mint account (PYUSD)
+    #[account(address = distribution_tree.mint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// Token Source account
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = authority,
+        associated_token::token_program = token_program
+    )]
+    pub token_source: InterfaceAccount<'info, TokenAccount>,
+
+    /// Token Vault account
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = distribution_tree,
+        associated_token::token_program = token_program
+    )]
+    pub token_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// Fees Wallet Token Account
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = Pubkey::from_str(FEES_WALLET).unwrap(),
+        associated_token::token_program = token_program
+    )]
+    pub fees_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// System & Token programs
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct NewEpochParams {
+    pub batch_id: String,
+    pub merkle_root: [u8; 32],
+    pub total_number_recipients: u64,
+    pub transfer_to_vault_amount: u64,
+    pub start_ts: i64,
+    pub end_ts: Option<i64>,
+    pub total_shares: Option<u64>,
+}
+
+impl<'info> NewEpoch<'info> {
+    fn transfer_to_vault(&self, amount: u64, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
+        transfer_checked_with_hook(
+            &self.token_program.to_account_info(),
+            &self.token_source.to_account_info(),
+            &self.mint.to_account_info(),
+            &self.token_vault.to_account_info(),
+            &self.authority.to_account_info(),
+            remaining_accounts,
+            amount,
+            self.mint.decimals,
+            &[],
+        )
+    }
+
+    fn pay_fees(&self, amount: u64, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
+        transfer_checked_with_hook(
+            &self.token_program.to_account_info(),
+            &self.token_source.to_account_info(),
+            &self.mint.to_account_info(),
+            &self.fees_token_account.to_account_info(),
+            &self.authority.to_account_info(),
+            remaining_accounts,
+            amount,
+            self.mint.decimals,
+            &[],
+        )
+    }
+}
+
+/// Validates the new epoch parameters
+///     1. Every recipient of the current epoch has been paid, or the current epoch has ended
+///     2. The end timestamp is in the future
+///     3. The start timestamp is before the end timestamp
+///     4. The total number of recipients is greater than 0
+///     5. The total shares of a pro-rata epoch is greater than 0
+///     6. Open-enrollment trees are not given a merkle root or shares
+pub fn validate(ctx: &Context<NewEpoch>, params: &NewEpochParams) -> Result<()> {
+    let current_ts = Clock::get()?.unix_timestamp;
+    require!(
+        ctx.accounts.distribution_tree.can_start_next_epoch(current_ts),
+        DistributionError::EpochInProgress
+    );
+    require_gt!(
+        params.end_ts.unwrap_or(i64::MAX),
+        current_ts,
+        DistributionError::TimestampsNotInFuture
+    );
+    require_gt!(
+        params.end_ts.unwrap_or(i64::MAX),
+        params.start_ts,
+        DistributionError::StartTimestampAfterEnd
+    );
+    require_gt!(
+        params.total_number_recipients,
+        0,
+        DistributionError::NoRecipients
+    );
+    if let Some(total_shares) = params.total_shares {
+        require_gt!(total_shares, 0, DistributionError::InvalidShares);
+    }
+    if ctx.accounts.distribution_tree.is_open_enrollment() {
+        require!(
+            params.merkle_root == [0u8; 32] && params.total_shares.is_none(),
+            DistributionError::InvalidOpenEnrollment
+        );
+    }
+    Ok(())
+}
+
+/// Starts the next epoch of a recurring distribution
+///     1. Posts the new merkle root and resets the recipients_distributed_bitmap
+///     2. Transfers the epoch's funding (if any) from the token_source to the token_vault, grossed up
+///        by the mint's transfer fee (if any), and checks the vault received transfer_to_vault_amount
+///     3. Pays fees on the new funding
+///     4. Emits an EpochStarted event so past roots remain queryable
+/// Funds left in the vault from previous epochs roll over into the new epoch, including the unpaid
+/// installments of installment leaves that were incomplete when the previous epoch ended
+pub fn handler<'info>(ctx: Context<'_, '_, 'info, 'info, NewEpoch<'info>>, params: NewEpochParams) -> Result<()> {
+    let distribution_tree = &mut ctx.accounts.distribution_tree;
+
+    distribution_tree.start_next_epoch(
+        params.merkle_root,
+        params.total_number_recipients,
+        params.start_ts,
+        params.end_ts,
+        params.total_shares,
+    )?;
+
+    if params.transfer_to_vault_amount > 0 {
+        let vault_balance_before = ctx.accounts.token_vault.amount;
+        let transfer_amount = gross_up_transfer_fee(
+            &ctx.accounts.mint.to_account_info(),
+            params.transfer_to_vault_amount,
+        )?;
+        ctx.accounts
+            .transfer_to_vault(transfer_amount, ctx.remaining_accounts)?;
+        require_vault_received(
+            &mut ctx.accounts.token_vault,
+            vault_balance_before,
+            params.transfer_to_vault_amount,
+        )?;
+
+        let fee_amount = calculate_fee(params.transfer_to_vault_amount, ctx.accounts.mint.decimals)?;
+
+        if fee_amount > 0 {
+            ctx.accounts.pay_fees(fee_amount, ctx.remaining_accounts)?;
+        }
+    }
+
+    let distribution_tree = &ctx.accounts.distribution_tree;
+
+    emit!(EpochStarted {
+        distribution_tree: distribution_tree.key(),
+        batch_id: distribution_tree.batch_id.clone(),
+        epoch: distribution_tree.epoch,
+        merkle_root: distribution_tree.merkle_root,
+        total_number_recipients: distribution_tree.total_number_recipients,
+        funded_amount: params.transfer_to_vault_amount,
+        start_ts: distribution_tree.start_ts,
+        end_ts: distribution_tree.end_ts,
+    });
+
+    msg!(
+        "Epoch {} started for {} recipients",
+        distribution_tree.epoch,
+        distribution_tree.total_number_recipients
+    );
+
+    Ok(())
+}
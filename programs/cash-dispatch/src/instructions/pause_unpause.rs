@@ -1,6 +1,6 @@
 use anchor_lang::prelude::*;
 
-use crate::{constants::DISTRIBUTION_TREE_SEED, error::DistributionError, state::DistributionTree};
+use crate::{constants::{CURRENT_VERSION, DISTRIBUTION_TREE_SEED}, error::DistributionError, state::DistributionTree};
 
 #[derive(Accounts)]
 #[instruction(params: PauseResumeParams)]
@@ -16,6 +16,7 @@ pub struct PauseResume<'info> {
             params.batch_id.as_bytes(),
         ],
         bump = distribution_tree.bump,
+        constraint = distribution_tree.version == CURRENT_VERSION @ DistributionError::UnsupportedVersion,
         has_one = authority @ DistributionError::SignerNotAuthorized
     )]
     pub distribution_tree: Account<'info, DistributionTree>,
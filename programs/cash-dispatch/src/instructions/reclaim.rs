@@ -1,6 +1,6 @@
 use anchor_lang::prelude::*;
 use crate::{
-    constants::DISTRIBUTION_TREE_SEED,
+    constants::{CURRENT_VERSION, DISTRIBUTION_TREE_SEED},
     error::DistributionError,
     state::DistributionTree,
 };
@@ -19,6 +19,7 @@ pub struct Reclaim<'info> {
             params.batch_id.as_bytes(),
         ],
         bump = distribution_tree.bump,
+        constraint = distribution_tree.version == CURRENT_VERSION @ DistributionError::UnsupportedVersion,
         has_one = authority @ DistributionError::SignerNotAuthorized,
         realloc = distribution_tree.calculate_minimum_account_size(),
         realloc::payer = authority,
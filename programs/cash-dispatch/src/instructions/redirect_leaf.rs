@@ -0,0 +1,154 @@
+use crate::{
+    constants::{CURRENT_VERSION, DISTRIBUTION_TREE_SEED, LEAF_REDIRECT_SEED},
+    error::DistributionError,
+    events::LeafRedirected,
+    state::{DistributionStatus, DistributionTree, InstallmentPlan, LeafExtensions, LeafRedirect, LeafSchedule, PayoutSplit},
+};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(params: RedirectLeafParams)]
+pub struct RedirectLeaf<'info> {
+    /// Authority of the DistributionTree, or the key committed in the leaf
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    /// DistributionTree account
+    #[account(
+        seeds = [
+            DISTRIBUTION_TREE_SEED.as_ref(),
+            distribution_tree.authority.as_ref(),
+            params.batch_id.as_bytes(),
+        ],
+        bump = distribution_tree.bump,
+        constraint = distribution_tree.version == CURRENT_VERSION @ DistributionError::UnsupportedVersion,
+    )]
+    pub distribution_tree: Account<'info, DistributionTree>,
+
+    /// LeafRedirect account
+    #[account(
+        init_if_needed,
+        payer = signer,
+        space = 8 + LeafRedirect::INIT_SPACE,
+        seeds = [
+            LEAF_REDIRECT_SEED.as_ref(),
+            distribution_tree.key().as_ref(),
+            params.epoch.to_le_bytes().as_ref(),
+            params.index.to_le_bytes().as_ref(),
+        ],
+        bump
+    )]
+    pub leaf_redirect: Account<'info, LeafRedirect>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct RedirectLeafParams {
+    pub batch_id: String,
+    pub epoch: u64,
+    pub index: u64,
+    /// Key committed in the leaf
+    pub original_recipient: Pubkey,
+    /// Amount committed in the leaf (shares for pro-rata trees)
+    pub amount: u64,
+    pub proof: Vec<[u8; 32]>,
+    pub destination: Pubkey,
+    /// (optional) Destinations committed in a split leaf
+    pub splits: Option<Vec<PayoutSplit>>,
+    /// (optional) Unlock and expiry timestamps committed in a scheduled leaf
+    pub schedule: Option<LeafSchedule>,
+    /// (optional) Installment plan committed in an installment leaf
+    pub installments: Option<InstallmentPlan>,
+    /// (optional) Index of the registered mint committed in the leaf (the tree's mint when omitted)
+    pub mint_index: Option<u8>,
+}
+
+/// Validates the redirect parameters
+///     1. The signer is the authority or the key committed in the leaf
+///     2. An existing redirect can only be changed by the authority
+///     3. The distribution has not been completed or cancelled
+///     4. The epoch is the current epoch
+///     5. The distribution is not open-enrollment
+///     6. The leaf has not been paid
+///     7. The destination is not the default pubkey
+///     8. The proof is valid (including the splits, schedule, installment plan and mint index of an extended leaf)
+pub fn validate(ctx: &Context<RedirectLeaf>, params: &RedirectLeafParams) -> Result<()> {
+    let distribution_tree = &ctx.accounts.distribution_tree;
+    let signer = ctx.accounts.signer.key();
+    let is_authority = signer == distribution_tree.authority;
+    require!(
+        is_authority || signer == params.original_recipient,
+        DistributionError::SignerNotAuthorized
+    );
+    require!(
+        is_authority || ctx.accounts.leaf_redirect.original_recipient == Pubkey::default(),
+        DistributionError::SignerNotAuthorized
+    );
+    require!(
+        !matches!(
+            distribution_tree.status,
+            DistributionStatus::Complete | DistributionStatus::Cancelled
+        ),
+        DistributionError::InvalidDistributionStatus
+    );
+    require_eq!(
+        params.epoch,
+        distribution_tree.epoch,
+        DistributionError::InvalidEpoch
+    );
+    require!(
+        !distribution_tree.is_open_enrollment(),
+        DistributionError::OpenEnrollmentTree
+    );
+    require!(
+        !distribution_tree.is_claimed(params.index)?,
+        DistributionError::AlreadyClaimed
+    );
+    require_keys_neq!(
+        params.destination,
+        Pubkey::default(),
+        DistributionError::InvalidRedirectDestination
+    );
+
+    distribution_tree.verify_extended_proof(
+        params.original_recipient,
+        params.amount,
+        LeafExtensions {
+            splits: params.splits.as_deref(),
+            schedule: params.schedule.as_ref(),
+            installments: params.installments.as_ref(),
+            mint_index: params.mint_index,
+        },
+        &params.proof,
+        params.index,
+    )?;
+
+    Ok(())
+}
+
+/// Records the redirect of an unpaid leaf
+/// `distribute` and `claim` pay the destination instead of the key committed in the leaf
+pub fn handler(ctx: Context<RedirectLeaf>, params: RedirectLeafParams) -> Result<()> {
+    let distribution_tree = ctx.accounts.distribution_tree.key();
+    let leaf_redirect = &mut ctx.accounts.leaf_redirect;
+
+    leaf_redirect.bump = ctx.bumps.leaf_redirect;
+    leaf_redirect.distribution_tree = distribution_tree;
+    leaf_redirect.epoch = params.epoch;
+    leaf_redirect.index = params.index;
+    leaf_redirect.original_recipient = params.original_recipient;
+    leaf_redirect.destination = params.destination;
+
+    emit!(LeafRedirected {
+        distribution_tree,
+        epoch: params.epoch,
+        index: params.index,
+        original_recipient: params.original_recipient,
+        destination: params.destination,
+        signer: ctx.accounts.signer.key(),
+    });
+
+    Ok(())
+}
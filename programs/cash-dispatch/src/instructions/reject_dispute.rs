@@ -0,0 +1,78 @@
+use crate::{
+    constants::{CURRENT_VERSION, DISPUTE_SEED, DISTRIBUTION_TREE_SEED},
+    error::DistributionError,
+    events::DisputeClosed,
+    state::{Dispute, DisputeStatus, DistributionTree},
+};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(params: RejectDisputeParams)]
+pub struct RejectDispute<'info> {
+    /// Authority of the DistributionTree
+    pub authority: Signer<'info>,
+
+    /// DistributionTree account
+    #[account(
+        seeds = [
+            DISTRIBUTION_TREE_SEED.as_ref(),
+            distribution_tree.authority.as_ref(),
+            params.batch_id.as_bytes(),
+        ],
+        bump = distribution_tree.bump,
+        constraint = distribution_tree.version == CURRENT_VERSION @ DistributionError::UnsupportedVersion,
+        has_one = authority @ DistributionError::SignerNotAuthorized,
+    )]
+    pub distribution_tree: Account<'info, DistributionTree>,
+
+    /// Dispute account
+    #[account(
+        mut,
+        seeds = [
+            DISPUTE_SEED.as_ref(),
+            distribution_tree.key().as_ref(),
+            params.epoch.to_le_bytes().as_ref(),
+            params.index.to_le_bytes().as_ref(),
+        ],
+        bump = dispute.bump,
+    )]
+    pub dispute: Account<'info, Dispute>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct RejectDisputeParams {
+    pub batch_id: String,
+    pub epoch: u64,
+    pub index: u64,
+    /// Hash of the supplementary proof backing the rejection
+    pub resolution_hash: [u8; 32],
+}
+
+/// Validates the rejection parameters
+///     1. The dispute is open
+pub fn validate(ctx: &Context<RejectDispute>, _params: &RejectDisputeParams) -> Result<()> {
+    require!(
+        ctx.accounts.dispute.status == DisputeStatus::Open,
+        DistributionError::DisputeNotOpen
+    );
+    Ok(())
+}
+
+/// Rejects a dispute, unfreezing the leaf at its original amount
+pub fn handler(ctx: Context<RejectDispute>, params: RejectDisputeParams) -> Result<()> {
+    let dispute = &mut ctx.accounts.dispute;
+    dispute.status = DisputeStatus::Rejected;
+    dispute.resolution_hash = params.resolution_hash;
+    dispute.closed_ts = Clock::get()?.unix_timestamp;
+
+    emit!(DisputeClosed {
+        distribution_tree: ctx.accounts.distribution_tree.key(),
+        epoch: params.epoch,
+        index: params.index,
+        resolved: false,
+        resolved_amount: 0,
+        resolution_hash: params.resolution_hash,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,206 @@
+use crate::{
+    constants::{CURRENT_VERSION, DISPUTE_SEED, DISTRIBUTION_TREE_SEED},
+    error::DistributionError,
+    events::DisputeClosed,
+    state::{Dispute, DisputeStatus, DistributionStatus, DistributionTree},
+    utils::{gross_up_transfer_fee, transfer_checked_with_hook},
+};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+#[derive(Accounts)]
+#[instruction(params: ResolveDisputeParams)]
+pub struct ResolveDispute<'info> {
+    /// Authority of the DistributionTree
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// DistributionTree account
+    #[account(
+        mut,
+        seeds = [
+            DISTRIBUTION_TREE_SEED.as_ref(),
+            distribution_tree.authority.as_ref(),
+            params.batch_id.as_bytes(),
+        ],
+        bump = distribution_tree.bump,
+        constraint = distribution_tree.version == CURRENT_VERSION @ DistributionError::UnsupportedVersion,
+        has_one = authority @ DistributionError::SignerNotAuthorized,
+        has_one = mint @ DistributionError::InvalidTokenMint,
+        has_one = token_vault @ DistributionError::InvalidTokenVault,
+    )]
+    pub distribution_tree: Account<'info, DistributionTree>,
+
+    /// Dispute account
+    #[account(
+        mut,
+        seeds = [
+            DISPUTE_SEED.as_ref(),
+            distribution_tree.key().as_ref(),
+            params.epoch.to_le_bytes().as_ref(),
+            params.index.to_le_bytes().as_ref(),
+        ],
+        bump = dispute.bump,
+    )]
+    pub dispute: Account<'info, Dispute>,
+
+    /// The recipient that opened the dispute
+    /// CHECK: Validated against the recipient recorded in the dispute
+    #[account(address = dispute.recipient)]
+    pub recipient: AccountInfo<'info>,
+
+    /// Mint account
+    #[account(address = distribution_tree.mint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// Token Vault account
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = distribution_tree,
+        associated_token::token_program = token_program
+    )]
+    pub token_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// Authority's token account, the reserve covering corrections above the leaf amount
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = authority,
+        associated_token::token_program = token_program
+    )]
+    pub token_source: InterfaceAccount<'info, TokenAccount>,
+
+    /// Recipient's token account
+    #[account(
+        init_if_needed,
+        payer = authority,
+        associated_token::mint = mint,
+        associated_token::authority = recipient,
+        associated_token::token_program = token_program
+    )]
+    pub recipient_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// System & Token programs
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct ResolveDisputeParams {
+    pub batch_id: String,
+    pub epoch: u64,
+    pub index: u64,
+    /// Corrected amount paid to the recipient
+    pub corrected_amount: u64,
+    /// Hash of the supplementary proof backing the correction
+    pub resolution_hash: [u8; 32],
+}
+
+impl<'info> ResolveDispute<'info> {
+    fn transfer_from_vault(&self, amount: u64, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
+        let signer_seeds = &[&self.distribution_tree.signer_seeds()[..]];
+
+        transfer_checked_with_hook(
+            &self.token_program.to_account_info(),
+            &self.token_vault.to_account_info(),
+            &self.mint.to_account_info(),
+            &self.recipient_token_account.to_account_info(),
+            &self.distribution_tree.to_account_info(),
+            remaining_accounts,
+            amount,
+            self.mint.decimals,
+            signer_seeds,
+        )
+    }
+
+    fn transfer_from_reserve(&self, amount: u64, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
+        transfer_checked_with_hook(
+            &self.token_program.to_account_info(),
+            &self.token_source.to_account_info(),
+            &self.mint.to_account_info(),
+            &self.recipient_token_account.to_account_info(),
+            &self.authority.to_account_info(),
+            remaining_accounts,
+            amount,
+            self.mint.decimals,
+            &[],
+        )
+    }
+}
+
+/// Validates the resolution parameters
+///     1. The distribution is active
+///     2. The epoch is the current epoch
+///     3. The dispute is open
+///     4. The leaf has not been paid, revoked or declined
+pub fn validate(ctx: &Context<ResolveDispute>, params: &ResolveDisputeParams) -> Result<()> {
+    let distribution_tree = &ctx.accounts.distribution_tree;
+    require!(
+        distribution_tree.status == DistributionStatus::Active,
+        DistributionError::DistributionNotActive
+    );
+    require_eq!(
+        params.epoch,
+        distribution_tree.epoch,
+        DistributionError::InvalidEpoch
+    );
+    require!(
+        ctx.accounts.dispute.status == DisputeStatus::Open,
+        DistributionError::DisputeNotOpen
+    );
+    require!(
+        !distribution_tree.is_claimed(params.index)?,
+        DistributionError::AlreadyClaimed
+    );
+    Ok(())
+}
+
+/// Resolves a dispute by paying the corrected amount to the recipient
+///     1. Marks the leaf as distributed
+///     2. Pays up to the leaf amount from the vault, any shortfall stays in the vault for reclaim
+///     3. Pays the correction above the leaf amount from the authority's reserve
+///     Both transfers are grossed up by the mint's transfer fee, if any, so the recipient receives the corrected amount
+///     4. Closes the dispute as resolved
+pub fn handler<'info>(ctx: Context<'_, '_, 'info, 'info, ResolveDispute<'info>>, params: ResolveDisputeParams) -> Result<()> {
+    let distribution_tree = &mut ctx.accounts.distribution_tree;
+    let leaf_amount = ctx.accounts.dispute.amount;
+
+    distribution_tree.increment_number_distributed()?;
+
+    distribution_tree.set_claimed(params.index)?;
+
+    let vault_amount = params.corrected_amount.min(leaf_amount);
+    let reserve_amount = params.corrected_amount.saturating_sub(leaf_amount);
+
+    let mint = ctx.accounts.mint.to_account_info();
+    if vault_amount > 0 {
+        let transfer_amount = gross_up_transfer_fee(&mint, vault_amount)?;
+        ctx.accounts.transfer_from_vault(transfer_amount, ctx.remaining_accounts)?;
+    }
+    if reserve_amount > 0 {
+        let transfer_amount = gross_up_transfer_fee(&mint, reserve_amount)?;
+        ctx.accounts.transfer_from_reserve(transfer_amount, ctx.remaining_accounts)?;
+    }
+
+    let dispute = &mut ctx.accounts.dispute;
+    dispute.status = DisputeStatus::Resolved;
+    dispute.resolved_amount = params.corrected_amount;
+    dispute.resolution_hash = params.resolution_hash;
+    dispute.closed_ts = Clock::get()?.unix_timestamp;
+
+    emit!(DisputeClosed {
+        distribution_tree: ctx.accounts.distribution_tree.key(),
+        epoch: params.epoch,
+        index: params.index,
+        resolved: true,
+        resolved_amount: params.corrected_amount,
+        resolution_hash: params.resolution_hash,
+    });
+
+    Ok(())
+}
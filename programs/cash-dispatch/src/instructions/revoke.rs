@@ -0,0 +1,229 @@
+use crate::{
+    constants::{CURRENT_VERSION, DISPUTE_SEED, DISTRIBUTION_TREE_SEED, INSTALLMENT_RECORD_SEED, MINT_REGISTRY_SEED},
+    error::DistributionError,
+    events::LeafRevoked,
+    state::{Dispute, DistributionStatus, DistributionTree, InstallmentPlan, InstallmentRecord, LeafExtensions, LeafSchedule, MintRegistry, PayoutSplit},
+    utils::transfer_checked_with_hook,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+#[derive(Accounts)]
+#[instruction(params: RevokeParams)]
+pub struct Revoke<'info> {
+    /// The authority of the DistributionTree
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// DistributionTree account
+    #[account(
+        mut,
+        seeds = [
+            DISTRIBUTION_TREE_SEED.as_ref(),
+            distribution_tree.authority.as_ref(),
+            params.batch_id.as_bytes(),
+        ],
+        bump = distribution_tree.bump,
+        constraint = distribution_tree.version == CURRENT_VERSION @ DistributionError::UnsupportedVersion,
+        has_one = authority @ DistributionError::SignerNotAuthorized,
+    )]
+    pub distribution_tree: Account<'info, DistributionTree>,
+
+    /// Mint account (the tree's mint, or the registered mint committed in the leaf)
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// Token Vault account of the mint
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = distribution_tree,
+        associated_token::token_program = token_program
+    )]
+    pub token_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// Authority's token account
+    #[account(
+        init_if_needed,
+        payer = authority,
+        associated_token::mint = mint,
+        associated_token::authority = authority,
+        associated_token::token_program = token_program
+    )]
+    pub authority_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// MintRegistry PDA of the tree (uninitialized unless additional mints have been registered)
+    /// CHECK: Address verified by seeds, loaded in validate when initialized
+    #[account(
+        seeds = [
+            MINT_REGISTRY_SEED.as_ref(),
+            distribution_tree.key().as_ref(),
+        ],
+        bump
+    )]
+    pub mint_registry: UncheckedAccount<'info>,
+
+    /// Dispute PDA of the leaf (uninitialized unless the leaf has been disputed)
+    /// CHECK: Address verified by seeds, checked in validate when initialized
+    #[account(
+        seeds = [
+            DISPUTE_SEED.as_ref(),
+            distribution_tree.key().as_ref(),
+            params.epoch.to_le_bytes().as_ref(),
+            params.index.to_le_bytes().as_ref(),
+        ],
+        bump
+    )]
+    pub dispute: UncheckedAccount<'info>,
+
+    /// InstallmentRecord PDA of the leaf (uninitialized unless an installment has been paid)
+    /// CHECK: Address verified by seeds, loaded in the handler when initialized
+    #[account(
+        seeds = [
+            INSTALLMENT_RECORD_SEED.as_ref(),
+            distribution_tree.key().as_ref(),
+            params.epoch.to_le_bytes().as_ref(),
+            params.index.to_le_bytes().as_ref(),
+        ],
+        bump
+    )]
+    pub installment_record: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct RevokeParams {
+    pub batch_id: String,
+    pub epoch: u64,
+    pub index: u64,
+    /// Key committed in the leaf
+    pub recipient: Pubkey,
+    /// Amount committed in the leaf (shares for pro-rata trees)
+    pub amount: u64,
+    pub proof: Vec<[u8; 32]>,
+    /// (optional) Destinations committed in a split leaf
+    pub splits: Option<Vec<PayoutSplit>>,
+    /// (optional) Unlock and expiry timestamps committed in a scheduled leaf
+    pub schedule: Option<LeafSchedule>,
+    /// (optional) Installment plan committed in an installment leaf
+    pub installments: Option<InstallmentPlan>,
+    /// (optional) Index of the registered mint committed in the leaf (the tree's mint when omitted)
+    pub mint_index: Option<u8>,
+}
+
+impl<'info> Revoke<'info> {
+    fn transfer_to_authority(&self, amount: u64, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
+        let signer_seeds = &[&self.distribution_tree.signer_seeds()[..]];
+
+        transfer_checked_with_hook(
+            &self.token_program.to_account_info(),
+            &self.token_vault.to_account_info(),
+            &self.mint.to_account_info(),
+            &self.authority_token_account.to_account_info(),
+            &self.distribution_tree.to_account_info(),
+            remaining_accounts,
+            amount,
+            self.mint.decimals,
+            signer_seeds,
+        )
+    }
+}
+
+/// Validates the revoke parameters
+///     1. The distribution is active or paused
+///     2. The epoch is the current epoch
+///     3. The distribution is not open-enrollment
+///     4. The leaf has not been paid or revoked
+///     5. The leaf is not frozen by an open dispute (the authority resolves or rejects it first)
+///     6. The installment plan is valid, on a fixed amount tree (installment leaves)
+///     7. The mint is the tree's mint, or the registered mint at the leaf's mint index (fixed amount trees only)
+///     8. The proof is valid (including the splits, schedule, installment plan and mint index of an extended leaf)
+pub fn validate(ctx: &Context<Revoke>, params: &RevokeParams) -> Result<()> {
+    let distribution_tree = &ctx.accounts.distribution_tree;
+    require!(
+        matches!(
+            distribution_tree.status,
+            DistributionStatus::Active | DistributionStatus::Paused
+        ),
+        DistributionError::InvalidDistributionStatus
+    );
+    require_eq!(
+        params.epoch,
+        distribution_tree.epoch,
+        DistributionError::InvalidEpoch
+    );
+    require!(
+        !distribution_tree.is_open_enrollment(),
+        DistributionError::OpenEnrollmentTree
+    );
+    require!(
+        !distribution_tree.is_claimed(params.index)?,
+        DistributionError::AlreadyClaimed
+    );
+
+    Dispute::require_not_open(&ctx.accounts.dispute.to_account_info())?;
+
+    if let Some(installments) = &params.installments {
+        require!(
+            distribution_tree.total_shares.is_none(),
+            DistributionError::InstallmentsNotSupported
+        );
+        installments.validate(params.amount)?;
+    }
+
+    MintRegistry::require_leaf_mint(
+        &ctx.accounts.mint_registry.to_account_info(),
+        distribution_tree,
+        ctx.accounts.mint.key(),
+        params.mint_index,
+    )?;
+
+    distribution_tree.verify_extended_proof(
+        params.recipient,
+        params.amount,
+        LeafExtensions {
+            splits: params.splits.as_deref(),
+            schedule: params.schedule.as_ref(),
+            installments: params.installments.as_ref(),
+            mint_index: params.mint_index,
+        },
+        &params.proof,
+        params.index,
+    )?;
+
+    Ok(())
+}
+
+/// Revokes an unpaid leaf before payout
+///     1. Marks the leaf in the recipients_distributed_bitmap so `distribute` and `claim` refuse it
+///     2. Claws back the leaf amount to the authority (pro-rata trees release the leaf's shares instead,
+///        clawing back the vault balance once no unpaid shares remain)
+///        Installment leaves only claw back the installments that have not been paid
+pub fn handler<'info>(ctx: Context<'_, '_, 'info, 'info, Revoke<'info>>, params: RevokeParams) -> Result<()> {
+    let unpaid_amount =
+        InstallmentRecord::unpaid_amount(&ctx.accounts.installment_record.to_account_info(), params.amount)?;
+    let distribution_tree = &mut ctx.accounts.distribution_tree;
+
+    let clawback_amount =
+        distribution_tree.revoke(params.index, unpaid_amount, ctx.accounts.token_vault.amount)?;
+
+    if clawback_amount > 0 {
+        ctx.accounts.transfer_to_authority(clawback_amount, ctx.remaining_accounts)?;
+    }
+
+    emit!(LeafRevoked {
+        distribution_tree: ctx.accounts.distribution_tree.key(),
+        epoch: params.epoch,
+        index: params.index,
+        recipient: params.recipient,
+        amount: params.amount,
+        clawback_amount,
+    });
+
+    Ok(())
+}
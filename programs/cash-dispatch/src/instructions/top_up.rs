@@ -0,0 +1,145 @@
+use std::str::FromStr;
+
+use crate::{
+    constants::{CURRENT_VERSION, DISTRIBUTION_TREE_SEED, FEES_WALLET},
+    error::DistributionError,
+    state::{DistributionStatus, DistributionTree},
+    utils::{calculate_fee, gross_up_transfer_fee, require_vault_received, transfer_checked_with_hook},
+};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+#[derive(Accounts)]
+#[instruction(params: TopUpParams)]
+pub struct TopUp<'info> {
+    /// Signer and Authority of the DistributionTree
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// DistributionTree account
+    #[account(
+        seeds = [
+            DISTRIBUTION_TREE_SEED.as_ref(),
+            distribution_tree.authority.as_ref(),
+            params.batch_id.as_bytes(),
+        ],
+        bump = distribution_tree.bump,
+        constraint = distribution_tree.version == CURRENT_VERSION @ DistributionError::UnsupportedVersion,
+        has_one = authority @ DistributionError::SignerNotAuthorized,
+        has_one = mint @ DistributionError::InvalidTokenMint,
+        has_one = token_vault @ DistributionError::InvalidTokenVault,
+    )]
+    pub distribution_tree: Account<'info, DistributionTree>,
+
+    /// Mint account (PYUSD)
+    #[account(address = distribution_tree.mint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// Token Source account
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = authority,
+        associated_token::token_program = token_program
+    )]
+    pub token_source: InterfaceAccount<'info, TokenAccount>,
+
+    /// Token Vault account
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = distribution_tree,
+        associated_token::token_program = token_program
+    )]
+    pub token_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// Fees Wallet Token Account
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = Pubkey::from_str(FEES_WALLET).unwrap(),
+        associated_token::token_program = token_program
+    )]
+    pub fees_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// System & Token programs
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct TopUpParams {
+    pub batch_id: String,
+    pub amount: u64,
+}
+
+impl<'info> TopUp<'info> {
+    fn transfer_to_vault(&self, amount: u64, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
+        transfer_checked_with_hook(
+            &self.token_program.to_account_info(),
+            &self.token_source.to_account_info(),
+            &self.mint.to_account_info(),
+            &self.token_vault.to_account_info(),
+            &self.authority.to_account_info(),
+            remaining_accounts,
+            amount,
+            self.mint.decimals,
+            &[],
+        )
+    }
+
+    fn pay_fees(&self, amount: u64, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
+        transfer_checked_with_hook(
+            &self.token_program.to_account_info(),
+            &self.token_source.to_account_info(),
+            &self.mint.to_account_info(),
+            &self.fees_token_account.to_account_info(),
+            &self.authority.to_account_info(),
+            remaining_accounts,
+            amount,
+            self.mint.decimals,
+            &[],
+        )
+    }
+}
+
+/// Validates the top up parameters
+///     1. The transfer amount is greater than 0
+///     2. The distribution has not been completed or cancelled
+pub fn validate(ctx: &Context<TopUp>, params: &TopUpParams) -> Result<()> {
+    require_gt!(params.amount, 0, DistributionError::ZeroTransferAmount);
+    require!(
+        !matches!(
+            ctx.accounts.distribution_tree.status,
+            DistributionStatus::Complete | DistributionStatus::Cancelled
+        ),
+        DistributionError::InvalidDistributionStatus
+    );
+    Ok(())
+}
+
+/// Adds funds to the token_vault of an existing distribution
+///     1. Transfers the amount from the token_source to the token_vault, grossed up by the mint's
+///        transfer fee (if any), and checks the vault received the amount
+///     2. Pays fees on the amount
+/// On pro-rata trees this raises the payout of every recipient yet to be paid
+pub fn handler<'info>(ctx: Context<'_, '_, 'info, 'info, TopUp<'info>>, params: TopUpParams) -> Result<()> {
+    let vault_balance_before = ctx.accounts.token_vault.amount;
+    let transfer_amount = gross_up_transfer_fee(&ctx.accounts.mint.to_account_info(), params.amount)?;
+    ctx.accounts.transfer_to_vault(transfer_amount, ctx.remaining_accounts)?;
+    require_vault_received(&mut ctx.accounts.token_vault, vault_balance_before, params.amount)?;
+
+    let fee_amount = calculate_fee(params.amount, ctx.accounts.mint.decimals)?;
+
+    if fee_amount > 0 {
+        ctx.accounts.pay_fees(fee_amount, ctx.remaining_accounts)?;
+    }
+
+    msg!("Topped up distribution vault with {}", params.amount);
+
+    Ok(())
+}
@@ -1,5 +1,6 @@
 pub mod constants;
 pub mod error;
+pub mod events;
 pub mod instructions;
 pub mod state;
 pub mod utils;
@@ -7,6 +8,7 @@ pub mod utils;
 use anchor_lang::prelude::*;
 
 pub use constants::*;
+pub use events::*;
 pub use instructions::*;
 pub use state::*;
 
@@ -17,7 +19,7 @@ pub mod cash_dispatch {
     use super::*;
 
     #[access_control(instructions::initialize::validate(&ctx, &params))]
-    pub fn initialize(ctx: Context<Initialize>, params: InitializeParams) -> Result<()> {
+    pub fn initialize<'info>(ctx: Context<'_, '_, 'info, 'info, Initialize<'info>>, params: InitializeParams) -> Result<()> {
         instructions::initialize::handler(ctx, params)
     }
 
@@ -30,17 +32,27 @@ pub mod cash_dispatch {
     }
 
     #[access_control(instructions::distribute::validate(&ctx, &params))]
-    pub fn distribute(ctx: Context<Distribute>, params: DistributeParams) -> Result<()> {
+    pub fn distribute<'info>(ctx: Context<'_, '_, 'info, 'info, Distribute<'info>>, params: DistributeParams) -> Result<()> {
         instructions::distribute::handler(ctx, params)
     }
 
     #[access_control(instructions::claim::validate(&ctx, &params))]
-    pub fn claim(ctx: Context<Claim>, params: ClaimParams) -> Result<()> {
+    pub fn claim<'info>(ctx: Context<'_, '_, 'info, 'info, Claim<'info>>, params: ClaimParams) -> Result<()> {
         instructions::claim::handler(ctx, params)
     }
 
+    #[access_control(instructions::claim_open::validate(&ctx, &params))]
+    pub fn claim_open<'info>(ctx: Context<'_, '_, 'info, 'info, ClaimOpen<'info>>, params: ClaimOpenParams) -> Result<()> {
+        instructions::claim_open::handler(ctx, params)
+    }
+
+    #[access_control(instructions::claim_link::validate(&ctx, &params))]
+    pub fn claim_link<'info>(ctx: Context<'_, '_, 'info, 'info, ClaimLink<'info>>, params: ClaimLinkParams) -> Result<()> {
+        instructions::claim_link::handler(ctx, params)
+    }
+
     #[access_control(instructions::cancel::validate(&ctx, &params))]
-    pub fn cancel(ctx: Context<Cancel>, params: CancelParams) -> Result<()> {
+    pub fn cancel<'info>(ctx: Context<'_, '_, 'info, 'info, Cancel<'info>>, params: CancelParams) -> Result<()> {
         instructions::cancel::handler(ctx, params)
     }
 
@@ -57,7 +69,80 @@ pub mod cash_dispatch {
     }
 
     #[access_control(instructions::close::validate(&ctx, &params))]
-    pub fn close(ctx: Context<Close>, params: CloseParams) -> Result<()> {
+    pub fn close<'info>(ctx: Context<'_, '_, 'info, 'info, Close<'info>>, params: CloseParams) -> Result<()> {
         instructions::close::handler(ctx, params)
     }
+
+    pub fn migrate(ctx: Context<Migrate>, params: MigrateParams) -> Result<()> {
+        instructions::migrate::handler(ctx, params)
+    }
+
+    #[access_control(instructions::new_epoch::validate(&ctx, &params))]
+    pub fn new_epoch<'info>(ctx: Context<'_, '_, 'info, 'info, NewEpoch<'info>>, params: NewEpochParams) -> Result<()> {
+        instructions::new_epoch::handler(ctx, params)
+    }
+
+    #[access_control(instructions::top_up::validate(&ctx, &params))]
+    pub fn top_up<'info>(ctx: Context<'_, '_, 'info, 'info, TopUp<'info>>, params: TopUpParams) -> Result<()> {
+        instructions::top_up::handler(ctx, params)
+    }
+
+    #[access_control(instructions::redirect_leaf::validate(&ctx, &params))]
+    pub fn redirect_leaf(ctx: Context<RedirectLeaf>, params: RedirectLeafParams) -> Result<()> {
+        instructions::redirect_leaf::handler(ctx, params)
+    }
+
+    #[access_control(instructions::revoke::validate(&ctx, &params))]
+    pub fn revoke<'info>(ctx: Context<'_, '_, 'info, 'info, Revoke<'info>>, params: RevokeParams) -> Result<()> {
+        instructions::revoke::handler(ctx, params)
+    }
+
+    #[access_control(instructions::decline::validate(&ctx, &params))]
+    pub fn decline<'info>(ctx: Context<'_, '_, 'info, 'info, Decline<'info>>, params: DeclineParams) -> Result<()> {
+        instructions::decline::handler(ctx, params)
+    }
+
+    #[access_control(instructions::dispute_leaf::validate(&ctx, &params))]
+    pub fn dispute(ctx: Context<DisputeLeaf>, params: DisputeLeafParams) -> Result<()> {
+        instructions::dispute_leaf::handler(ctx, params)
+    }
+
+    #[access_control(instructions::resolve_dispute::validate(&ctx, &params))]
+    pub fn resolve_dispute<'info>(ctx: Context<'_, '_, 'info, 'info, ResolveDispute<'info>>, params: ResolveDisputeParams) -> Result<()> {
+        instructions::resolve_dispute::handler(ctx, params)
+    }
+
+    #[access_control(instructions::reject_dispute::validate(&ctx, &params))]
+    pub fn reject_dispute(ctx: Context<RejectDispute>, params: RejectDisputeParams) -> Result<()> {
+        instructions::reject_dispute::handler(ctx, params)
+    }
+
+    #[access_control(instructions::delegate_claim::validate(&ctx, &params))]
+    pub fn delegate_claim(ctx: Context<DelegateClaim>, params: DelegateClaimParams) -> Result<()> {
+        instructions::delegate_claim::handler(ctx, params)
+    }
+
+    #[access_control(instructions::add_mint::validate(&ctx, &params))]
+    pub fn add_mint<'info>(ctx: Context<'_, '_, 'info, 'info, AddMint<'info>>, params: AddMintParams) -> Result<()> {
+        instructions::add_mint::handler(ctx, params)
+    }
+
+    #[access_control(instructions::initialize_native::validate(&ctx, &params))]
+    pub fn initialize_native(ctx: Context<InitializeNative>, params: InitializeNativeParams) -> Result<()> {
+        instructions::initialize_native::handler(ctx, params)
+    }
+
+    #[access_control(instructions::distribute_native::validate(&ctx, &params))]
+    pub fn distribute_native(ctx: Context<DistributeNative>, params: DistributeNativeParams) -> Result<()> {
+        instructions::distribute_native::handler(ctx, params)
+    }
+
+    #[access_control(instructions::claim_native::validate(&ctx, &params))]
+    pub fn claim_native(ctx: Context<ClaimNative>, params: ClaimNativeParams) -> Result<()> {
+        instructions::claim_native::handler(ctx, params)
+    }
+
+    pub fn cancel_native(ctx: Context<CancelNative>, params: CancelNativeParams) -> Result<()> {
+        instructions::cancel_native::handler(ctx, params)
+    }
 }
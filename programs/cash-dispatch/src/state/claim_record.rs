@@ -0,0 +1,20 @@
+use anchor_lang::prelude::*;
+
+/// Records a claim from an open-enrollment DistributionTree
+/// One exists per claimant per epoch, so a claimant cannot claim twice in the same epoch
+#[account]
+#[derive(InitSpace)]
+pub struct ClaimRecord {
+    /// Bump seed.
+    pub bump: u8,
+    /// The DistributionTree claimed from
+    pub distribution_tree: Pubkey,
+    /// The wallet that claimed
+    pub claimant: Pubkey,
+    /// The epoch claimed in
+    pub epoch: u64,
+    /// The amount claimed
+    pub amount: u64,
+    /// Time of the claim (Unix Timestamp)
+    pub claimed_ts: i64,
+}
@@ -0,0 +1,25 @@
+use anchor_lang::prelude::*;
+
+/// Records that a recipient formally declined their allocation, for legal reporting
+#[account]
+#[derive(InitSpace)]
+pub struct DeclineRecord {
+    /// Bump seed.
+    pub bump: u8,
+    /// The DistributionTree of the leaf
+    pub distribution_tree: Pubkey,
+    /// The epoch of the leaf
+    pub epoch: u64,
+    /// The index of the leaf
+    pub index: u64,
+    /// The recipient that declined
+    pub recipient: Pubkey,
+    /// Amount committed in the leaf (shares for pro-rata trees)
+    pub amount: u64,
+    /// (optional) Wallet the allocation was forwarded to, none if it stayed in the residual pool
+    pub destination: Option<Pubkey>,
+    /// Amount forwarded to the destination
+    pub forwarded_amount: u64,
+    /// Time of the decline (Unix Timestamp)
+    pub declined_ts: i64,
+}
@@ -0,0 +1,51 @@
+use anchor_lang::prelude::*;
+
+use crate::error::DistributionError;
+
+/// Allows a delegate (e.g. a legal representative or guardian) to claim a leaf on behalf of its recipient
+/// Recorded by the recipient, or approved by the authority
+#[account]
+#[derive(InitSpace)]
+pub struct Delegation {
+    /// Bump seed.
+    pub bump: u8,
+    /// The DistributionTree of the leaf
+    pub distribution_tree: Pubkey,
+    /// The epoch of the leaf
+    pub epoch: u64,
+    /// The index of the leaf
+    pub index: u64,
+    /// The wallet paid by the leaf (the key committed in the leaf, or its redirect destination)
+    pub recipient: Pubkey,
+    /// The wallet allowed to claim on behalf of the recipient
+    pub delegate: Pubkey,
+    /// The wallet that receives the payout of a delegated claim (the recipient, or a designated wallet)
+    pub payee: Pubkey,
+    /// The signer that recorded the delegation (the recipient or the authority)
+    pub approved_by: Pubkey,
+}
+
+impl Delegation {
+    /// Loads the Delegation if one has been recorded at this address
+    pub fn load(account: &AccountInfo) -> Result<Option<Delegation>> {
+        if account.owner != &crate::ID || account.data_is_empty() {
+            return Ok(None);
+        }
+        let delegation = Delegation::try_deserialize(&mut &account.data.borrow()[..])?;
+        Ok(Some(delegation))
+    }
+
+    /// Returns the wallet paid by the leaf for a claim, honoring a recorded delegation
+    ///     - The delegate claims for the recipient and must pay the delegation's payee
+    ///     - Anyone else claims for themselves and must pay their own wallet
+    pub fn resolve_claim_recipient(account: &AccountInfo, claimant: Pubkey, payee: Pubkey) -> Result<Pubkey> {
+        if let Some(delegation) = Self::load(account)? {
+            if claimant == delegation.delegate {
+                require_keys_eq!(payee, delegation.payee, DistributionError::InvalidPayee);
+                return Ok(delegation.recipient);
+            }
+        }
+        require_keys_eq!(payee, claimant, DistributionError::InvalidPayee);
+        Ok(claimant)
+    }
+}
@@ -0,0 +1,56 @@
+use anchor_lang::prelude::*;
+
+use crate::error::DistributionError;
+
+/// A recipient's dispute of the amount committed in their leaf
+/// While open, the leaf is frozen and cannot be paid through `distribute`, `claim` or `claim_link`
+#[account]
+#[derive(InitSpace)]
+pub struct Dispute {
+    /// Bump seed.
+    pub bump: u8,
+    /// The DistributionTree of the leaf
+    pub distribution_tree: Pubkey,
+    /// The epoch of the leaf
+    pub epoch: u64,
+    /// The index of the leaf
+    pub index: u64,
+    /// The recipient that opened the dispute (and is paid when it is resolved)
+    pub recipient: Pubkey,
+    /// Amount committed in the leaf
+    pub amount: u64,
+    /// Hash of the recipient's reason for the dispute (kept off-chain)
+    pub reason_hash: [u8; 32],
+    /// The status of the dispute
+    pub status: DisputeStatus,
+    /// Amount paid when the dispute was resolved
+    pub resolved_amount: u64,
+    /// Hash of the supplementary proof backing the authority's decision (kept off-chain)
+    pub resolution_hash: [u8; 32],
+    /// Time the dispute was opened (Unix Timestamp)
+    pub opened_ts: i64,
+    /// Time the dispute was resolved or rejected (Unix Timestamp)
+    pub closed_ts: i64,
+}
+
+impl Dispute {
+    /// Fails if an open Dispute has been recorded at this address
+    pub fn require_not_open(account: &AccountInfo) -> Result<()> {
+        if account.owner != &crate::ID || account.data_is_empty() {
+            return Ok(());
+        }
+        let dispute = Dispute::try_deserialize(&mut &account.data.borrow()[..])?;
+        require!(
+            dispute.status != DisputeStatus::Open,
+            DistributionError::LeafDisputed
+        );
+        Ok(())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum DisputeStatus {
+    Open,
+    Resolved,
+    Rejected,
+}
@@ -1,6 +1,45 @@
-use anchor_lang::{prelude::*, solana_program::keccak::hashv};
+use anchor_lang::prelude::*;
+use anchor_spl::token::spl_token::native_mint;
+
+use crate::{
+    error::DistributionError,
+    state::{InstallmentPlan, InstallmentRecord, LeafSchedule, MintRegistry, MintRisks, PayoutSplit},
+    utils::{gross_up_transfer_fee, hashv, verify},
+    BITMAP_ARRAY_STEP, CURRENT_VERSION,
+    DISTRIBUTION_TREE_SEED, LEAF_HASH_PREFIX, NODE_HASH_PREFIX,
+};
+
+/// Optional terms committed in an extended leaf
+#[derive(Default, Clone, Copy)]
+pub struct LeafExtensions<'a> {
+    pub splits: Option<&'a [PayoutSplit]>,
+    pub schedule: Option<&'a LeafSchedule>,
+    pub installments: Option<&'a InstallmentPlan>,
+    pub mint_index: Option<u8>,
+}
 
-use crate::{error::DistributionError, utils::verify, BITMAP_ARRAY_STEP, CURRENT_VERSION, DISTRIBUTION_TREE_SEED};
+/// Settings recorded on a new Distribution Tree
+/// Token and native trees set the fields they support and default the rest
+#[derive(Default)]
+pub struct InitializeTreeParams {
+    pub bump: u8,
+    pub authority: Pubkey,
+    pub batch_id: String,
+    pub allow_claims: bool,
+    pub merkle_root: [u8; 32],
+    pub mint: Pubkey,
+    pub token_vault: Pubkey,
+    pub total_number_recipients: u64,
+    pub start_ts: i64,
+    /// Defaults to i64::MAX (no end) when omitted
+    pub end_ts: Option<i64>,
+    pub gatekeeper_network: Option<Pubkey>,
+    pub hash_algorithm: HashAlgorithm,
+    pub total_shares: Option<u64>,
+    pub open_claim_amount: Option<u64>,
+    pub decline_destination: Option<Pubkey>,
+    pub mint_risks: MintRisks,
+}
 
 #[account]
 #[derive(InitSpace)]
@@ -41,6 +80,32 @@ pub struct DistributionTree {
     pub end_ts: i64,
     /// (optional) Gateway Network
     pub gatekeeper_network: Option<Pubkey>,
+    /// Hash function used to build the merkle tree
+    pub hash_algorithm: HashAlgorithm,
+    /// Whether leaves and nodes are hashed with domain separation prefixes (false for trees created as v1)
+    pub domain_separated: bool,
+    /// Current epoch (incremented each time a new merkle root is posted for the next payment cycle)
+    pub epoch: u64,
+    /// (optional) Total shares committed by the leaves of a pro-rata tree
+    /// When set, leaves commit to shares instead of absolute amounts
+    pub total_shares: Option<u64>,
+    /// Shares paid out in the current epoch (pro-rata trees only)
+    pub distributed_shares: u64,
+    /// (optional) Fixed amount paid to each claimant of an open-enrollment tree
+    /// When set, there is no merkle list: any holder of a valid gateway token may claim once per epoch,
+    /// up to total_number_recipients claimants
+    pub open_claim_amount: Option<u64>,
+    /// Number of leaves revoked by the authority in the current epoch
+    /// Revoked and declined leaves are marked in the recipients_distributed_bitmap and count towards completion
+    pub number_revoked: u64,
+    /// Number of leaves declined by their recipients in the current epoch
+    pub number_declined: u64,
+    /// (optional) Wallet that receives declined allocations (e.g. the authority or a charity)
+    /// When not set, declined allocations remain in the token_vault as a residual pool
+    pub decline_destination: Option<Pubkey>,
+    /// Risky capabilities of the mint acknowledged by the authority at initialization
+    /// (all false when the mint had none, or for trees migrated from earlier versions)
+    pub mint_risks: MintRisks,
 }
 
 impl DistributionTree {
@@ -64,7 +129,20 @@ impl DistributionTree {
             + 4 // recipients_distributed_bitmap length
             + self.recipients_distributed_bitmap.len() * 8 // each u64 is 8 bytes
             + 1 // Option for gatekeeper network
-            + self.gatekeeper_network.map_or(0, |_| 32);
+            + self.gatekeeper_network.map_or(0, |_| 32)
+            + 1 // hash_algorithm (enum)
+            + 1 // domain_separated
+            + 8 // epoch
+            + 1 // Option for total_shares
+            + self.total_shares.map_or(0, |_| 8)
+            + 8 // distributed_shares
+            + 1 // Option for open_claim_amount
+            + self.open_claim_amount.map_or(0, |_| 8)
+            + 8 // number_revoked
+            + 8 // number_declined
+            + 1 // Option for decline_destination
+            + self.decline_destination.map_or(0, |_| 32)
+            + 3; // mint_risks
         size
     }
 
@@ -73,37 +151,50 @@ impl DistributionTree {
         self.calculate_account_size() - (self.recipients_distributed_bitmap.len() * 8)
     }
 
-    /// Initializes the Distribution Tree
-    pub fn initialize(
-        &mut self,
-        bump: u8,
-        authority: Pubkey,
-        batch_id: String,
-        allow_claims: bool,
-        merkle_root: [u8; 32],
-        mint: Pubkey,
-        token_vault: Pubkey,
+    /// Calculates the account size required by the next epoch, whose bitmap is allocated for
+    /// `total_number_recipients` and whose total_shares may be set or cleared
+    pub fn calculate_next_epoch_account_size(
+        &self,
         total_number_recipients: u64,
-        start_ts: i64,
-        end_ts: Option<i64>,
-        gatekeeper_network: Option<Pubkey>,
-    ) -> Result<()> {
-        let end_ts = end_ts.unwrap_or(i64::MAX);
-        self.bump = bump;
+        total_shares: Option<u64>,
+    ) -> usize {
+        let bitmap_size = if self.is_open_enrollment() {
+            0
+        } else {
+            Self::calculate_initial_vec_size(total_number_recipients) * 8
+        };
+        self.calculate_minimum_account_size() - self.total_shares.map_or(0, |_| 8)
+            + total_shares.map_or(0, |_| 8)
+            + bitmap_size
+    }
+
+    /// Initializes the Distribution Tree
+    pub fn initialize(&mut self, params: InitializeTreeParams) -> Result<()> {
+        self.bump = params.bump;
         self.version = CURRENT_VERSION;
-        self.authority = authority;
-        self.batch_id = batch_id;
+        self.authority = params.authority;
+        self.batch_id = params.batch_id;
         self.status = DistributionStatus::Active;
-        self.allow_claims = allow_claims;
-        self.merkle_root = merkle_root;
-        self.mint = mint;
-        self.token_vault = token_vault;
-        self.total_number_recipients = total_number_recipients;
+        self.allow_claims = params.allow_claims;
+        self.merkle_root = params.merkle_root;
+        self.mint = params.mint;
+        self.token_vault = params.token_vault;
+        self.total_number_recipients = params.total_number_recipients;
         self.number_distributed = 0;
-        self.start_ts = start_ts;
-        self.end_ts = end_ts;
+        self.start_ts = params.start_ts;
+        self.end_ts = params.end_ts.unwrap_or(i64::MAX);
+        self.open_claim_amount = params.open_claim_amount;
         self.initialize_recipients_distributed_bitmap()?;
-        self.gatekeeper_network = gatekeeper_network;
+        self.gatekeeper_network = params.gatekeeper_network;
+        self.hash_algorithm = params.hash_algorithm;
+        self.domain_separated = true;
+        self.epoch = 0;
+        self.total_shares = params.total_shares;
+        self.distributed_shares = 0;
+        self.number_revoked = 0;
+        self.number_declined = 0;
+        self.decline_destination = params.decline_destination;
+        self.mint_risks = params.mint_risks;
         Ok(())
     }
 
@@ -117,13 +208,74 @@ impl DistributionTree {
         ]
     }
 
+    /// Starts the next epoch of the distribution
+    ///     1. Increments the epoch
+    ///     2. Replaces the merkle root, recipients and timestamps
+    ///     3. Resets the number distributed and the recipients_distributed_bitmap
+    pub fn start_next_epoch(
+        &mut self,
+        merkle_root: [u8; 32],
+        total_number_recipients: u64,
+        start_ts: i64,
+        end_ts: Option<i64>,
+        total_shares: Option<u64>,
+    ) -> Result<()> {
+        self.epoch = self
+            .epoch
+            .checked_add(1)
+            .ok_or(DistributionError::MathError)?;
+        self.merkle_root = merkle_root;
+        self.total_number_recipients = total_number_recipients;
+        self.number_distributed = 0;
+        self.start_ts = start_ts;
+        self.end_ts = end_ts.unwrap_or(i64::MAX);
+        self.total_shares = total_shares;
+        self.distributed_shares = 0;
+        self.number_revoked = 0;
+        self.number_declined = 0;
+        self.initialize_recipients_distributed_bitmap()?;
+        Ok(())
+    }
+
+    /// A new epoch can start once every recipient has been paid or the current epoch has ended
+    pub fn can_start_next_epoch(&self, current_ts: i64) -> bool {
+        self.status == DistributionStatus::Complete
+            || (self.status == DistributionStatus::Active && current_ts > self.end_ts)
+    }
+
+    /// Whether the tree pays a fixed amount to any gateway token holder rather than a merkle list
+    pub fn is_open_enrollment(&self) -> bool {
+        self.open_claim_amount.is_some()
+    }
+
+    /// Whether the tree distributes native SOL from its SOL vault rather than SPL tokens
+    pub fn is_native(&self) -> bool {
+        self.mint == native_mint::ID
+    }
+
+    /// Calculates the length of the recipients_distributed_bitmap vector allocated up front for `total_number_recipients`
+    pub fn calculate_initial_vec_size(total_number_recipients: u64) -> usize {
+        Self::calculate_vec_size(total_number_recipients).min(BITMAP_ARRAY_STEP)
+    }
+
+    fn calculate_vec_size(total_number_recipients: u64) -> usize {
+        ((total_number_recipients + 63) / 64) as usize
+    }
+
     /// Calculates the required size of the recipients_distributed_bitmap vector
     fn calculate_required_vec_size(&self) -> usize {
-        ((self.total_number_recipients + 63) / 64) as usize
+        Self::calculate_vec_size(self.total_number_recipients)
     }
 
     /// Initializes the recipients_distributed_bitmap bitmap
+    /// Open-enrollment trees track claimants with ClaimRecord PDAs and do not need a bitmap
     fn initialize_recipients_distributed_bitmap(&mut self) -> Result<()> {
+        if self.is_open_enrollment() {
+            self.recipients_distributed_bitmap = vec![0u64; 0];
+            self.status = DistributionStatus::Active;
+            return Ok(());
+        }
+
         let vec_size = self.calculate_required_vec_size();
 
         if vec_size <= BITMAP_ARRAY_STEP {
@@ -197,11 +349,104 @@ impl DistributionTree {
 
         require_gte!(
             self.total_number_recipients,
-            self.number_distributed,
+            self.number_settled()?,
             DistributionError::DistributionAlreadyComplete
         );
 
-        if self.is_complete() {
+        if self.is_complete()? {
+            self.status = DistributionStatus::Complete;
+        }
+
+        Ok(())
+    }
+
+    /// Pays every overdue installment of an installment leaf
+    /// The leaf is only marked as distributed once its last installment has been paid
+    /// Returns the payout
+    pub fn settle_installments(
+        &mut self,
+        index: u64,
+        installment_record: &mut InstallmentRecord,
+        current_ts: i64,
+    ) -> Result<u64> {
+        let installments = installment_record.pay_overdue(current_ts)?;
+        if installment_record.is_complete() {
+            self.increment_number_distributed()?;
+            self.set_claimed(index)?;
+        }
+        installment_record
+            .amount_per_installment
+            .checked_mul(installments as u64)
+            .ok_or(DistributionError::MathError.into())
+    }
+
+    /// Revokes an unpaid leaf, marking it in the recipients_distributed_bitmap so it can no longer be paid
+    /// Returns the amount to claw back from the token_vault: the leaf amount for fixed amount trees,
+    /// nothing for pro-rata trees, where the leaf's shares are removed so the remaining recipients split the vault,
+    /// unless it was the last unpaid leaf, in which case the whole vault balance is clawed back
+    pub fn revoke(&mut self, index: u64, leaf_amount: u64, vault_balance: u64) -> Result<u64> {
+        let clawback_amount = match self.total_shares {
+            Some(_) => {
+                self.release_shares(leaf_amount)?;
+                if self.total_shares == Some(self.distributed_shares) {
+                    vault_balance
+                } else {
+                    0
+                }
+            }
+            None => leaf_amount,
+        };
+
+        self.number_revoked = self
+            .number_revoked
+            .checked_add(1)
+            .ok_or(DistributionError::MathError)?;
+        self.settle_without_payment(index)?;
+
+        Ok(clawback_amount)
+    }
+
+    /// Declines a leaf on behalf of its recipient, marking it in the recipients_distributed_bitmap
+    /// Returns the amount to forward to the decline_destination. Without a decline_destination the
+    /// allocation stays in the token_vault as a residual pool (pro-rata trees release the leaf's shares)
+    pub fn decline(&mut self, index: u64, leaf_amount: u64, vault_balance: u64) -> Result<u64> {
+        let forwarded_amount = match self.decline_destination {
+            Some(_) => self.settle_payout(leaf_amount, vault_balance)?,
+            None => {
+                self.release_shares(leaf_amount)?;
+                0
+            }
+        };
+
+        self.number_declined = self
+            .number_declined
+            .checked_add(1)
+            .ok_or(DistributionError::MathError)?;
+        self.settle_without_payment(index)?;
+
+        Ok(forwarded_amount)
+    }
+
+    /// Removes a leaf's shares from total_shares so the remaining recipients split the vault (pro-rata trees only)
+    fn release_shares(&mut self, leaf_amount: u64) -> Result<()> {
+        if let Some(total_shares) = self.total_shares {
+            let remaining_shares = total_shares
+                .checked_sub(self.distributed_shares)
+                .ok_or(DistributionError::MathError)?;
+            require!(
+                leaf_amount > 0 && leaf_amount <= remaining_shares,
+                DistributionError::InvalidShares
+            );
+            self.total_shares = Some(total_shares - leaf_amount);
+        }
+        Ok(())
+    }
+
+    /// Marks a leaf as settled without paying its recipient
+    fn settle_without_payment(&mut self, index: u64) -> Result<()> {
+        self.set_claimed(index)?;
+
+        if self.is_complete()? {
             self.status = DistributionStatus::Complete;
         }
 
@@ -252,19 +497,194 @@ impl DistributionTree {
         proof: &Vec<[u8; 32]>,
         index: u64,
     ) -> Result<()> {
-        let leaf = self.get_leaf(recipient, amount, index);
-        let proof_is_valid = verify(proof, self.merkle_root, leaf);
+        let leaf = self.get_leaf(recipient, amount, index)?;
+        self.verify_leaf(leaf, proof)
+    }
+
+    /// Verifies the proof of a leaf, which may extend the standard leaf with its payout splits,
+    /// schedule, installment plan and/or mint index
+    pub fn verify_extended_proof(
+        &self,
+        recipient: Pubkey,
+        amount: u64,
+        leaf_extensions: LeafExtensions,
+        proof: &Vec<[u8; 32]>,
+        index: u64,
+    ) -> Result<()> {
+        let mut extensions = Vec::with_capacity(4);
+        if let Some(splits) = leaf_extensions.splits {
+            extensions.push(PayoutSplit::hash_splits(self.hash_algorithm, splits)?);
+        }
+        if let Some(schedule) = leaf_extensions.schedule {
+            extensions.push(schedule.hash(self.hash_algorithm)?);
+        }
+        if let Some(installments) = leaf_extensions.installments {
+            extensions.push(installments.hash(self.hash_algorithm)?);
+        }
+        if let Some(mint_index) = leaf_extensions.mint_index {
+            extensions.push(MintRegistry::hash_mint_index(self.hash_algorithm, mint_index)?);
+        }
+
+        let leaf = if extensions.is_empty() {
+            self.get_leaf(recipient, amount, index)?
+        } else {
+            self.get_extended_leaf(recipient, amount, &extensions, index)?
+        };
+        self.verify_leaf(leaf, proof)
+    }
+
+    fn verify_leaf(&self, leaf: [u8; 32], proof: &Vec<[u8; 32]>) -> Result<()> {
+        let (_, node_prefix) = self.hash_prefixes();
+        let proof_is_valid = verify(proof, self.merkle_root, leaf, node_prefix, self.hash_algorithm)?;
         require!(proof_is_valid, DistributionError::InvalidProof);
         Ok(())
     }
 
-    fn get_leaf(&self, recipient: Pubkey, amount: u64, index: u64) -> [u8; 32] {
-        hashv(&[
-            &index.to_le_bytes(),
-            &recipient.to_bytes(),
-            &amount.to_le_bytes(),
-        ])
-        .0
+    fn get_leaf(&self, recipient: Pubkey, amount: u64, index: u64) -> Result<[u8; 32]> {
+        Self::hash_leaf(self.hash_algorithm, self.domain_separated, recipient, amount, index)
+    }
+
+    /// Hashes a standard leaf of a tree with the given hash algorithm and domain separation
+    /// Off-chain tree builders use it to produce leaves the program verifies
+    pub fn hash_leaf(
+        hash_algorithm: HashAlgorithm,
+        domain_separated: bool,
+        recipient: Pubkey,
+        amount: u64,
+        index: u64,
+    ) -> Result<[u8; 32]> {
+        let (leaf_prefix, _) = Self::prefixes(domain_separated);
+        let recipient = recipient.to_bytes();
+        match hash_algorithm {
+            // Poseidon inputs are big-endian field elements, so the pubkey is split in two
+            // halves to guarantee each input is below the BN254 modulus
+            HashAlgorithm::Poseidon => hashv(
+                hash_algorithm,
+                &[
+                    leaf_prefix,
+                    &index.to_be_bytes(),
+                    &recipient[..16],
+                    &recipient[16..],
+                    &amount.to_be_bytes(),
+                ],
+            ),
+            HashAlgorithm::Keccak | HashAlgorithm::Sha256 => hashv(
+                hash_algorithm,
+                &[
+                    leaf_prefix,
+                    &index.to_le_bytes(),
+                    &recipient,
+                    &amount.to_le_bytes(),
+                ],
+            ),
+        }
+    }
+
+    /// An extended leaf appends the hash of each extension to the leaf pre-image, in order:
+    /// the splits hash of a split leaf, the schedule hash of a scheduled leaf, the
+    /// installment plan hash of an installment leaf, then the mint index hash of a leaf paying
+    /// a registered mint
+    /// The Poseidon syscall takes at most 12 inputs, so Poseidon leaves append a single element instead,
+    /// the hash of the extension hashes (split in halves): H(prefix, index, recipient, amount, H(extensions))
+    /// The longer pre-image can't collide with a standard leaf or an internal node
+    fn get_extended_leaf(
+        &self,
+        recipient: Pubkey,
+        amount: u64,
+        extensions: &[[u8; 32]],
+        index: u64,
+    ) -> Result<[u8; 32]> {
+        let (leaf_prefix, _) = self.hash_prefixes();
+        let recipient = recipient.to_bytes();
+        match self.hash_algorithm {
+            HashAlgorithm::Poseidon => {
+                let index = index.to_be_bytes();
+                let amount = amount.to_be_bytes();
+                let extension_halves: Vec<&[u8]> = extensions
+                    .iter()
+                    .flat_map(|extension| [&extension[..16], &extension[16..]])
+                    .collect();
+                let extensions_hash = hashv(self.hash_algorithm, &extension_halves)?;
+                hashv(
+                    self.hash_algorithm,
+                    &[
+                        leaf_prefix,
+                        &index,
+                        &recipient[..16],
+                        &recipient[16..],
+                        &amount,
+                        &extensions_hash,
+                    ],
+                )
+            }
+            HashAlgorithm::Keccak | HashAlgorithm::Sha256 => {
+                let index = index.to_le_bytes();
+                let amount = amount.to_le_bytes();
+                let mut vals: Vec<&[u8]> = vec![leaf_prefix, &index, &recipient, &amount];
+                for extension in extensions {
+                    vals.push(extension);
+                }
+                hashv(self.hash_algorithm, &vals)
+            }
+        }
+    }
+
+    /// Returns the (leaf, node) hash prefixes for this tree
+    /// Trees created as v1 were built without domain separation, so both prefixes are empty
+    fn hash_prefixes(&self) -> (&'static [u8], &'static [u8]) {
+        Self::prefixes(self.domain_separated)
+    }
+
+    /// Returns the (leaf, node) hash prefixes of a tree with or without domain separation
+    pub fn prefixes(domain_separated: bool) -> (&'static [u8], &'static [u8]) {
+        if domain_separated {
+            (LEAF_HASH_PREFIX, NODE_HASH_PREFIX)
+        } else {
+            (&[], &[])
+        }
+    }
+
+    /// Calculates the payout for a leaf and records it against the tree
+    /// Fixed amount trees pay the amount committed in the leaf. Pro-rata trees pay the leaf's
+    /// share of what remains in the vault, rounded down:
+    ///     payout = vault_balance * shares / (total_shares - distributed_shares)
+    /// so top-ups raise the payout of every unpaid recipient, and the final recipient
+    /// receives everything left in the vault (dust sweep)
+    pub fn settle_payout(&mut self, leaf_amount: u64, vault_balance: u64) -> Result<u64> {
+        let Some(total_shares) = self.total_shares else {
+            return Ok(leaf_amount);
+        };
+
+        let remaining_shares = total_shares
+            .checked_sub(self.distributed_shares)
+            .ok_or(DistributionError::MathError)?;
+        require!(
+            leaf_amount > 0 && leaf_amount <= remaining_shares,
+            DistributionError::InvalidShares
+        );
+
+        let payout = (vault_balance as u128)
+            .checked_mul(leaf_amount as u128)
+            .ok_or(DistributionError::MathError)?
+            .checked_div(remaining_shares as u128)
+            .ok_or(DistributionError::MathError)?;
+
+        self.distributed_shares = self
+            .distributed_shares
+            .checked_add(leaf_amount)
+            .ok_or(DistributionError::MathError)?;
+
+        u64::try_from(payout).map_err(|_| DistributionError::MathError.into())
+    }
+
+    /// Amount sent from the token_vault to pay `payout`
+    /// Fixed amount payouts are grossed up by the mint's transfer fee so the recipient receives the full
+    /// amount, while pro-rata payouts are shares of the vault and bear the fee
+    pub fn payout_transfer_amount(&self, mint: &AccountInfo, payout: u64) -> Result<u64> {
+        match self.total_shares {
+            Some(_) => Ok(payout),
+            None => gross_up_transfer_fee(mint, payout),
+        }
     }
 
     pub fn pause(&mut self) -> Result<()> {
@@ -289,9 +709,25 @@ impl DistributionTree {
         self.status = DistributionStatus::Cancelled;
     }
 
-    fn is_complete(&self) -> bool {
-        self.number_distributed == self.total_number_recipients
+    /// Number of leaves paid, revoked or declined in the current epoch
+    fn number_settled(&self) -> Result<u64> {
+        self.number_distributed
+            .checked_add(self.number_revoked)
+            .and_then(|settled| settled.checked_add(self.number_declined))
+            .ok_or(DistributionError::MathError.into())
     }
+
+    fn is_complete(&self) -> Result<bool> {
+        Ok(self.number_settled()? == self.total_number_recipients)
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq, InitSpace)]
+pub enum HashAlgorithm {
+    #[default]
+    Keccak,
+    Sha256,
+    Poseidon,
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace)]
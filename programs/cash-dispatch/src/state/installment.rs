@@ -0,0 +1,189 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    error::DistributionError,
+    state::{HashAlgorithm, LeafSchedule},
+    utils::hashv,
+};
+
+/// Installment terms committed in an installment leaf
+/// The leaf amount is paid as `count` installments of `amount_per_installment`, one every `interval` seconds
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct InstallmentPlan {
+    pub amount_per_installment: u64,
+    pub count: u16,
+    /// Seconds between installments
+    pub interval: i64,
+}
+
+impl InstallmentPlan {
+    /// Validates that the plan has a non-zero amount, count and interval, and that its
+    /// installments add up to the leaf amount
+    pub fn validate(&self, leaf_amount: u64) -> Result<()> {
+        require!(
+            self.amount_per_installment > 0 && self.count > 0 && self.interval > 0,
+            DistributionError::InvalidInstallmentPlan
+        );
+        require!(
+            self.amount_per_installment.checked_mul(self.count as u64) == Some(leaf_amount),
+            DistributionError::InvalidInstallmentPlan
+        );
+        Ok(())
+    }
+
+    /// Hashes the plan into the commitment included in an installment leaf
+    ///     hash = H(amount_per_installment || count || interval)
+    /// Poseidon inputs are big-endian field elements, Keccak and Sha256 use little-endian bytes
+    pub fn hash(&self, hash_algorithm: HashAlgorithm) -> Result<[u8; 32]> {
+        match hash_algorithm {
+            HashAlgorithm::Poseidon => hashv(
+                hash_algorithm,
+                &[
+                    &self.amount_per_installment.to_be_bytes(),
+                    &self.count.to_be_bytes(),
+                    &self.interval.to_be_bytes(),
+                ],
+            ),
+            HashAlgorithm::Keccak | HashAlgorithm::Sha256 => hashv(
+                hash_algorithm,
+                &[
+                    &self.amount_per_installment.to_le_bytes(),
+                    &self.count.to_le_bytes(),
+                    &self.interval.to_le_bytes(),
+                ],
+            ),
+        }
+    }
+
+    /// Validates that the last installment, due `(count - 1) * interval` after the first, falls on or
+    /// before the end of the distribution and the expiry of a scheduled leaf, so every installment can be paid
+    pub fn validate_due_dates(&self, start_ts: i64, end_ts: i64, schedule: Option<&LeafSchedule>) -> Result<()> {
+        let first_due_ts = Self::first_due_ts(start_ts, schedule);
+        let last_due_ts = (self.count as i64 - 1)
+            .checked_mul(self.interval)
+            .and_then(|offset| first_due_ts.checked_add(offset))
+            .ok_or(DistributionError::InstallmentsPastEnd)?;
+        let end_ts = schedule.map_or(end_ts, |schedule| schedule.expiry_ts.min(end_ts));
+        require_gte!(end_ts, last_due_ts, DistributionError::InstallmentsPastEnd);
+        Ok(())
+    }
+
+    /// The first installment is due when the leaf unlocks, or when the distribution starts
+    pub fn first_due_ts(start_ts: i64, schedule: Option<&LeafSchedule>) -> i64 {
+        schedule.map_or(start_ts, |schedule| schedule.unlock_ts)
+    }
+
+    /// Number of installments due at current_ts, the first being due at first_due_ts
+    pub fn installments_due(&self, first_due_ts: i64, current_ts: i64) -> u16 {
+        if current_ts < first_due_ts {
+            return 0;
+        }
+        let elapsed_intervals = (current_ts - first_due_ts) / self.interval;
+        elapsed_intervals
+            .saturating_add(1)
+            .min(self.count as i64) as u16
+    }
+}
+
+/// Tracks the installments paid on an installment leaf
+/// One exists per leaf per epoch. Monitoring tools compare `installments_paid` with the
+/// installments due to find overdue installments
+#[account]
+#[derive(InitSpace)]
+pub struct InstallmentRecord {
+    /// Bump seed.
+    pub bump: u8,
+    /// The DistributionTree of the leaf
+    pub distribution_tree: Pubkey,
+    /// The epoch of the leaf
+    pub epoch: u64,
+    /// The index of the leaf
+    pub index: u64,
+    pub amount_per_installment: u64,
+    pub count: u16,
+    pub interval: i64,
+    /// Time the first installment is due (Unix Timestamp)
+    pub first_due_ts: i64,
+    /// Number of installments paid
+    pub installments_paid: u16,
+    /// Time of the last payment (Unix Timestamp)
+    pub last_paid_ts: i64,
+}
+
+impl InstallmentRecord {
+    /// Loads the InstallmentRecord if the leaf's first installment has been paid
+    pub fn load(account: &AccountInfo) -> Result<Option<InstallmentRecord>> {
+        if account.owner != &crate::ID || account.data_is_empty() {
+            return Ok(None);
+        }
+        let installment_record = InstallmentRecord::try_deserialize(&mut &account.data.borrow()[..])?;
+        Ok(Some(installment_record))
+    }
+
+    /// Amount of the leaf left to pay, after the installments recorded at this address (if any)
+    pub fn unpaid_amount(account: &AccountInfo, leaf_amount: u64) -> Result<u64> {
+        let Some(installment_record) = Self::load(account)? else {
+            return Ok(leaf_amount);
+        };
+        let paid_amount = installment_record
+            .amount_per_installment
+            .checked_mul(installment_record.installments_paid as u64)
+            .ok_or(DistributionError::MathError)?;
+        leaf_amount
+            .checked_sub(paid_amount)
+            .ok_or(DistributionError::MathError.into())
+    }
+
+    /// Records the plan of the leaf on its first payment
+    pub fn initialize(
+        &mut self,
+        bump: u8,
+        distribution_tree: Pubkey,
+        epoch: u64,
+        index: u64,
+        plan: &InstallmentPlan,
+        first_due_ts: i64,
+    ) {
+        self.bump = bump;
+        self.distribution_tree = distribution_tree;
+        self.epoch = epoch;
+        self.index = index;
+        self.amount_per_installment = plan.amount_per_installment;
+        self.count = plan.count;
+        self.interval = plan.interval;
+        self.first_due_ts = first_due_ts;
+    }
+
+    pub fn plan(&self) -> InstallmentPlan {
+        InstallmentPlan {
+            amount_per_installment: self.amount_per_installment,
+            count: self.count,
+            interval: self.interval,
+        }
+    }
+
+    /// Number of installments due at current_ts that have not been paid
+    pub fn installments_overdue(&self, current_ts: i64) -> u16 {
+        self.plan()
+            .installments_due(self.first_due_ts, current_ts)
+            .saturating_sub(self.installments_paid)
+    }
+
+    /// Whether every installment has been paid
+    pub fn is_complete(&self) -> bool {
+        self.installments_paid == self.count
+    }
+
+    /// Records the payment of every overdue installment
+    /// Returns the number of installments paid
+    pub fn pay_overdue(&mut self, current_ts: i64) -> Result<u16> {
+        let installments = self.installments_overdue(current_ts);
+        require!(installments > 0, DistributionError::NoInstallmentsDue);
+        self.installments_paid = self
+            .installments_paid
+            .checked_add(installments)
+            .ok_or(DistributionError::MathError)?;
+        self.last_paid_ts = current_ts;
+        Ok(installments)
+    }
+}
@@ -0,0 +1,61 @@
+use anchor_lang::prelude::*;
+
+use crate::error::DistributionError;
+
+/// Redirects the payout of an unpaid leaf to a new destination
+/// Recorded by the authority, or by the key committed in the leaf, when that key is lost or rotated
+#[account]
+#[derive(InitSpace)]
+pub struct LeafRedirect {
+    /// Bump seed.
+    pub bump: u8,
+    /// The DistributionTree of the leaf
+    pub distribution_tree: Pubkey,
+    /// The epoch of the leaf
+    pub epoch: u64,
+    /// The index of the leaf
+    pub index: u64,
+    /// The key committed in the leaf
+    pub original_recipient: Pubkey,
+    /// The wallet that receives the payout instead
+    pub destination: Pubkey,
+}
+
+impl LeafRedirect {
+    /// Loads the LeafRedirect if one has been recorded at this address
+    pub fn load(account: &AccountInfo) -> Result<Option<LeafRedirect>> {
+        if account.owner != &crate::ID || account.data_is_empty() {
+            return Ok(None);
+        }
+        let leaf_redirect = LeafRedirect::try_deserialize(&mut &account.data.borrow()[..])?;
+        Ok(Some(leaf_redirect))
+    }
+
+    /// Returns the key committed in the leaf for a payee, honoring a recorded redirect
+    ///     - Without a redirect, the payee must be the key committed in the leaf
+    ///     - With a redirect, the payee must be the redirect destination
+    pub fn resolve_leaf_recipient(account: &AccountInfo, payee: Pubkey) -> Result<Pubkey> {
+        let Some(leaf_redirect) = Self::load(account)? else {
+            return Ok(payee);
+        };
+        require_keys_eq!(
+            payee,
+            leaf_redirect.destination,
+            DistributionError::PayeeNotRedirectDestination
+        );
+        Ok(leaf_redirect.original_recipient)
+    }
+
+    /// Checks the destination of a leaf that pays any wallet (e.g. a claim link) against a recorded redirect
+    /// Once redirected, the leaf can only be paid to the redirect destination
+    pub fn require_redirect_destination(account: &AccountInfo, destination: Pubkey) -> Result<()> {
+        if let Some(leaf_redirect) = Self::load(account)? {
+            require_keys_eq!(
+                destination,
+                leaf_redirect.destination,
+                DistributionError::PayeeNotRedirectDestination
+            );
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,37 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::DistributionError, state::HashAlgorithm, utils::hashv};
+
+/// Unlock and expiry timestamps committed in a scheduled leaf
+/// The leaf can only be paid within [unlock_ts, expiry_ts], in addition to the tree-wide window
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct LeafSchedule {
+    pub unlock_ts: i64,
+    /// i64::MAX for a leaf that never expires
+    pub expiry_ts: i64,
+}
+
+impl LeafSchedule {
+    /// Validates that the leaf has unlocked and has not expired
+    pub fn validate(&self, current_ts: i64) -> Result<()> {
+        require_gte!(current_ts, self.unlock_ts, DistributionError::LeafLocked);
+        require_gte!(self.expiry_ts, current_ts, DistributionError::LeafExpired);
+        Ok(())
+    }
+
+    /// Hashes the schedule into the commitment included in a scheduled leaf
+    ///     hash = H(unlock_ts || expiry_ts)
+    /// Poseidon inputs are big-endian field elements, Keccak and Sha256 use little-endian bytes
+    pub fn hash(&self, hash_algorithm: HashAlgorithm) -> Result<[u8; 32]> {
+        match hash_algorithm {
+            HashAlgorithm::Poseidon => hashv(
+                hash_algorithm,
+                &[&self.unlock_ts.to_be_bytes(), &self.expiry_ts.to_be_bytes()],
+            ),
+            HashAlgorithm::Keccak | HashAlgorithm::Sha256 => hashv(
+                hash_algorithm,
+                &[&self.unlock_ts.to_le_bytes(), &self.expiry_ts.to_le_bytes()],
+            ),
+        }
+    }
+}
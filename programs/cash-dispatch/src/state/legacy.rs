@@ -0,0 +1,202 @@
+use anchor_lang::{prelude::*, Discriminator};
+
+use crate::{
+    error::DistributionError, DistributionStatus, DistributionTree, HashAlgorithm, MintRisks,
+    CURRENT_VERSION,
+};
+
+/// Layout of DistributionTree version 1, the only layout deployed before CURRENT_VERSION
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct DistributionTreeV1 {
+    pub bump: u8,
+    pub version: u64,
+    pub authority: Pubkey,
+    pub batch_id: String,
+    pub recipients_distributed_bitmap: Vec<u64>,
+    pub status: DistributionStatus,
+    pub allow_claims: bool,
+    pub merkle_root: [u8; 32],
+    pub mint: Pubkey,
+    pub token_vault: Pubkey,
+    pub total_number_recipients: u64,
+    pub number_distributed: u64,
+    pub start_ts: i64,
+    pub end_ts: i64,
+    pub gatekeeper_network: Option<Pubkey>,
+}
+
+impl DistributionTreeV1 {
+    /// Upgrades to the current layout, filling new fields with values that preserve the tree's behavior
+    fn into_current(self) -> DistributionTree {
+        DistributionTree {
+            bump: self.bump,
+            version: CURRENT_VERSION,
+            authority: self.authority,
+            batch_id: self.batch_id,
+            recipients_distributed_bitmap: self.recipients_distributed_bitmap,
+            status: self.status,
+            allow_claims: self.allow_claims,
+            merkle_root: self.merkle_root,
+            mint: self.mint,
+            token_vault: self.token_vault,
+            total_number_recipients: self.total_number_recipients,
+            number_distributed: self.number_distributed,
+            start_ts: self.start_ts,
+            end_ts: self.end_ts,
+            gatekeeper_network: self.gatekeeper_network,
+            hash_algorithm: HashAlgorithm::Keccak,
+            domain_separated: false,
+            epoch: 0,
+            total_shares: None,
+            distributed_shares: 0,
+            open_claim_amount: None,
+            number_revoked: 0,
+            number_declined: 0,
+            decline_destination: None,
+            mint_risks: MintRisks::default(),
+        }
+    }
+}
+
+/// Reads the version of a serialized DistributionTree (including discriminator)
+/// The discriminator (8 bytes) and bump (1 byte) precede version in every layout
+pub fn read_version(data: &[u8]) -> Result<u64> {
+    require!(
+        data.len() >= 17 && data[..8] == DistributionTree::DISCRIMINATOR,
+        ErrorCode::AccountDiscriminatorMismatch
+    );
+    Ok(u64::from_le_bytes(data[9..17].try_into().unwrap()))
+}
+
+/// Deserializes an older DistributionTree and upgrades it to CURRENT_VERSION
+pub fn migrate_distribution_tree(data: &[u8]) -> Result<DistributionTree> {
+    let version = read_version(data)?;
+    let mut data = &data[8..];
+
+    match version {
+        1 => Ok(DistributionTreeV1::deserialize(&mut data)?.into_current()),
+        CURRENT_VERSION => err!(DistributionError::AlreadyCurrentVersion),
+        _ => err!(DistributionError::UnsupportedVersion),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AUTHORITY: Pubkey = Pubkey::new_from_array([1; 32]);
+    const MINT: Pubkey = Pubkey::new_from_array([2; 32]);
+    const TOKEN_VAULT: Pubkey = Pubkey::new_from_array([3; 32]);
+    const GATEKEEPER_NETWORK: Pubkey = Pubkey::new_from_array([4; 32]);
+    const MERKLE_ROOT: [u8; 32] = [5; 32];
+
+    /// Serializes a v1 tree byte by byte, as it is stored on chain
+    fn v1_account_data(version: u64, gatekeeper_network: Option<Pubkey>) -> Vec<u8> {
+        let mut data = DistributionTree::DISCRIMINATOR.to_vec();
+        data.push(254); // bump
+        data.extend_from_slice(&version.to_le_bytes());
+        data.extend_from_slice(AUTHORITY.as_ref());
+        data.extend_from_slice(&4u32.to_le_bytes()); // batch_id
+        data.extend_from_slice(b"v1-a");
+        data.extend_from_slice(&2u32.to_le_bytes()); // recipients_distributed_bitmap
+        data.extend_from_slice(&0b1011u64.to_le_bytes());
+        data.extend_from_slice(&u64::MAX.to_le_bytes());
+        data.push(3); // status: Paused
+        data.push(1); // allow_claims
+        data.extend_from_slice(&MERKLE_ROOT);
+        data.extend_from_slice(MINT.as_ref());
+        data.extend_from_slice(TOKEN_VAULT.as_ref());
+        data.extend_from_slice(&70u64.to_le_bytes()); // total_number_recipients
+        data.extend_from_slice(&3u64.to_le_bytes()); // number_distributed
+        data.extend_from_slice(&1_700_000_000i64.to_le_bytes()); // start_ts
+        data.extend_from_slice(&1_800_000_000i64.to_le_bytes()); // end_ts
+        match gatekeeper_network {
+            Some(gatekeeper_network) => {
+                data.push(1);
+                data.extend_from_slice(gatekeeper_network.as_ref());
+            }
+            None => data.push(0),
+        }
+        data
+    }
+
+    /// Migrates the bytes, then reads the tree back from its serialized current layout
+    fn migrate_round_trip(data: &[u8]) -> DistributionTree {
+        let migrated = migrate_distribution_tree(data).unwrap();
+        let mut serialized = Vec::new();
+        migrated.try_serialize(&mut serialized).unwrap();
+        assert!(serialized.len() <= migrated.calculate_account_size());
+        DistributionTree::try_deserialize(&mut serialized.as_slice()).unwrap()
+    }
+
+    fn assert_v1_fields(distribution_tree: &DistributionTree, gatekeeper_network: Option<Pubkey>) {
+        assert_eq!(distribution_tree.bump, 254);
+        assert_eq!(distribution_tree.version, CURRENT_VERSION);
+        assert_eq!(distribution_tree.authority, AUTHORITY);
+        assert_eq!(distribution_tree.batch_id, "v1-a");
+        assert_eq!(distribution_tree.recipients_distributed_bitmap, vec![0b1011, u64::MAX]);
+        assert!(distribution_tree.status == DistributionStatus::Paused);
+        assert!(distribution_tree.allow_claims);
+        assert_eq!(distribution_tree.merkle_root, MERKLE_ROOT);
+        assert_eq!(distribution_tree.mint, MINT);
+        assert_eq!(distribution_tree.token_vault, TOKEN_VAULT);
+        assert_eq!(distribution_tree.total_number_recipients, 70);
+        assert_eq!(distribution_tree.number_distributed, 3);
+        assert_eq!(distribution_tree.start_ts, 1_700_000_000);
+        assert_eq!(distribution_tree.end_ts, 1_800_000_000);
+        assert_eq!(distribution_tree.gatekeeper_network, gatekeeper_network);
+        // Fields added since v1 are filled with values that preserve the tree's behavior
+        assert!(distribution_tree.hash_algorithm == HashAlgorithm::Keccak);
+        assert!(!distribution_tree.domain_separated);
+        assert_eq!(distribution_tree.epoch, 0);
+        assert_eq!(distribution_tree.total_shares, None);
+        assert_eq!(distribution_tree.distributed_shares, 0);
+        assert_eq!(distribution_tree.open_claim_amount, None);
+        assert_eq!(distribution_tree.number_revoked, 0);
+        assert_eq!(distribution_tree.number_declined, 0);
+        assert_eq!(distribution_tree.decline_destination, None);
+        assert!(distribution_tree.mint_risks == MintRisks::default());
+    }
+
+    #[test]
+    fn migrates_v1_tree() {
+        let distribution_tree = migrate_round_trip(&v1_account_data(1, None));
+        assert_v1_fields(&distribution_tree, None);
+    }
+
+    #[test]
+    fn migrates_v1_tree_with_gatekeeper_network() {
+        let distribution_tree = migrate_round_trip(&v1_account_data(1, Some(GATEKEEPER_NETWORK)));
+        assert_v1_fields(&distribution_tree, Some(GATEKEEPER_NETWORK));
+    }
+
+    #[test]
+    fn migrates_v1_tree_with_trailing_account_space() {
+        let mut data = v1_account_data(1, None);
+        data.resize(data.len() + 64, 0);
+        let distribution_tree = migrate_round_trip(&data);
+        assert_v1_fields(&distribution_tree, None);
+    }
+
+    #[test]
+    fn rejects_current_and_unknown_versions() {
+        assert_eq!(
+            migrate_distribution_tree(&v1_account_data(CURRENT_VERSION, None)).err().unwrap(),
+            DistributionError::AlreadyCurrentVersion.into()
+        );
+        assert_eq!(
+            migrate_distribution_tree(&v1_account_data(CURRENT_VERSION + 1, None)).err().unwrap(),
+            DistributionError::UnsupportedVersion.into()
+        );
+    }
+
+    #[test]
+    fn rejects_other_accounts() {
+        let mut data = v1_account_data(1, None);
+        data[0] ^= 1;
+        assert_eq!(
+            migrate_distribution_tree(&data).err().unwrap(),
+            ErrorCode::AccountDiscriminatorMismatch.into()
+        );
+    }
+}
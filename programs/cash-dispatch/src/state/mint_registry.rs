@@ -0,0 +1,71 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::MAX_ADDITIONAL_MINTS,
+    error::DistributionError,
+    state::{DistributionTree, HashAlgorithm, MintRisks},
+    utils::hashv,
+};
+
+/// Registers additional mints paid by a DistributionTree, each from its own vault
+/// (the tree's associated token account for the mint)
+/// Mint index 0 is the tree's own mint, mint index i is `mints[i - 1]`
+#[account]
+#[derive(InitSpace)]
+pub struct MintRegistry {
+    /// Bump seed.
+    pub bump: u8,
+    /// The DistributionTree paying the mints
+    pub distribution_tree: Pubkey,
+    /// The additional mints, in mint index order
+    #[max_len(MAX_ADDITIONAL_MINTS)]
+    pub mints: Vec<Pubkey>,
+    /// The acknowledged risks of each additional mint, in mint index order
+    #[max_len(MAX_ADDITIONAL_MINTS)]
+    pub mint_risks: Vec<MintRisks>,
+}
+
+impl MintRegistry {
+    /// Loads the MintRegistry if one has been created at this address
+    pub fn load(account: &AccountInfo) -> Result<Option<MintRegistry>> {
+        if account.owner != &crate::ID || account.data_is_empty() {
+            return Ok(None);
+        }
+        let mint_registry = MintRegistry::try_deserialize(&mut &account.data.borrow()[..])?;
+        Ok(Some(mint_registry))
+    }
+
+    /// Hashes the mint index into the commitment included in a leaf paying a registered mint
+    ///     hash = H(mint_index)
+    pub fn hash_mint_index(hash_algorithm: HashAlgorithm, mint_index: u8) -> Result<[u8; 32]> {
+        hashv(hash_algorithm, &[&[mint_index]])
+    }
+
+    /// Validates the mint paid by a leaf
+    ///     - Leaves without a mint index pay the tree's own mint
+    ///     - Leaves with a mint index pay the registered mint at that index, on fixed amount trees only
+    pub fn require_leaf_mint(
+        account: &AccountInfo,
+        distribution_tree: &DistributionTree,
+        mint: Pubkey,
+        mint_index: Option<u8>,
+    ) -> Result<()> {
+        let Some(mint_index) = mint_index else {
+            require_keys_eq!(mint, distribution_tree.mint, DistributionError::InvalidTokenMint);
+            return Ok(());
+        };
+        require!(
+            distribution_tree.total_shares.is_none(),
+            DistributionError::MultiMintNotSupported
+        );
+        require!(mint_index > 0, DistributionError::InvalidMintIndex);
+
+        let mint_registry = Self::load(account)?.ok_or(DistributionError::InvalidMintIndex)?;
+        let registered_mint = mint_registry
+            .mints
+            .get(mint_index as usize - 1)
+            .ok_or(DistributionError::InvalidMintIndex)?;
+        require_keys_eq!(mint, *registered_mint, DistributionError::InvalidTokenMint);
+        Ok(())
+    }
+}
@@ -0,0 +1,47 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::spl_token_2022::{
+    self,
+    extension::{
+        default_account_state::DefaultAccountState, permanent_delegate::PermanentDelegate,
+        BaseStateWithExtensions, StateWithExtensions,
+    },
+    state::AccountState,
+};
+
+/// Capabilities of a mint that let a third party drain or lock the token vault after funding
+/// Recorded on the DistributionTree at initialization, once acknowledged by the authority
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MintRisks {
+    /// The mint has a permanent delegate that can transfer or burn the vault's tokens
+    pub permanent_delegate: bool,
+    /// New token accounts of the mint, including the vault, start frozen
+    pub default_frozen: bool,
+    /// The mint has a freeze authority that can freeze the vault
+    pub freeze_authority: bool,
+}
+
+impl MintRisks {
+    /// Inspects the mint's freeze authority and Token-2022 extensions
+    pub fn inspect(mint: &AccountInfo) -> Result<MintRisks> {
+        let mint_data = mint.try_borrow_data()?;
+        let mint_state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
+
+        let permanent_delegate = mint_state
+            .get_extension::<PermanentDelegate>()
+            .is_ok_and(|extension| Option::<Pubkey>::from(extension.delegate).is_some());
+        let default_frozen = mint_state
+            .get_extension::<DefaultAccountState>()
+            .is_ok_and(|extension| extension.state == AccountState::Frozen as u8);
+
+        Ok(MintRisks {
+            permanent_delegate,
+            default_frozen,
+            freeze_authority: mint_state.base.freeze_authority.is_some(),
+        })
+    }
+
+    /// Whether the mint has any risky capability
+    pub fn any(&self) -> bool {
+        self.permanent_delegate || self.default_frozen || self.freeze_authority
+    }
+}
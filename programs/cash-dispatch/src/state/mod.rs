@@ -1,3 +1,25 @@
 pub mod distribution_tree;
+pub mod claim_record;
+pub mod leaf_redirect;
+pub mod decline_record;
+pub mod dispute;
+pub mod delegation;
+pub mod payout_split;
+pub mod leaf_schedule;
+pub mod installment;
+pub mod mint_registry;
+pub mod mint_risks;
+pub mod legacy;
 
-pub use distribution_tree::*;
\ No newline at end of file
+pub use distribution_tree::*;
+pub use claim_record::*;
+pub use leaf_redirect::*;
+pub use decline_record::*;
+pub use dispute::*;
+pub use delegation::*;
+pub use payout_split::*;
+pub use leaf_schedule::*;
+pub use installment::*;
+pub use mint_registry::*;
+pub use mint_risks::*;
+pub use legacy::*;
@@ -0,0 +1,93 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{MAX_PAYOUT_SPLITS, TOTAL_SPLIT_BPS},
+    error::DistributionError,
+    state::HashAlgorithm,
+    utils::hashv,
+};
+
+/// A destination of a split leaf and its share of the payout in basis points
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct PayoutSplit {
+    pub destination: Pubkey,
+    pub bps: u16,
+}
+
+impl PayoutSplit {
+    /// Validates that the splits have 1 to MAX_PAYOUT_SPLITS destinations, each with a
+    /// non-zero share, adding up to TOTAL_SPLIT_BPS
+    pub fn validate(splits: &[PayoutSplit]) -> Result<()> {
+        require!(
+            !splits.is_empty() && splits.len() <= MAX_PAYOUT_SPLITS,
+            DistributionError::InvalidPayoutSplits
+        );
+        let mut total_bps: u16 = 0;
+        for split in splits {
+            require!(
+                split.bps > 0 && split.destination != Pubkey::default(),
+                DistributionError::InvalidPayoutSplits
+            );
+            total_bps = total_bps
+                .checked_add(split.bps)
+                .ok_or(DistributionError::InvalidPayoutSplits)?;
+        }
+        require_eq!(total_bps, TOTAL_SPLIT_BPS, DistributionError::InvalidPayoutSplits);
+        Ok(())
+    }
+
+    /// Hashes the splits into the commitment included in a split leaf
+    /// Each split is chained onto the previous hash, starting from 32 zero bytes:
+    ///     hash = H(hash || destination || bps)
+    /// Poseidon inputs are big-endian field elements, so the destination is split in two halves
+    pub fn hash_splits(hash_algorithm: HashAlgorithm, splits: &[PayoutSplit]) -> Result<[u8; 32]> {
+        let mut hash = [0u8; 32];
+        for split in splits {
+            let destination = split.destination.to_bytes();
+            hash = match hash_algorithm {
+                HashAlgorithm::Poseidon => hashv(
+                    hash_algorithm,
+                    &[
+                        &hash,
+                        &destination[..16],
+                        &destination[16..],
+                        &split.bps.to_be_bytes(),
+                    ],
+                )?,
+                HashAlgorithm::Keccak | HashAlgorithm::Sha256 => hashv(
+                    hash_algorithm,
+                    &[&hash, &destination, &split.bps.to_le_bytes()],
+                )?,
+            };
+        }
+        Ok(hash)
+    }
+
+    /// Divides a payout between the splits
+    /// Each destination receives payout * bps / TOTAL_SPLIT_BPS rounded down, and the
+    /// rounding remainder goes to the first destination so the full payout is always paid
+    pub fn split_amounts(payout: u64, splits: &[PayoutSplit]) -> Result<Vec<u64>> {
+        let mut amounts = splits
+            .iter()
+            .map(|split| {
+                let amount = (payout as u128)
+                    .checked_mul(split.bps as u128)
+                    .ok_or(DistributionError::MathError)?
+                    .checked_div(TOTAL_SPLIT_BPS as u128)
+                    .ok_or(DistributionError::MathError)?;
+                u64::try_from(amount).map_err(|_| DistributionError::MathError.into())
+            })
+            .collect::<Result<Vec<u64>>>()?;
+
+        let paid = amounts
+            .iter()
+            .try_fold(0u64, |total, amount| total.checked_add(*amount))
+            .ok_or(DistributionError::MathError)?;
+        let remainder = payout.checked_sub(paid).ok_or(DistributionError::MathError)?;
+        amounts[0] = amounts[0]
+            .checked_add(remainder)
+            .ok_or(DistributionError::MathError)?;
+
+        Ok(amounts)
+    }
+}
@@ -1,7 +1,21 @@
 use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use anchor_lang::solana_program::{hash, keccak, poseidon};
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+use anchor_spl::token_2022::spl_token_2022::{
+    self,
+    extension::{
+        transfer_fee::{TransferFeeAmount, TransferFeeConfig},
+        BaseStateWithExtensions, StateWithExtensions,
+    },
+    onchain::invoke_transfer_checked,
+};
+use anchor_spl::token_2022_extensions::{harvest_withheld_tokens_to_mint, HarvestWithheldTokensToMint};
+use anchor_spl::token_interface::{close_account, CloseAccount, Mint, TokenAccount};
 use solana_gateway::{Gateway, VerificationOptions};
 use crate::error::DistributionError;
-use crate::constants::MAX_FEE_AMOUNT;
+use crate::constants::{FEE_REFERENCE_DECIMALS, MAX_FEE_AMOUNT, SOL_VAULT_SEED};
+use crate::state::{HashAlgorithm, MintRegistry, PayoutSplit};
 
 /// Source: https://github.com/saber-hq/merkle-distributor/blob/master/programs/merkle-distributor/src/merkle_proof.rs
 /// These functions deal with verification of Merkle trees (hash trees).
@@ -11,26 +25,57 @@ use crate::constants::MAX_FEE_AMOUNT;
 /// defined by `root`. For this, a `proof` must be provided, containing
 /// sibling hashes on the branch from the leaf to the root of the tree. Each
 /// pair of leaves and each pair of pre-images are assumed to be sorted.
-pub fn verify(proof: &Vec<[u8; 32]>, root: [u8; 32], leaf: [u8; 32]) -> bool {
+/// `node_prefix` is prepended to every internal node pre-image (empty for v1 trees).
+pub fn verify(
+    proof: &Vec<[u8; 32]>,
+    root: [u8; 32],
+    leaf: [u8; 32],
+    node_prefix: &[u8],
+    hash_algorithm: HashAlgorithm,
+) -> Result<bool> {
     let mut computed_hash = leaf;
-    for proof_element in proof.into_iter() {
-        if computed_hash <= *proof_element {
-            // Hash(current computed hash + current element of the proof)
-            computed_hash =
-                anchor_lang::solana_program::keccak::hashv(&[&computed_hash, proof_element]).0;
-            // Hash(current element of the proof + current computed hash)
-        } else {
-            computed_hash =
-                anchor_lang::solana_program::keccak::hashv(&[proof_element, &computed_hash]).0;
-        }
+    for proof_element in proof.iter() {
+        computed_hash = hash_node(hash_algorithm, node_prefix, &computed_hash, proof_element)?;
     }
     // Check if the computed hash (root) is equal to the provided root
-    computed_hash == root
+    Ok(computed_hash == root)
+}
+
+/// Hashes two sibling nodes into their parent: Hash(prefix + smaller node + larger node)
+pub fn hash_node(
+    hash_algorithm: HashAlgorithm,
+    node_prefix: &[u8],
+    first: &[u8; 32],
+    second: &[u8; 32],
+) -> Result<[u8; 32]> {
+    if first <= second {
+        hashv(hash_algorithm, &[node_prefix, first, second])
+    } else {
+        hashv(hash_algorithm, &[node_prefix, second, first])
+    }
+}
+
+/// Hashes `vals` with the given algorithm
+/// Keccak and Sha256 hash the concatenated bytes, while Poseidon treats each value
+/// as a separate big-endian BN254 field element
+pub fn hashv(hash_algorithm: HashAlgorithm, vals: &[&[u8]]) -> Result<[u8; 32]> {
+    match hash_algorithm {
+        HashAlgorithm::Keccak => Ok(keccak::hashv(vals).0),
+        HashAlgorithm::Sha256 => Ok(hash::hashv(vals).to_bytes()),
+        HashAlgorithm::Poseidon => {
+            poseidon::hashv(poseidon::Parameters::Bn254X5, poseidon::Endianness::BigEndian, vals)
+                .map(|hash| hash.to_bytes())
+                .map_err(|_| {
+                    msg!("Poseidon hash failed");
+                    DistributionError::InvalidHashInput.into()
+                })
+        }
+    }
 }
 
 pub fn check_gateway_token(
     gateway_token: Option<&AccountInfo>,
-    recipient: &AccountInfo,
+    recipient: &Pubkey,
     gatekeeper_network: &Pubkey,
     options: Option<VerificationOptions>,
 ) -> Result<()> {
@@ -40,7 +85,7 @@ pub fn check_gateway_token(
     );
     Gateway::verify_gateway_token_account_info(
         gateway_token.unwrap(),
-        &recipient.key(),
+        recipient,
         &gatekeeper_network,
         options,
     )
@@ -52,6 +97,313 @@ pub fn check_gateway_token(
     Ok(())
 }
 
+/// Transfers tokens with `transfer_checked`, forwarding the extra accounts required by a
+/// Token-2022 transfer hook. `additional_accounts` must contain the hook program, its
+/// extra-account-metas PDA and the accounts listed in it (usually `ctx.remaining_accounts`).
+/// Mints without a transfer hook ignore them.
+#[allow(clippy::too_many_arguments)]
+pub fn transfer_checked_with_hook<'info>(
+    token_program: &AccountInfo<'info>,
+    from: &AccountInfo<'info>,
+    mint: &AccountInfo<'info>,
+    to: &AccountInfo<'info>,
+    authority: &AccountInfo<'info>,
+    additional_accounts: &[AccountInfo<'info>],
+    amount: u64,
+    decimals: u8,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    invoke_transfer_checked(
+        token_program.key,
+        from.clone(),
+        mint.clone(),
+        to.clone(),
+        authority.clone(),
+        additional_accounts,
+        amount,
+        decimals,
+        signer_seeds,
+    )
+    .map_err(Into::into)
+}
+
+/// Returns the amount to send so that the destination receives `net_amount`
+/// Token-2022 mints with the TransferFee extension withhold a fee from every transfer, so the fee
+/// charged at the current epoch is added on top. Other mints return `net_amount` unchanged
+pub fn gross_up_transfer_fee(mint: &AccountInfo, net_amount: u64) -> Result<u64> {
+    if mint.owner != &spl_token_2022::ID {
+        return Ok(net_amount);
+    }
+    let mint_data = mint.try_borrow_data()?;
+    let mint_state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
+    let Ok(transfer_fee_config) = mint_state.get_extension::<TransferFeeConfig>() else {
+        return Ok(net_amount);
+    };
+    let fee = transfer_fee_config
+        .calculate_inverse_epoch_fee(Clock::get()?.epoch, net_amount)
+        .ok_or(DistributionError::MathError)?;
+    net_amount
+        .checked_add(fee)
+        .ok_or(DistributionError::MathError.into())
+}
+
+/// Checks that a deposit credited the vault with at least `expected_amount`, net of any transfer fee
+/// (a grossed-up deposit may round in the vault's favour)
+pub fn require_vault_received(
+    token_vault: &mut InterfaceAccount<TokenAccount>,
+    balance_before: u64,
+    expected_amount: u64,
+) -> Result<()> {
+    token_vault.reload()?;
+    let received = token_vault
+        .amount
+        .checked_sub(balance_before)
+        .ok_or(DistributionError::MathError)?;
+    require_gte!(received, expected_amount, DistributionError::VaultUnderfunded);
+    Ok(())
+}
+
+/// Checks that a native SOL payout leaves both accounts rent-exempt
+///     1. The SOL vault keeps the rent-exempt minimum of a system account
+///     2. The recipient holds at least the rent-exempt minimum for its data after the payout
+pub fn require_native_payout_rent_exempt(
+    sol_vault: &AccountInfo,
+    recipient: &AccountInfo,
+    amount: u64,
+) -> Result<()> {
+    let rent = Rent::get()?;
+    let vault_balance_after = sol_vault
+        .lamports()
+        .checked_sub(amount)
+        .ok_or(DistributionError::InsufficientVaultBalance)?;
+    require_gte!(
+        vault_balance_after,
+        rent.minimum_balance(0),
+        DistributionError::InsufficientVaultBalance
+    );
+    let recipient_balance_after = recipient
+        .lamports()
+        .checked_add(amount)
+        .ok_or(DistributionError::MathError)?;
+    require!(
+        rent.is_exempt(recipient_balance_after, recipient.data_len()),
+        DistributionError::RecipientNotRentExempt
+    );
+    Ok(())
+}
+
+/// Transfers lamports out of the SOL vault of a native SOL DistributionTree, signed by the vault PDA
+pub fn transfer_from_sol_vault<'info>(
+    system_program: &AccountInfo<'info>,
+    sol_vault: &AccountInfo<'info>,
+    destination: &AccountInfo<'info>,
+    distribution_tree: &Pubkey,
+    sol_vault_bump: u8,
+    amount: u64,
+) -> Result<()> {
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        SOL_VAULT_SEED,
+        distribution_tree.as_ref(),
+        std::slice::from_ref(&sol_vault_bump),
+    ]];
+    transfer(
+        CpiContext::new_with_signer(
+            system_program.clone(),
+            Transfer {
+                from: sol_vault.clone(),
+                to: destination.clone(),
+            },
+            signer_seeds,
+        ),
+        amount,
+    )
+}
+
+/// Fans a payout out to the destinations of a split leaf
+/// The first `splits.len()` accounts of `remaining_accounts` must be token accounts of the mint owned
+/// by each destination, in the same order. Any accounts after them are forwarded to the transfer hook.
+/// With `gross_up`, each transfer is grossed up by the mint's transfer fee so every destination
+/// receives its full share
+#[allow(clippy::too_many_arguments)]
+pub fn transfer_to_splits<'info>(
+    token_program: &AccountInfo<'info>,
+    from: &AccountInfo<'info>,
+    mint: &InterfaceAccount<'info, Mint>,
+    authority: &AccountInfo<'info>,
+    splits: &[PayoutSplit],
+    remaining_accounts: &'info [AccountInfo<'info>],
+    payout: u64,
+    gross_up: bool,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    require_gte!(
+        remaining_accounts.len(),
+        splits.len(),
+        DistributionError::InvalidSplitTokenAccount
+    );
+    let (split_accounts, hook_accounts) = remaining_accounts.split_at(splits.len());
+    let amounts = PayoutSplit::split_amounts(payout, splits)?;
+
+    for ((split, split_account), amount) in splits.iter().zip(split_accounts).zip(amounts) {
+        require!(
+            split_account.is_writable && split_account.owner == token_program.key,
+            DistributionError::InvalidSplitTokenAccount
+        );
+        let token_account = InterfaceAccount::<TokenAccount>::try_from(split_account)?;
+        require!(
+            token_account.mint == mint.key() && token_account.owner == split.destination,
+            DistributionError::InvalidSplitTokenAccount
+        );
+        if amount == 0 {
+            continue;
+        }
+        let amount = if gross_up {
+            gross_up_transfer_fee(&mint.to_account_info(), amount)?
+        } else {
+            amount
+        };
+        transfer_checked_with_hook(
+            token_program,
+            from,
+            &mint.to_account_info(),
+            split_account,
+            authority,
+            hook_accounts,
+            amount,
+            mint.decimals,
+            signer_seeds,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Closes an emptied token vault of the distribution tree, returning its rent to `destination`
+/// Token-2022 accounts holding withheld transfer fees cannot be closed, so the fees are harvested
+/// to the mint first (the mint must then be writable)
+pub fn close_token_vault<'info>(
+    token_program: &AccountInfo<'info>,
+    token_vault: &AccountInfo<'info>,
+    mint: &AccountInfo<'info>,
+    destination: &AccountInfo<'info>,
+    distribution_tree: &AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let has_withheld_fees = token_vault.owner == &spl_token_2022::ID && {
+        let token_vault_data = token_vault.try_borrow_data()?;
+        StateWithExtensions::<spl_token_2022::state::Account>::unpack(&token_vault_data)?
+            .get_extension::<TransferFeeAmount>()
+            .is_ok_and(|transfer_fee_amount| u64::from(transfer_fee_amount.withheld_amount) > 0)
+    };
+    if has_withheld_fees {
+        harvest_withheld_tokens_to_mint(
+            CpiContext::new(
+                token_program.clone(),
+                HarvestWithheldTokensToMint {
+                    token_program_id: token_program.clone(),
+                    mint: mint.clone(),
+                },
+            ),
+            vec![token_vault.clone()],
+        )?;
+    }
+
+    close_account(CpiContext::new_with_signer(
+        token_program.clone(),
+        CloseAccount {
+            account: token_vault.clone(),
+            destination: destination.clone(),
+            authority: distribution_tree.clone(),
+        },
+        signer_seeds,
+    ))
+}
+
+/// Sweeps the vault of every mint registered in the MintRegistry to the authority
+/// `remaining_accounts` must start with `[mint, token_vault, authority_token_account, token_program]`
+/// for each registered mint, in mint index order. The accounts after them are forwarded to the mints'
+/// transfer hooks, and returned.
+pub fn sweep_registered_vaults<'info>(
+    distribution_tree: &AccountInfo<'info>,
+    authority: Pubkey,
+    mint_registry: &MintRegistry,
+    remaining_accounts: &'info [AccountInfo<'info>],
+    signer_seeds: &[&[&[u8]]],
+) -> Result<&'info [AccountInfo<'info>]> {
+    let vault_accounts_len = mint_registry.mints.len() * 4;
+    require_gte!(
+        remaining_accounts.len(),
+        vault_accounts_len,
+        DistributionError::InvalidMintVaultAccounts
+    );
+    let (vault_accounts, other_accounts) = remaining_accounts.split_at(vault_accounts_len);
+
+    for (registered_mint, accounts) in mint_registry.mints.iter().zip(vault_accounts.chunks(4)) {
+        let [mint, token_vault, authority_token_account, token_program] = accounts else {
+            return err!(DistributionError::InvalidMintVaultAccounts);
+        };
+        require!(
+            mint.key == registered_mint
+                && mint.owner == token_program.key
+                && token_vault.is_writable
+                && authority_token_account.is_writable,
+            DistributionError::InvalidMintVaultAccounts
+        );
+        require_keys_eq!(
+            token_vault.key(),
+            get_associated_token_address_with_program_id(
+                distribution_tree.key,
+                mint.key,
+                token_program.key
+            ),
+            DistributionError::InvalidMintVaultAccounts
+        );
+
+        let mint_account = InterfaceAccount::<Mint>::try_from(mint)?;
+        let authority_token = InterfaceAccount::<TokenAccount>::try_from(authority_token_account)?;
+        require!(
+            authority_token.mint == *registered_mint && authority_token.owner == authority,
+            DistributionError::InvalidMintVaultAccounts
+        );
+
+        let amount = InterfaceAccount::<TokenAccount>::try_from(token_vault)?.amount;
+        if amount == 0 {
+            continue;
+        }
+        transfer_checked_with_hook(
+            token_program,
+            token_vault,
+            mint,
+            authority_token_account,
+            distribution_tree,
+            other_accounts,
+            amount,
+            mint_account.decimals,
+            signer_seeds,
+        )?;
+    }
+
+    Ok(other_accounts)
+}
+
+/// Closes the vault of every mint registered in the MintRegistry, returning its rent to the authority
+/// The vaults must have been emptied by `sweep_registered_vaults`, which validates `remaining_accounts`
+pub fn close_registered_vaults<'info>(
+    distribution_tree: &AccountInfo<'info>,
+    authority: &AccountInfo<'info>,
+    mint_registry: &MintRegistry,
+    remaining_accounts: &'info [AccountInfo<'info>],
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    for accounts in remaining_accounts.chunks(4).take(mint_registry.mints.len()) {
+        let [mint, token_vault, _, token_program] = accounts else {
+            return err!(DistributionError::InvalidMintVaultAccounts);
+        };
+        close_token_vault(token_program, token_vault, mint, authority, distribution_tree, signer_seeds)?;
+    }
+    Ok(())
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum FeeTier {
     Free,
@@ -72,6 +424,7 @@ impl FeeTier {
         }
     }
 
+    /// Minimum amount of the tier, in FEE_REFERENCE_DECIMALS units
     pub fn get_threshold(&self) -> u64 {
         match self {
             FeeTier::Free => 0,
@@ -83,8 +436,11 @@ impl FeeTier {
     }
 }
 
-pub fn calculate_fee(amount: u64) -> Result<u64> {
-    let fee_tier = get_fee_tier(amount);
+/// Calculates the program fee on an amount of a mint with `decimals`
+/// The tier thresholds and MAX_FEE_AMOUNT are scaled from FEE_REFERENCE_DECIMALS to the mint's decimals,
+/// so the fee depends on the value funded rather than the number of base units
+pub fn calculate_fee(amount: u64, decimals: u8) -> Result<u64> {
+    let fee_tier = get_fee_tier(amount, decimals);
     let fee_bps = fee_tier.get_fee_bps();
 
     let fee_amount = amount
@@ -93,10 +449,23 @@ pub fn calculate_fee(amount: u64) -> Result<u64> {
         .checked_div(10_000)
         .ok_or(ProgramError::ArithmeticOverflow)?;
 
-    Ok(fee_amount.min(MAX_FEE_AMOUNT))
+    Ok(fee_amount.min(scale_to_decimals(MAX_FEE_AMOUNT, decimals)))
+}
+
+/// Converts an amount in FEE_REFERENCE_DECIMALS units to units of a mint with `decimals`,
+/// saturating at u64::MAX
+fn scale_to_decimals(amount: u64, decimals: u8) -> u64 {
+    let scaled = if decimals >= FEE_REFERENCE_DECIMALS {
+        10u128
+            .checked_pow((decimals - FEE_REFERENCE_DECIMALS) as u32)
+            .and_then(|factor| (amount as u128).checked_mul(factor))
+    } else {
+        Some(amount as u128 / 10u128.pow((FEE_REFERENCE_DECIMALS - decimals) as u32))
+    };
+    scaled.map_or(u64::MAX, |scaled| scaled.min(u64::MAX as u128) as u64)
 }
 
-fn get_fee_tier(amount: u64) -> FeeTier {
+fn get_fee_tier(amount: u64, decimals: u8) -> FeeTier {
     [
         FeeTier::Tier4,
         FeeTier::Tier3,
@@ -104,6 +473,6 @@ fn get_fee_tier(amount: u64) -> FeeTier {
         FeeTier::Tier1,
     ]
     .into_iter()
-    .find(|tier| amount >= tier.get_threshold())
+    .find(|tier| amount >= scale_to_decimals(tier.get_threshold(), decimals))
     .unwrap_or(FeeTier::Free)
 }
@@ -0,0 +1,128 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+use spl_tlv_account_resolution::{account::ExtraAccountMeta, seeds::Seed, state::ExtraAccountMetaList};
+use spl_transfer_hook_interface::instruction::ExecuteInstruction;
+
+declare_id!("ytsnZwf34asvWTUYYWSJ9y2UShkqszWa1whwhyyqWy9");
+
+pub const EXTRA_ACCOUNT_METAS_SEED: &[u8] = b"extra-account-metas";
+pub const TRANSFER_COUNTER_SEED: &[u8] = b"TRANSFER_COUNTER";
+
+/// Sample Token-2022 transfer hook used by the cash-dispatch tests
+/// Counts the transfers of a mint in a TransferCounter PDA, the only extra account the hook requires
+#[program]
+pub mod sample_transfer_hook {
+    use super::*;
+
+    /// Creates the mint's extra-account-metas PDA, listing the TransferCounter, and the TransferCounter
+    pub fn initialize_extra_account_meta_list(
+        ctx: Context<InitializeExtraAccountMetaList>,
+    ) -> Result<()> {
+        ExtraAccountMetaList::init::<ExecuteInstruction>(
+            &mut ctx.accounts.extra_account_meta_list.try_borrow_mut_data()?,
+            &extra_account_metas()?,
+        )?;
+
+        let transfer_counter = &mut ctx.accounts.transfer_counter;
+        transfer_counter.bump = ctx.bumps.transfer_counter;
+        transfer_counter.mint = ctx.accounts.mint.key();
+        Ok(())
+    }
+
+    /// Invoked by Token-2022 on every transfer of the mint
+    #[interface(spl_transfer_hook_interface::execute)]
+    pub fn transfer_hook(ctx: Context<TransferHook>, amount: u64) -> Result<()> {
+        let transfer_counter = &mut ctx.accounts.transfer_counter;
+        transfer_counter.transfers = transfer_counter
+            .transfers
+            .checked_add(1)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        transfer_counter.amount = transfer_counter
+            .amount
+            .checked_add(amount)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        Ok(())
+    }
+}
+
+/// The TransferCounter PDA of the mint being transferred (account index 1 of Execute)
+fn extra_account_metas() -> Result<Vec<ExtraAccountMeta>> {
+    Ok(vec![ExtraAccountMeta::new_with_seeds(
+        &[
+            Seed::Literal {
+                bytes: TRANSFER_COUNTER_SEED.to_vec(),
+            },
+            Seed::AccountKey { index: 1 },
+        ],
+        false,
+        true,
+    )?])
+}
+
+#[derive(Accounts)]
+pub struct InitializeExtraAccountMetaList<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: Initialized as an ExtraAccountMetaList in the handler
+    #[account(
+        init,
+        payer = payer,
+        space = ExtraAccountMetaList::size_of(extra_account_metas()?.len())?,
+        seeds = [EXTRA_ACCOUNT_METAS_SEED, mint.key().as_ref()],
+        bump
+    )]
+    pub extra_account_meta_list: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + TransferCounter::INIT_SPACE,
+        seeds = [TRANSFER_COUNTER_SEED, mint.key().as_ref()],
+        bump
+    )]
+    pub transfer_counter: Account<'info, TransferCounter>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Accounts of the transfer hook Execute instruction, in the order Token-2022 passes them
+#[derive(Accounts)]
+pub struct TransferHook<'info> {
+    #[account(token::mint = mint)]
+    pub source_token: InterfaceAccount<'info, TokenAccount>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(token::mint = mint)]
+    pub destination_token: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Owner or delegate of the source token account
+    pub owner: UncheckedAccount<'info>,
+
+    /// CHECK: Address verified by seeds
+    #[account(seeds = [EXTRA_ACCOUNT_METAS_SEED, mint.key().as_ref()], bump)]
+    pub extra_account_meta_list: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [TRANSFER_COUNTER_SEED, mint.key().as_ref()],
+        bump = transfer_counter.bump
+    )]
+    pub transfer_counter: Account<'info, TransferCounter>,
+}
+
+/// Counts the transfers of a mint
+#[account]
+#[derive(InitSpace)]
+pub struct TransferCounter {
+    /// Bump seed.
+    pub bump: u8,
+    /// The mint whose transfers are counted
+    pub mint: Pubkey,
+    /// Number of transfers
+    pub transfers: u64,
+    /// Total amount transferred
+    pub amount: u64,
+}
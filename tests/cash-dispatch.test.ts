@@ -9,6 +9,27 @@ import { claimTests } from "./instructions/5-claim/claimTests";
 import { pauseResumeTests } from "./instructions/6-pause/pauseResumeTests";
 import { gatekeeperTests } from "./instructions/7-gatekeeper/gatekeeperTests";
 import { reclaimTests } from "./instructions/8-reclaim/reclaimTests";
+import { hashAlgorithmTests } from "./instructions/9-hash-algorithm/hashAlgorithmTests";
+import { migrateTests } from "./instructions/10-migrate/migrateTests";
+import { epochTests } from "./instructions/11-epoch/epochTests";
+import { proRataTests } from "./instructions/12-pro-rata/proRataTests";
+import { openEnrollmentTests } from "./instructions/13-open-enrollment/openEnrollmentTests";
+import { claimLinkTests } from "./instructions/14-claim-link/claimLinkTests";
+import { redirectTests } from "./instructions/15-redirect/redirectTests";
+import { revokeTests } from "./instructions/16-revoke/revokeTests";
+import { declineTests } from "./instructions/17-decline/declineTests";
+import { disputeTests } from "./instructions/18-dispute/disputeTests";
+import { delegationTests } from "./instructions/19-delegation/delegationTests";
+import { claimDestinationTests } from "./instructions/20-claim-destination/claimDestinationTests";
+import { splitPayoutTests } from "./instructions/21-split-payouts/splitPayoutTests";
+import { leafScheduleTests } from "./instructions/22-leaf-schedule/leafScheduleTests";
+import { installmentTests } from "./instructions/23-installments/installmentTests";
+import { multiMintTests } from "./instructions/24-multi-mint/multiMintTests";
+import { nativeSolTests } from "./instructions/25-native-sol/nativeSolTests";
+import { transferFeeTests } from "./instructions/26-transfer-fee/transferFeeTests";
+import { transferHookTests } from "./instructions/27-transfer-hook/transferHookTests";
+import { cliTests } from "./instructions/28-cli/cliTests";
+import { crankTests } from "./instructions/29-crank/crankTests";
 
 describe("The Cash Dispatch Program", () => {
   const testEnv = new TestEnvironment();
@@ -26,5 +47,26 @@ describe("The Cash Dispatch Program", () => {
   describe('Pause/Resume Instruction Tests', () => pauseResumeTests(testEnv));
   describe('Gatekeeper Authorization Tests', () => gatekeeperTests(testEnv));
   describe('Reclaim & Close Instruction Tests', () => reclaimTests(testEnv));
+  describe('Hash Algorithm Tests', () => hashAlgorithmTests(testEnv));
+  describe('Migrate Instruction Tests', () => migrateTests(testEnv));
+  describe('New Epoch Instruction Tests', () => epochTests(testEnv));
+  describe('Pro-Rata Distribution Tests', () => proRataTests(testEnv));
+  describe('Open-Enrollment Claim Tests', () => openEnrollmentTests(testEnv));
+  describe('Claim Link Instruction Tests', () => claimLinkTests(testEnv));
+  describe('Redirect Leaf Instruction Tests', () => redirectTests(testEnv));
+  describe('Revoke Instruction Tests', () => revokeTests(testEnv));
+  describe('Decline Instruction Tests', () => declineTests(testEnv));
+  describe('Dispute Instruction Tests', () => disputeTests(testEnv));
+  describe('Delegate Claim Instruction Tests', () => delegationTests(testEnv));
+  describe('Claim Destination Tests', () => claimDestinationTests(testEnv));
+  describe('Split Payout Tests', () => splitPayoutTests(testEnv));
+  describe('Leaf Schedule Tests', () => leafScheduleTests(testEnv));
+  describe('Installment Tests', () => installmentTests(testEnv));
+  describe('Multi-Mint Tests', () => multiMintTests(testEnv));
+  describe('Native SOL Tests', () => nativeSolTests(testEnv));
+  describe('Transfer Fee Tests', () => transferFeeTests(testEnv));
+  describe('Transfer Hook Tests', () => transferHookTests(testEnv));
+  describe('Dispatch CLI Tests', () => cliTests(testEnv));
+  describe('Distribution Crank Tests', () => crankTests(testEnv));
 
 });
@@ -3,6 +3,8 @@ import { PublicKey, Keypair, SystemProgram } from '@solana/web3.js';
 import { ASSOCIATED_TOKEN_PROGRAM_ID, getAssociatedTokenAddressSync, TOKEN_2022_PROGRAM_ID } from "@solana/spl-token";
 import { BN } from '@coral-xyz/anchor';
 import { assert } from 'chai';
+import { CURRENT_VERSION } from "../../utils/constants";
+import { HashAlgorithm, LeafExtensions, toAnchorHashAlgorithm } from "../../utils/merkle-tree";
 
 export interface Initialize {
     authority: Keypair,
@@ -19,6 +21,12 @@ export interface Initialize {
     endTs: number | null,
     allowClaims?: boolean,
     gatekeeperNetwork?: PublicKey,
+    hashAlgorithm?: HashAlgorithm,
+    totalShares?: number,
+    openClaimAmount?: number,
+    declineDestination?: PublicKey,
+    // The test PYUSD mint has a freeze authority, so its risks are acknowledged by default
+    acknowledgeMintRisks?: boolean,
 }
 
 export async function initialize(
@@ -35,6 +43,11 @@ export async function initialize(
         startTs: new BN(initialize.startTs),
         endTs: initialize.endTs ? new BN(initialize.endTs) : null,
         gatekeeperNetwork: initialize.gatekeeperNetwork ?? null,
+        hashAlgorithm: initialize.hashAlgorithm ? toAnchorHashAlgorithm(initialize.hashAlgorithm) : null,
+        totalShares: initialize.totalShares ? new BN(initialize.totalShares) : null,
+        openClaimAmount: initialize.openClaimAmount ? new BN(initialize.openClaimAmount) : null,
+        declineDestination: initialize.declineDestination ?? null,
+        acknowledgeMintRisks: initialize.acknowledgeMintRisks ?? true,
     };
 
     const accounts = {
@@ -61,9 +74,19 @@ export async function initialize(
         assert.strictEqual(distributionTreeData.tokenVault.toString(), initialize.tokenVault.toString());
         assert.strictEqual(distributionTreeData.totalNumberRecipients.toNumber(), initialize.totalNumberRecipients);
         assert.strictEqual(distributionTreeData.startTs.toNumber(), initialize.startTs);
+        assert.strictEqual(distributionTreeData.version.toNumber(), CURRENT_VERSION);
         if (initialize.gatekeeperNetwork) {
             assert.strictEqual(distributionTreeData.gatekeeperNetwork?.toString(), initialize.gatekeeperNetwork.toString());
         }
+        assert.deepStrictEqual(distributionTreeData.hashAlgorithm, toAnchorHashAlgorithm(initialize.hashAlgorithm ?? "keccak"));
+        assert.strictEqual(distributionTreeData.totalShares?.toNumber() ?? null, initialize.totalShares ?? null);
+        assert.strictEqual(distributionTreeData.distributedShares.toNumber(), 0);
+        assert.strictEqual(distributionTreeData.openClaimAmount?.toNumber() ?? null, initialize.openClaimAmount ?? null);
+        assert.deepStrictEqual(distributionTreeData.mintRisks, {
+            permanentDelegate: false,
+            defaultFrozen: false,
+            freezeAuthority: true,
+        });
 
     } catch (error) {
         throw error;
@@ -77,6 +100,11 @@ interface CreateNewDistributionTreeParams {
     startOffset?: number,
     allowClaims?: boolean,
     gatekeeperNetwork?: PublicKey,
+    hashAlgorithm?: HashAlgorithm,
+    totalShares?: number,
+    transferToVaultAmount?: number,
+    declineDestination?: PublicKey,
+    leafExtensions?: Record<number, LeafExtensions>,
 }
 
 export async function createNewDistributionTree({
@@ -84,9 +112,14 @@ export async function createNewDistributionTree({
     numPayments,
     startOffset,
     allowClaims,
-    gatekeeperNetwork
+    gatekeeperNetwork,
+    hashAlgorithm,
+    totalShares,
+    transferToVaultAmount,
+    declineDestination,
+    leafExtensions
 }: CreateNewDistributionTreeParams) {
-    await testEnv.newTree({ numPayments, startOffset });
+    await testEnv.newTree({ numPayments, startOffset, hashAlgorithm, leafExtensions });
     let initializeParams: Initialize = {
         authority: testEnv.authority,
         distributionTreePda: testEnv.distributionTreePda,
@@ -96,12 +129,15 @@ export async function createNewDistributionTree({
         merkleRoot: testEnv.balanceTree.getRoot(),
         batchId: testEnv.distributionUniqueId,
         totalNumberRecipients: Object.keys(testEnv.merkleDistributorInfo.payments).length,
-        transferToVaultAmount: Object.values(testEnv.merkleDistributorInfo.payments).reduce((sum, payment) => sum + payment.amount.toNumber(), 0),
+        transferToVaultAmount: transferToVaultAmount ?? Object.values(testEnv.merkleDistributorInfo.payments).reduce((sum, payment) => sum + payment.amount.toNumber(), 0),
         mintDecimals: 6,
         startTs: testEnv.distributionStartTs,
         endTs: null,
         gatekeeperNetwork,
-        allowClaims
+        allowClaims,
+        hashAlgorithm,
+        totalShares,
+        declineDestination
     };
     await initialize(testEnv, initializeParams)
 }
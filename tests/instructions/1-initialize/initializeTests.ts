@@ -12,7 +12,8 @@ import { assertInstructionWillFail } from "../helpers";
  * 
  * This test suite:
  * 1. Verifies that the initialization cannot occur under a variety of incorrect parameters/conditions
- * 2. Verifies that the initialization can occur under proper parameters/conditions
+ * 2. Verifies that the initialization can occur under proper parameters/conditions, recording the
+ *    acknowledged freeze authority of the mint
  * 3. Verifies the tree cannot be reinitialized
  */
 export async function initializeTests(testEnv: TestEnvironment) {
@@ -143,6 +144,30 @@ export async function initializeTests(testEnv: TestEnvironment) {
                 expectedAnchorError: "BatchIdTooLong"
             });
         });
+        it('Cannot initialize with mint decimals that do not match the mint', async () => {
+            const incorrectParams: Initialize = {
+                ...correctParams,
+                mintDecimals: 9,
+            };
+            await assertInstructionWillFail({
+                testEnv,
+                params: incorrectParams,
+                executeInstruction: initialize,
+                expectedAnchorError: "InvalidMintDecimals"
+            });
+        });
+        it('Cannot initialize without acknowledging the mint\'s freeze authority', async () => {
+            const incorrectParams: Initialize = {
+                ...correctParams,
+                acknowledgeMintRisks: false,
+            };
+            await assertInstructionWillFail({
+                testEnv,
+                params: incorrectParams,
+                executeInstruction: initialize,
+                expectedAnchorError: "UnacknowledgedMintRisks"
+            });
+        });
         it('Initializes successfully with correct parameters', async () => {
             await initialize(testEnv, correctParams);
         });
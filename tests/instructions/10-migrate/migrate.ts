@@ -0,0 +1,53 @@
+import { TestEnvironment } from "../../utils/environment/test-environment";
+import { PublicKey, Keypair, SystemProgram } from '@solana/web3.js';
+import { assert } from 'chai';
+import { CURRENT_VERSION } from "../../utils/constants";
+import { calculateAccountSize } from "../helpers";
+
+export interface Migrate {
+    authority: Keypair,
+    distributionTreePda: PublicKey,
+    batchId: string,
+}
+
+export async function migrate(testEnv: TestEnvironment, migrate: Migrate) {
+    try {
+        const initialDistributionTreeData = await testEnv.program.account.distributionTree.fetchNullable(migrate.distributionTreePda);
+
+        await testEnv.program.methods
+            .migrate({ batchId: migrate.batchId })
+            .accountsPartial({
+                authority: migrate.authority.publicKey,
+                distributionTree: migrate.distributionTreePda,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([migrate.authority])
+            .rpc({ commitment: "processed" });
+
+        // Fetch and assert the migrated DistributionTree account data
+        const distributionTreeData = await testEnv.program.account.distributionTree.fetch(migrate.distributionTreePda);
+        assert.strictEqual(distributionTreeData.version.toNumber(), CURRENT_VERSION);
+        assert.strictEqual(distributionTreeData.authority.toString(), migrate.authority.publicKey.toString());
+        assert.strictEqual(distributionTreeData.batchId, migrate.batchId);
+        if (initialDistributionTreeData) {
+            assert.deepStrictEqual(distributionTreeData.status, initialDistributionTreeData.status);
+            assert.strictEqual(distributionTreeData.numberDistributed.toNumber(), initialDistributionTreeData.numberDistributed.toNumber());
+            assert.isTrue(Buffer.from(distributionTreeData.merkleRoot).equals(Buffer.from(initialDistributionTreeData.merkleRoot)));
+        }
+
+        // Assert the account was reallocated for the current layout and remains rent exempt
+        const accountInfo = await testEnv.program.provider.connection.getAccountInfo(migrate.distributionTreePda);
+        if (!accountInfo) {
+            throw new Error("Account info not found");
+        }
+        const expectedAccountSize = calculateAccountSize(
+            distributionTreeData.recipientsDistributedBitmap.length,
+            !!distributionTreeData.gatekeeperNetwork
+        );
+        assert.isAtLeast(accountInfo.data.length, expectedAccountSize);
+        const minimumRent = await testEnv.program.provider.connection.getMinimumBalanceForRentExemption(accountInfo.data.length);
+        assert.isAtLeast(accountInfo.lamports, minimumRent);
+    } catch (error) {
+        throw error;
+    }
+}
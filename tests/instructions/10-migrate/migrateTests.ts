@@ -0,0 +1,127 @@
+import { TestEnvironment } from "../../utils/environment/test-environment";
+import { web3 } from "@coral-xyz/anchor";
+import { assert } from "chai";
+import { LEGACY_AUTHORITY_SECRET, LEGACY_BATCH_ID } from "../../utils/constants";
+import { createAssociatedTokenAccountIdempotent, TOKEN_2022_PROGRAM_ID } from "@solana/spl-token";
+import { getDistributionTreePDA, getUserTokenAccountAddress } from "../../utils/pdas";
+import { airdropToMultiple } from "../../utils/solana-helpers";
+import { assertInstructionWillFail } from "../helpers";
+import { createNewDistributionTree } from "../1-initialize/initialize";
+import { Migrate, migrate } from "./migrate";
+
+/**
+ * MIGRATE INSTRUCTION TESTS
+ * 
+ * @param testEnv 
+ * 
+ * This test suite uses a v1 Distribution Tree loaded at genesis (tests/genesis/legacy-distribution-tree-v1.json) and:
+ *  1. Verifies the legacy tree is rejected by other instructions until it is migrated
+ *  2. Verifies the tree cannot be migrated by the wrong authority
+ *  3. Verifies the tree can be migrated, preserving its state and v1 (non domain-separated) hashing
+ *  4. Verifies the tree cannot be migrated twice and can be closed after migrating
+ *  5. Verifies trees created on the current version cannot be migrated
+ */
+export async function migrateTests(testEnv: TestEnvironment) {
+    const legacyAuthority = web3.Keypair.fromSecretKey(new Uint8Array(LEGACY_AUTHORITY_SECRET));
+    let correctParams: Migrate;
+    // The legacy tree's token vault was never created, so close has nothing to sweep
+    const legacyTokenVault = new web3.PublicKey(Buffer.alloc(32, 9));
+
+    const closeLegacyTree = async (testEnv: TestEnvironment, params: Migrate) => {
+        await testEnv.program.methods
+            .close({ batchId: params.batchId, acknowledgeIrreversible: true })
+            .accountsPartial({
+                authority: params.authority.publicKey,
+                distributionTree: params.distributionTreePda,
+                mint: testEnv.pyUsdMint,
+                tokenVault: legacyTokenVault,
+                authorityTokenAccount: getUserTokenAccountAddress({ recipient: params.authority.publicKey, mint: testEnv.pyUsdMint }),
+                tokenProgram: TOKEN_2022_PROGRAM_ID,
+                systemProgram: web3.SystemProgram.programId,
+            })
+            .signers([params.authority])
+            .rpc({ commitment: "processed" });
+    };
+
+    describe('Migrating a v1 distribution tree', () => {
+        before('Set Migrate Params', async () => {
+            await airdropToMultiple([legacyAuthority.publicKey], testEnv.provider.connection, web3.LAMPORTS_PER_SOL);
+            await createAssociatedTokenAccountIdempotent(
+                testEnv.provider.connection,
+                legacyAuthority,
+                testEnv.pyUsdMint,
+                legacyAuthority.publicKey,
+                { commitment: 'processed', skipPreflight: true },
+                TOKEN_2022_PROGRAM_ID
+            );
+            correctParams = {
+                authority: legacyAuthority,
+                distributionTreePda: getDistributionTreePDA({
+                    distributorProgram: testEnv.program.programId,
+                    authority: legacyAuthority.publicKey,
+                    batchId: LEGACY_BATCH_ID
+                }),
+                batchId: LEGACY_BATCH_ID,
+            };
+        });
+
+        it('Cannot close a legacy tree before migrating', async () => {
+            await assertInstructionWillFail({
+                testEnv,
+                params: correctParams,
+                executeInstruction: closeLegacyTree,
+                expectedAnchorError: "UnsupportedVersion"
+            });
+        });
+
+        it('Cannot migrate with an incorrect authority', async () => {
+            await assertInstructionWillFail({
+                testEnv,
+                params: { ...correctParams, authority: testEnv.wrongAuthority },
+                executeInstruction: migrate,
+                expectedAnchorError: "ConstraintSeeds"
+            });
+        });
+
+        it('Can migrate a legacy tree to the current version', async () => {
+            await migrate(testEnv, correctParams);
+            const distributionTreeData = await testEnv.program.account.distributionTree.fetch(correctParams.distributionTreePda);
+            assert.deepStrictEqual(distributionTreeData.hashAlgorithm, { keccak: {} });
+            assert.isFalse(distributionTreeData.domainSeparated, "v1 trees must keep v1 hashing");
+        });
+
+        it('Cannot migrate the same tree twice', async () => {
+            await assertInstructionWillFail({
+                testEnv,
+                params: correctParams,
+                executeInstruction: migrate,
+                expectedAnchorError: "AlreadyCurrentVersion"
+            });
+        });
+
+        it('Can close the tree after migrating', async () => {
+            await closeLegacyTree(testEnv, correctParams);
+            const distributionTreeData = await testEnv.program.account.distributionTree.fetchNullable(correctParams.distributionTreePda);
+            assert.isNull(distributionTreeData, "Distribution Tree should be closed");
+        });
+    });
+
+    describe('Migrating a current distribution tree', () => {
+        before('Initializes a new distribution tree', async () => {
+            await createNewDistributionTree({ testEnv, numPayments: 5 });
+        });
+
+        it('Cannot migrate a tree that is already on the current version', async () => {
+            await assertInstructionWillFail({
+                testEnv,
+                params: {
+                    authority: testEnv.authority,
+                    distributionTreePda: testEnv.distributionTreePda,
+                    batchId: testEnv.distributionUniqueId,
+                },
+                executeInstruction: migrate,
+                expectedAnchorError: "AlreadyCurrentVersion"
+            });
+        });
+    });
+}
@@ -0,0 +1,88 @@
+import { TestEnvironment } from "../../utils/environment/test-environment";
+import { createNewDistributionTree } from "../1-initialize/initialize";
+import { createDistributeParams, distribute, distributeAllPayments } from "../2-distribute/distribute";
+import { assertInstructionWillFail } from "../helpers";
+import { createNewEpochParams, NewEpoch, newEpoch } from "./newEpoch";
+
+/**
+ * NEW EPOCH INSTRUCTION TESTS
+ * 
+ * @param testEnv 
+ * 
+ * This test suite initializes a new Distribution Tree and then:
+ *  1. Verifies a new epoch cannot start while the current epoch is in progress
+ *  2. Distributes all payments for epoch 0
+ *  3. Verifies a new epoch cannot be started by the wrong authority
+ *  4. Starts epoch 1 with a new merkle root and funding, resetting the bitmap
+ *  5. Verifies distributions for a stale epoch are rejected
+ *  6. Distributes all payments for epoch 1
+ *  7. Starts epoch 2 as a pro-rata epoch, growing the account for the new total_shares
+ */
+export async function epochTests(testEnv: TestEnvironment) {
+    const totalNumberRecipients = 5;
+    let correctParams: NewEpoch;
+
+    describe('Recurring distributions across epochs', () => {
+        before('Initializes a new distribution tree', async () => {
+            await createNewDistributionTree({ testEnv, numPayments: totalNumberRecipients });
+        });
+
+        it('Cannot start a new epoch while the current epoch is in progress', async () => {
+            const { merkleRoot } = testEnv.merkleDistributorInfo;
+            await assertInstructionWillFail({
+                testEnv,
+                params: {
+                    authority: testEnv.authority,
+                    distributionTreePda: testEnv.distributionTreePda,
+                    mint: testEnv.pyUsdMint,
+                    tokenSource: testEnv.tokenSource,
+                    tokenVault: testEnv.tokenVault,
+                    merkleRoot,
+                    batchId: testEnv.distributionUniqueId,
+                    totalNumberRecipients,
+                    transferToVaultAmount: 0,
+                    startTs: testEnv.distributionStartTs,
+                    endTs: null,
+                },
+                executeInstruction: newEpoch,
+                expectedAnchorError: "EpochInProgress"
+            });
+        });
+
+        it('Can distribute all payments for epoch 0', async () => {
+            await distributeAllPayments({ testEnv, totalNumberRecipients });
+        });
+
+        it('Cannot start a new epoch with an incorrect authority', async () => {
+            correctParams = await createNewEpochParams(testEnv, totalNumberRecipients);
+            await assertInstructionWillFail({
+                testEnv,
+                params: { ...correctParams, authority: testEnv.wrongAuthority },
+                executeInstruction: newEpoch,
+            });
+        });
+
+        it('Can start epoch 1 with a new merkle root and funding', async () => {
+            await newEpoch(testEnv, correctParams);
+        });
+
+        it('Cannot distribute with a stale epoch', async () => {
+            const distributeParams = await createDistributeParams({ testEnv, index: 0 });
+            await assertInstructionWillFail({
+                testEnv,
+                params: { ...distributeParams, epoch: testEnv.epoch - 1 },
+                executeInstruction: distribute,
+                expectedAnchorError: "InvalidEpoch"
+            });
+        });
+
+        it('Can distribute all payments for epoch 1', async () => {
+            await distributeAllPayments({ testEnv, totalNumberRecipients });
+        });
+
+        it('Can start a pro-rata epoch 2 on a fixed amount tree', async () => {
+            const proRataParams = await createNewEpochParams(testEnv, totalNumberRecipients);
+            await newEpoch(testEnv, { ...proRataParams, totalShares: proRataParams.transferToVaultAmount });
+        });
+    });
+}
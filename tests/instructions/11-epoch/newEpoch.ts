@@ -0,0 +1,107 @@
+import { TestEnvironment } from "../../utils/environment/test-environment";
+import { PublicKey, Keypair, SystemProgram } from '@solana/web3.js';
+import { ASSOCIATED_TOKEN_PROGRAM_ID, getAssociatedTokenAddressSync, TOKEN_2022_PROGRAM_ID } from "@solana/spl-token";
+import { BN, BorshCoder, EventParser } from '@coral-xyz/anchor';
+import { assert } from 'chai';
+
+export interface NewEpoch {
+    authority: Keypair,
+    distributionTreePda: PublicKey,
+    mint: PublicKey,
+    tokenSource: PublicKey,
+    tokenVault: PublicKey,
+    merkleRoot: Buffer,
+    batchId: string,
+    totalNumberRecipients: number,
+    transferToVaultAmount: number,
+    startTs: number,
+    endTs: number | null,
+    totalShares?: number,
+}
+
+export async function newEpoch(
+    testEnv: TestEnvironment,
+    newEpoch: NewEpoch,
+) {
+    const newEpochParams = {
+        batchId: newEpoch.batchId,
+        merkleRoot: Array.from(newEpoch.merkleRoot),
+        totalNumberRecipients: new BN(newEpoch.totalNumberRecipients),
+        transferToVaultAmount: new BN(newEpoch.transferToVaultAmount),
+        startTs: new BN(newEpoch.startTs),
+        endTs: newEpoch.endTs ? new BN(newEpoch.endTs) : null,
+        totalShares: newEpoch.totalShares ? new BN(newEpoch.totalShares) : null,
+    };
+
+    const accounts = {
+        authority: newEpoch.authority.publicKey,
+        distributionTree: newEpoch.distributionTreePda,
+        mint: newEpoch.mint,
+        tokenSource: newEpoch.tokenSource,
+        tokenVault: newEpoch.tokenVault,
+        feesTokenAccount: getAssociatedTokenAddressSync(newEpoch.mint, testEnv.feesWallet.publicKey, false, TOKEN_2022_PROGRAM_ID),
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+    };
+
+    try {
+        const initialDistributionTreeData = await testEnv.program.account.distributionTree.fetch(newEpoch.distributionTreePda);
+        const initialVaultBalance = await testEnv.program.provider.connection.getTokenAccountBalance(newEpoch.tokenVault);
+
+        const txid = await testEnv.program.methods.newEpoch(newEpochParams)
+            .accountsPartial(accounts)
+            .signers([newEpoch.authority])
+            .rpc({ commitment: "confirmed" });
+
+        // Fetch and assert the DistributionTree account data
+        const distributionTreeData = await testEnv.program.account.distributionTree.fetch(newEpoch.distributionTreePda);
+        const expectedEpoch = initialDistributionTreeData.epoch.toNumber() + 1;
+        assert.strictEqual(distributionTreeData.epoch.toNumber(), expectedEpoch);
+        assert.isTrue(Buffer.from(distributionTreeData.merkleRoot).equals(newEpoch.merkleRoot));
+        assert.strictEqual(distributionTreeData.totalNumberRecipients.toNumber(), newEpoch.totalNumberRecipients);
+        assert.strictEqual(distributionTreeData.numberDistributed.toNumber(), 0);
+        assert.strictEqual(distributionTreeData.startTs.toNumber(), newEpoch.startTs);
+        assert.strictEqual(distributionTreeData.totalShares?.toNumber() ?? null, newEpoch.totalShares ?? null);
+        assert.deepStrictEqual(distributionTreeData.status, { active: {} });
+        distributionTreeData.recipientsDistributedBitmap.forEach((bitmap, index) => {
+            assert.isTrue(bitmap.isZero(), `Bitmap element ${index} should be reset`);
+        });
+
+        // Assert the vault received the epoch's funding
+        const tokenVaultBalance = await testEnv.program.provider.connection.getTokenAccountBalance(newEpoch.tokenVault);
+        const vaultBalanceChange = BigInt(tokenVaultBalance.value.amount) - BigInt(initialVaultBalance.value.amount);
+        assert.strictEqual(vaultBalanceChange.toString(), newEpoch.transferToVaultAmount.toString());
+
+        // Assert the EpochStarted event records the new root
+        const transaction = await testEnv.program.provider.connection.getTransaction(txid, {
+            commitment: "confirmed",
+            maxSupportedTransactionVersion: 0
+        });
+        const eventParser = new EventParser(testEnv.program.programId, new BorshCoder(testEnv.program.idl));
+        const events = [...eventParser.parseLogs(transaction?.meta?.logMessages ?? [])];
+        const epochStarted = events.find(event => event.name === "epochStarted");
+        assert.ok(epochStarted, "EpochStarted event should be emitted");
+        assert.strictEqual(epochStarted.data.epoch.toNumber(), expectedEpoch);
+        assert.isTrue(Buffer.from(epochStarted.data.merkleRoot).equals(newEpoch.merkleRoot));
+    } catch (error) {
+        throw error;
+    }
+}
+
+export async function createNewEpochParams(testEnv: TestEnvironment, numPayments: number): Promise<NewEpoch> {
+    await testEnv.newEpoch({ numPayments });
+    return {
+        authority: testEnv.authority,
+        distributionTreePda: testEnv.distributionTreePda,
+        mint: testEnv.pyUsdMint,
+        tokenSource: testEnv.tokenSource,
+        tokenVault: testEnv.tokenVault,
+        merkleRoot: testEnv.balanceTree.getRoot(),
+        batchId: testEnv.distributionUniqueId,
+        totalNumberRecipients: testEnv.merkleDistributorInfo.payments.length,
+        transferToVaultAmount: testEnv.merkleDistributorInfo.payments.reduce((sum, payment) => sum + payment.amount.toNumber(), 0),
+        startTs: testEnv.distributionStartTs,
+        endTs: null,
+    };
+}
@@ -0,0 +1,90 @@
+import { TestEnvironment } from "../../utils/environment/test-environment";
+import { BASE_PAYMENT_AMOUNT } from "../../utils/constants";
+import { createNewDistributionTree } from "../1-initialize/initialize";
+import { createDistributeParams, distribute } from "../2-distribute/distribute";
+import { assertInstructionWillFail, verifyTreeComplete } from "../helpers";
+import { calculateProRataPayout, TopUp, topUp } from "./topUp";
+import { assert } from 'chai';
+
+/**
+ * PRO-RATA DISTRIBUTION TESTS
+ * 
+ * @param testEnv 
+ * 
+ * This test suite initializes a pro-rata Distribution Tree whose leaves commit to shares and then:
+ *  1. Pays the first recipient its share of the vault
+ *  2. Verifies a top up cannot be made by the wrong authority or for zero tokens
+ *  3. Tops up the vault, raising the payout of every unpaid recipient
+ *  4. Pays the remaining recipients, the last of which receives the rounding dust
+ *  5. Verifies the vault is empty and the tree is complete
+ */
+export async function proRataTests(testEnv: TestEnvironment) {
+    const totalNumberRecipients = 4;
+    // Shares are the leaf amounts: 1, 2, 3 and 4 x BASE_PAYMENT_AMOUNT
+    const totalShares = 10 * BASE_PAYMENT_AMOUNT;
+    // An uneven pool so that payouts leave rounding dust behind
+    const initialPool = 7 * BASE_PAYMENT_AMOUNT + 1;
+    let correctTopUpParams: TopUp;
+
+    describe('Pro-rata distributions', () => {
+        before('Initializes a new pro-rata distribution tree', async () => {
+            await createNewDistributionTree({
+                testEnv,
+                numPayments: totalNumberRecipients,
+                totalShares,
+                transferToVaultAmount: initialPool,
+            });
+            correctTopUpParams = {
+                authority: testEnv.authority,
+                distributionTreePda: testEnv.distributionTreePda,
+                mint: testEnv.pyUsdMint,
+                tokenSource: testEnv.tokenSource,
+                tokenVault: testEnv.tokenVault,
+                batchId: testEnv.distributionUniqueId,
+                amount: 3 * BASE_PAYMENT_AMOUNT,
+            };
+        });
+
+        it('Pays the first recipient its share of the vault', async () => {
+            const distributeParams = await createDistributeParams({ testEnv, index: 0 });
+            const expectedPayout = await calculateProRataPayout(testEnv, distributeParams.amount);
+            await distribute(testEnv, { ...distributeParams, expectedPayout }, undefined, false, false, false);
+        });
+
+        it('Cannot top up with an incorrect authority', async () => {
+            await assertInstructionWillFail({
+                testEnv,
+                params: { ...correctTopUpParams, authority: testEnv.wrongAuthority },
+                executeInstruction: topUp,
+            });
+        });
+
+        it('Cannot top up with zero tokens', async () => {
+            await assertInstructionWillFail({
+                testEnv,
+                params: { ...correctTopUpParams, amount: 0 },
+                executeInstruction: topUp,
+                expectedAnchorError: "ZeroTransferAmount"
+            });
+        });
+
+        it('Can top up the vault, raising the remaining payouts', async () => {
+            const distributeParams = await createDistributeParams({ testEnv, index: 1 });
+            const payoutBefore = await calculateProRataPayout(testEnv, distributeParams.amount);
+            await topUp(testEnv, correctTopUpParams);
+            const payoutAfter = await calculateProRataPayout(testEnv, distributeParams.amount);
+            assert.isTrue(payoutAfter.gt(payoutBefore), "Top up should raise the payout of unpaid recipients");
+        });
+
+        it('Pays the remaining recipients and sweeps the dust to the last', async () => {
+            for (let index = 1; index < totalNumberRecipients; index++) {
+                const distributeParams = await createDistributeParams({ testEnv, index });
+                const expectedPayout = await calculateProRataPayout(testEnv, distributeParams.amount);
+                await distribute(testEnv, { ...distributeParams, expectedPayout }, undefined, false, false, false);
+            }
+            const vaultBalance = await testEnv.program.provider.connection.getTokenAccountBalance(testEnv.tokenVault);
+            assert.strictEqual(vaultBalance.value.amount, "0");
+            await verifyTreeComplete(testEnv, totalNumberRecipients);
+        });
+    });
+}
@@ -0,0 +1,64 @@
+import { TestEnvironment } from "../../utils/environment/test-environment";
+import { PublicKey, Keypair, SystemProgram } from '@solana/web3.js';
+import { ASSOCIATED_TOKEN_PROGRAM_ID, getAssociatedTokenAddressSync, TOKEN_2022_PROGRAM_ID } from "@solana/spl-token";
+import { BN } from '@coral-xyz/anchor';
+import { assert } from 'chai';
+
+export interface TopUp {
+    authority: Keypair,
+    distributionTreePda: PublicKey,
+    mint: PublicKey,
+    tokenSource: PublicKey,
+    tokenVault: PublicKey,
+    batchId: string,
+    amount: number,
+}
+
+export async function topUp(
+    testEnv: TestEnvironment,
+    topUp: TopUp,
+) {
+    const topUpParams = {
+        batchId: topUp.batchId,
+        amount: new BN(topUp.amount),
+    };
+
+    const accounts = {
+        authority: topUp.authority.publicKey,
+        distributionTree: topUp.distributionTreePda,
+        mint: topUp.mint,
+        tokenSource: topUp.tokenSource,
+        tokenVault: topUp.tokenVault,
+        feesTokenAccount: getAssociatedTokenAddressSync(topUp.mint, testEnv.feesWallet.publicKey, false, TOKEN_2022_PROGRAM_ID),
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+    };
+
+    try {
+        const initialVaultBalance = await testEnv.program.provider.connection.getTokenAccountBalance(topUp.tokenVault);
+
+        await testEnv.program.methods.topUp(topUpParams)
+            .accountsPartial(accounts)
+            .signers([topUp.authority])
+            .rpc({ commitment: "processed" });
+
+        // Assert the vault received the top up
+        const tokenVaultBalance = await testEnv.program.provider.connection.getTokenAccountBalance(topUp.tokenVault);
+        const vaultBalanceChange = BigInt(tokenVaultBalance.value.amount) - BigInt(initialVaultBalance.value.amount);
+        assert.strictEqual(vaultBalanceChange.toString(), topUp.amount.toString());
+    } catch (error) {
+        throw error;
+    }
+}
+
+/**
+ * Mirrors DistributionTree::settle_payout for a pro-rata tree:
+ *  payout = vault_balance * shares / (total_shares - distributed_shares), rounded down
+ */
+export async function calculateProRataPayout(testEnv: TestEnvironment, shares: BN): Promise<BN> {
+    const distributionTreeData = await testEnv.program.account.distributionTree.fetch(testEnv.distributionTreePda);
+    const vaultBalance = await testEnv.program.provider.connection.getTokenAccountBalance(testEnv.tokenVault);
+    const remainingShares = distributionTreeData.totalShares!.sub(distributionTreeData.distributedShares);
+    return new BN(vaultBalance.value.amount).mul(shares).div(remainingShares);
+}
@@ -0,0 +1,72 @@
+import { TestEnvironment } from "../../utils/environment/test-environment";
+import { PublicKey, Keypair, SystemProgram } from '@solana/web3.js';
+import { ASSOCIATED_TOKEN_PROGRAM_ID, TOKEN_2022_PROGRAM_ID } from "@solana/spl-token";
+import { BN } from '@coral-xyz/anchor';
+import { assert } from 'chai';
+import { getClaimRecordPDA } from "../../utils/pdas";
+
+export interface ClaimOpen {
+    claimant: Keypair,
+    distributionTreePda: PublicKey,
+    mint: PublicKey,
+    tokenVault: PublicKey,
+    claimantTokenAccount: PublicKey,
+    batchId: string,
+    gatewayToken: PublicKey,
+    expectedAmount: number,
+    epoch?: number,
+}
+
+export async function claimOpen(
+    testEnv: TestEnvironment,
+    claimOpen: ClaimOpen,
+) {
+    const epoch = claimOpen.epoch ?? testEnv.epoch;
+    const claimOpenParams = {
+        batchId: claimOpen.batchId,
+        epoch: new BN(epoch),
+    };
+
+    const claimRecord = getClaimRecordPDA({
+        distributorProgram: testEnv.program.programId,
+        distributionTree: claimOpen.distributionTreePda,
+        claimant: claimOpen.claimant.publicKey,
+        epoch
+    });
+
+    const accounts = {
+        claimant: claimOpen.claimant.publicKey,
+        distributionTree: claimOpen.distributionTreePda,
+        claimRecord,
+        mint: claimOpen.mint,
+        tokenVault: claimOpen.tokenVault,
+        claimantTokenAccount: claimOpen.claimantTokenAccount,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        gatewayToken: claimOpen.gatewayToken,
+    };
+
+    const initialClaimantBalance = await testEnv.program.provider.connection.getTokenAccountBalance(claimOpen.claimantTokenAccount).catch(() => ({ value: { amount: '0' } }));
+
+    try {
+        await testEnv.program.methods.claimOpen(claimOpenParams)
+            .accountsPartial(accounts)
+            .signers([claimOpen.claimant])
+            .rpc({ commitment: "processed" });
+
+        // Fetch and assert the ClaimRecord account data
+        const claimRecordData = await testEnv.program.account.claimRecord.fetch(claimRecord);
+        assert.strictEqual(claimRecordData.distributionTree.toString(), claimOpen.distributionTreePda.toString());
+        assert.strictEqual(claimRecordData.claimant.toString(), claimOpen.claimant.publicKey.toString());
+        assert.strictEqual(claimRecordData.epoch.toNumber(), epoch);
+        assert.strictEqual(claimRecordData.amount.toNumber(), claimOpen.expectedAmount);
+
+        // Fetch and assert the claimant token account data
+        const claimantTokenAccountData = await testEnv.program.provider.connection.getTokenAccountBalance(claimOpen.claimantTokenAccount);
+        const claimantBalanceChange = BigInt(claimantTokenAccountData.value.amount) - BigInt(initialClaimantBalance.value.amount);
+        assert.strictEqual(claimantBalanceChange.toString(), claimOpen.expectedAmount.toString());
+    } catch (error) {
+        throw error;
+    }
+}
@@ -0,0 +1,121 @@
+import { TestEnvironment } from "../../utils/environment/test-environment";
+import { web3 } from "@coral-xyz/anchor";
+import { BASE_PAYMENT_AMOUNT } from "../../utils/constants";
+import { airdropToMultiple } from "../../utils/solana-helpers";
+import { gatewayAuthorizeAccount } from "../../utils/civic/authorize";
+import { getUserTokenAccountAddress } from "../../utils/pdas";
+import { initialize, Initialize } from "../1-initialize/initialize";
+import { assertInstructionWillFail } from "../helpers";
+import { ClaimOpen, claimOpen } from "./claimOpen";
+import { assert } from 'chai';
+
+/**
+ * OPEN-ENROLLMENT CLAIM TESTS
+ * 
+ * @param testEnv 
+ * 
+ * This test suite initializes an open-enrollment Distribution Tree (no merkle list) capped at 2 claimants and then:
+ *  1. Verifies an open-enrollment tree requires a gatekeeper network
+ *  2. Fails to claim without a valid Gateway Token
+ *  3. Claims the fixed amount with a Gateway Token
+ *  4. Fails to claim twice with the same wallet
+ *  5. Claims with a second wallet, completing the distribution
+ *  6. Fails to claim once the claimant cap is reached
+ */
+export async function openEnrollmentTests(testEnv: TestEnvironment) {
+    const totalNumberRecipients = 2;
+    const openClaimAmount = 5 * BASE_PAYMENT_AMOUNT;
+    const claimants = Array.from({ length: totalNumberRecipients + 1 }, () => web3.Keypair.generate());
+    let initializeParams: Initialize;
+
+    const createClaimOpenParams = async (claimant: web3.Keypair, authorize = true): Promise<ClaimOpen> => {
+        const gatewayToken = authorize
+            ? (await gatewayAuthorizeAccount({ testEnv, account: claimant.publicKey })).publicKey
+            : web3.Keypair.generate().publicKey;
+        return {
+            claimant,
+            distributionTreePda: testEnv.distributionTreePda,
+            mint: testEnv.pyUsdMint,
+            tokenVault: testEnv.tokenVault,
+            claimantTokenAccount: getUserTokenAccountAddress({ recipient: claimant.publicKey, mint: testEnv.pyUsdMint }),
+            batchId: testEnv.distributionUniqueId,
+            gatewayToken,
+            expectedAmount: openClaimAmount,
+        };
+    };
+
+    describe('Open-enrollment claims without a merkle list', () => {
+        before('Prepares an open-enrollment distribution tree', async () => {
+            await testEnv.newTree({ numPayments: totalNumberRecipients });
+            await airdropToMultiple(claimants.map(claimant => claimant.publicKey), testEnv.provider.connection, web3.LAMPORTS_PER_SOL);
+            initializeParams = {
+                authority: testEnv.authority,
+                distributionTreePda: testEnv.distributionTreePda,
+                mint: testEnv.pyUsdMint,
+                tokenSource: testEnv.tokenSource,
+                tokenVault: testEnv.tokenVault,
+                merkleRoot: Buffer.alloc(32),
+                batchId: testEnv.distributionUniqueId,
+                totalNumberRecipients,
+                transferToVaultAmount: totalNumberRecipients * openClaimAmount,
+                mintDecimals: 6,
+                startTs: testEnv.distributionStartTs,
+                endTs: null,
+                allowClaims: true,
+                gatekeeperNetwork: testEnv.civicConfig.gatekeeperNetwork.publicKey,
+                openClaimAmount,
+            };
+        });
+
+        it('Cannot initialize an open-enrollment tree without a gatekeeper network', async () => {
+            await assertInstructionWillFail({
+                testEnv,
+                params: { ...initializeParams, gatekeeperNetwork: undefined },
+                executeInstruction: initialize,
+                expectedAnchorError: "InvalidOpenEnrollment"
+            });
+        });
+
+        it('Can initialize an open-enrollment tree', async () => {
+            await initialize(testEnv, initializeParams);
+        });
+
+        it('Cannot claim without a Gateway Token', async () => {
+            await assertInstructionWillFail({
+                testEnv,
+                params: await createClaimOpenParams(claimants[0], false),
+                executeInstruction: claimOpen,
+                expectedAnchorError: "InvalidGatewayToken"
+            });
+        });
+
+        it('Can claim the fixed amount with a Gateway Token', async () => {
+            await claimOpen(testEnv, await createClaimOpenParams(claimants[0]));
+        });
+
+        it('Cannot claim twice with the same wallet', async () => {
+            await assertInstructionWillFail({
+                testEnv,
+                params: await createClaimOpenParams(claimants[0]),
+                executeInstruction: claimOpen,
+                expectedTransactionError: "already in use"
+            });
+        });
+
+        it('Can claim with a second wallet, completing the distribution', async () => {
+            await claimOpen(testEnv, await createClaimOpenParams(claimants[1]));
+            const distributionTreeData = await testEnv.program.account.distributionTree.fetch(testEnv.distributionTreePda);
+            assert.strictEqual(distributionTreeData.numberDistributed.toNumber(), totalNumberRecipients);
+            assert.deepStrictEqual(distributionTreeData.status, { complete: {} });
+        });
+
+        it('Cannot claim once the claimant cap is reached', async () => {
+            await assertInstructionWillFail({
+                testEnv,
+                params: await createClaimOpenParams(claimants[2]),
+                executeInstruction: claimOpen,
+                expectedAnchorError: "DistributionNotActive"
+            });
+        });
+    });
+}
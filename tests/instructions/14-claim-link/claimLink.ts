@@ -0,0 +1,101 @@
+import { TestEnvironment } from "../../utils/environment/test-environment";
+import { PublicKey, Keypair, SystemProgram } from '@solana/web3.js';
+import { ASSOCIATED_TOKEN_PROGRAM_ID, TOKEN_2022_PROGRAM_ID } from "@solana/spl-token";
+import { BN } from '@coral-xyz/anchor';
+import { assert } from 'chai';
+import { InstallmentPlan, isBitSet, LeafSchedule, PayoutSplit } from "../../utils/merkle-tree";
+import { getDisputePDA, getLeafRedirectPDA, getMintRegistryPDA, getUserTokenAccountAddress } from "../../utils/pdas";
+
+export interface ClaimLink {
+    payer: Keypair,
+    linkSigner: Keypair,
+    destination: PublicKey,
+    distributionTreePda: PublicKey,
+    mint: PublicKey,
+    tokenVault: PublicKey,
+    amount: BN,
+    proof: Buffer[],
+    batchId: string,
+    index: number,
+    gatewayToken?: PublicKey,
+    epoch?: number,
+    splits?: PayoutSplit[],
+    schedule?: LeafSchedule,
+    installments?: InstallmentPlan,
+    mintIndex?: number,
+}
+
+export async function claimLink(
+    testEnv: TestEnvironment,
+    claimLink: ClaimLink,
+) {
+    const claimLinkParams = {
+        amount: claimLink.amount,
+        batchId: claimLink.batchId,
+        proof: claimLink.proof.map(buffer => Array.from(buffer)),
+        index: new BN(claimLink.index),
+        epoch: new BN(claimLink.epoch ?? testEnv.epoch),
+        splits: claimLink.splits ?? null,
+        schedule: claimLink.schedule ?? null,
+        installments: claimLink.installments ?? null,
+        mintIndex: claimLink.mintIndex ?? null,
+    };
+
+    const destinationTokenAccount = getUserTokenAccountAddress({
+        recipient: claimLink.destination,
+        mint: claimLink.mint
+    });
+
+    const accounts = {
+        payer: claimLink.payer.publicKey,
+        linkSigner: claimLink.linkSigner.publicKey,
+        distributionTree: claimLink.distributionTreePda,
+        mint: claimLink.mint,
+        tokenVault: claimLink.tokenVault,
+        destination: claimLink.destination,
+        destinationTokenAccount,
+        mintRegistry: getMintRegistryPDA({
+            distributorProgram: testEnv.program.programId,
+            distributionTree: claimLink.distributionTreePda
+        }),
+        leafRedirect: getLeafRedirectPDA({
+            distributorProgram: testEnv.program.programId,
+            distributionTree: claimLink.distributionTreePda,
+            epoch: claimLinkParams.epoch.toNumber(),
+            index: claimLink.index
+        }),
+        dispute: getDisputePDA({
+            distributorProgram: testEnv.program.programId,
+            distributionTree: claimLink.distributionTreePda,
+            epoch: claimLinkParams.epoch.toNumber(),
+            index: claimLink.index
+        }),
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        gatewayToken: claimLink.gatewayToken ?? null,
+    };
+
+    const initialDestinationBalance = await testEnv.program.provider.connection.getTokenAccountBalance(destinationTokenAccount).catch(() => ({ value: { amount: '0' } }));
+
+    try {
+        await testEnv.program.methods.claimLink(claimLinkParams)
+            .accountsPartial(accounts)
+            .signers([claimLink.payer, claimLink.linkSigner])
+            .rpc({ commitment: "processed" });
+
+        // Fetch and assert the DistributionTree account data
+        const distributionTreeData = await testEnv.program.account.distributionTree.fetch(claimLink.distributionTreePda);
+        assert.isTrue(
+            isBitSet(distributionTreeData.recipientsDistributedBitmap, claimLink.index),
+            `Bitmap not set for claim link at index ${claimLink.index}`
+        );
+
+        // Fetch and assert the destination token account data
+        const destinationTokenAccountData = await testEnv.program.provider.connection.getTokenAccountBalance(destinationTokenAccount);
+        const destinationBalanceChange = BigInt(destinationTokenAccountData.value.amount) - BigInt(initialDestinationBalance.value.amount);
+        assert.strictEqual(destinationBalanceChange.toString(), claimLink.amount.toString());
+    } catch (error) {
+        throw error;
+    }
+}
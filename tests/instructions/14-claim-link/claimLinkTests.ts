@@ -0,0 +1,126 @@
+import { TestEnvironment } from "../../utils/environment/test-environment";
+import { web3 } from "@coral-xyz/anchor";
+import { assert } from 'chai';
+import { airdropToMultiple } from "../../utils/solana-helpers";
+import { getAccountByIndex } from "../../utils/merkle-tree";
+import { createClaimLink, parseClaimLink } from "../../utils/claim-link";
+import { createNewDistributionTree } from "../1-initialize/initialize";
+import { assertInstructionWillFail } from "../helpers";
+import { redirectLeaf } from "../15-redirect/redirectLeaf";
+import { ClaimLink, claimLink } from "./claimLink";
+
+/**
+ * CLAIM LINK INSTRUCTION TESTS
+ * 
+ * @param testEnv 
+ * 
+ * This test suite initializes a new Distribution Tree with Claims enabled, whose leaves are keyed by
+ * ephemeral link keypairs (the payment keypairs stand in for the keypairs encoded in emailed claim links), and then:
+ *  1. Round-trips a claim link through its URL encoding
+ *  2. Fails to redeem with a link keypair that is not committed in the leaf
+ *  3. Redeems the link from a new wallet to an arbitrary destination
+ *  4. Fails to redeem the same link twice
+ *  5. Redirects a second link as the authority, then fails to redeem it to any wallet but the redirect destination
+ */
+export async function claimLinkTests(testEnv: TestEnvironment) {
+    const index = 0;
+    const recipientWallet = web3.Keypair.generate();
+    const destination = web3.Keypair.generate().publicKey;
+    const redirectedIndex = 1;
+    const redirectDestination = web3.Keypair.generate().publicKey;
+    let correctParams: ClaimLink;
+
+    describe('Redeem claim links from a new wallet', () => {
+        before('Initializes a new distribution tree with claims enabled', async () => {
+            await createNewDistributionTree({ testEnv, allowClaims: true });
+            await airdropToMultiple([recipientWallet.publicKey], testEnv.provider.connection, web3.LAMPORTS_PER_SOL);
+
+            const paymentInfo = getAccountByIndex(testEnv.merkleDistributorInfo, index);
+            if (!paymentInfo) {
+                throw new Error('No recipient found');
+            }
+            const link = createClaimLink('https://example.com/claim', {
+                authority: testEnv.authority.publicKey,
+                batchId: testEnv.distributionUniqueId,
+                index,
+                linkKeypair: paymentInfo.keypair,
+            });
+            const { authority, linkKeypair, batchId } = parseClaimLink(link);
+            assert.strictEqual(authority.toString(), testEnv.authority.publicKey.toString());
+
+            correctParams = {
+                payer: recipientWallet,
+                linkSigner: linkKeypair,
+                destination,
+                distributionTreePda: testEnv.distributionTreePda,
+                mint: testEnv.pyUsdMint,
+                tokenVault: testEnv.tokenVault,
+                amount: paymentInfo.amount,
+                proof: testEnv.balanceTree.getProof(index, linkKeypair.publicKey, paymentInfo.amount),
+                batchId,
+                index,
+            };
+        });
+
+        it('Recovers the committed link keypair from the claim link', async () => {
+            const paymentInfo = getAccountByIndex(testEnv.merkleDistributorInfo, index);
+            assert.strictEqual(correctParams.linkSigner.publicKey.toString(), paymentInfo?.keypair.publicKey.toString());
+        });
+
+        it('Cannot redeem with a link keypair that is not in the leaf', async () => {
+            await assertInstructionWillFail({
+                testEnv,
+                params: { ...correctParams, linkSigner: web3.Keypair.generate() },
+                executeInstruction: claimLink,
+                expectedAnchorError: "InvalidProof"
+            });
+        });
+
+        it('Can redeem the link to an arbitrary destination', async () => {
+            await claimLink(testEnv, correctParams);
+        });
+
+        it('Cannot redeem the same link twice', async () => {
+            await assertInstructionWillFail({
+                testEnv,
+                params: correctParams,
+                executeInstruction: claimLink,
+                expectedAnchorError: "AlreadyClaimed"
+            });
+        });
+
+        it('Cannot redeem a redirected link to a wallet other than the redirect destination', async () => {
+            const paymentInfo = getAccountByIndex(testEnv.merkleDistributorInfo, redirectedIndex);
+            if (!paymentInfo) {
+                throw new Error('No recipient found');
+            }
+            const linkKeypair = paymentInfo.keypair;
+            const proof = testEnv.balanceTree.getProof(redirectedIndex, linkKeypair.publicKey, paymentInfo.amount);
+            await redirectLeaf(testEnv, {
+                signer: testEnv.authority,
+                distributionTreePda: testEnv.distributionTreePda,
+                batchId: testEnv.distributionUniqueId,
+                index: redirectedIndex,
+                originalRecipient: linkKeypair.publicKey,
+                amount: paymentInfo.amount,
+                proof,
+                destination: redirectDestination,
+            });
+
+            const redirectedParams: ClaimLink = {
+                ...correctParams,
+                linkSigner: linkKeypair,
+                amount: paymentInfo.amount,
+                proof,
+                index: redirectedIndex,
+            };
+            await assertInstructionWillFail({
+                testEnv,
+                params: redirectedParams,
+                executeInstruction: claimLink,
+                expectedAnchorError: "PayeeNotRedirectDestination"
+            });
+            await claimLink(testEnv, { ...redirectedParams, destination: redirectDestination });
+        });
+    });
+}
@@ -0,0 +1,73 @@
+import { TestEnvironment } from "../../utils/environment/test-environment";
+import { PublicKey, Keypair, SystemProgram } from '@solana/web3.js';
+import { BN } from '@coral-xyz/anchor';
+import { assert } from 'chai';
+import { InstallmentPlan, LeafSchedule, PayoutSplit } from "../../utils/merkle-tree";
+import { getLeafRedirectPDA } from "../../utils/pdas";
+
+export interface RedirectLeaf {
+    signer: Keypair,
+    distributionTreePda: PublicKey,
+    batchId: string,
+    index: number,
+    originalRecipient: PublicKey,
+    amount: BN,
+    proof: Buffer[],
+    destination: PublicKey,
+    epoch?: number,
+    splits?: PayoutSplit[],
+    schedule?: LeafSchedule,
+    installments?: InstallmentPlan,
+    mintIndex?: number,
+}
+
+export async function redirectLeaf(
+    testEnv: TestEnvironment,
+    redirectLeaf: RedirectLeaf,
+) {
+    const epoch = redirectLeaf.epoch ?? testEnv.epoch;
+    const redirectLeafParams = {
+        batchId: redirectLeaf.batchId,
+        epoch: new BN(epoch),
+        index: new BN(redirectLeaf.index),
+        originalRecipient: redirectLeaf.originalRecipient,
+        amount: redirectLeaf.amount,
+        proof: redirectLeaf.proof.map(buffer => Array.from(buffer)),
+        destination: redirectLeaf.destination,
+        splits: redirectLeaf.splits ?? null,
+        schedule: redirectLeaf.schedule ?? null,
+        installments: redirectLeaf.installments ?? null,
+        mintIndex: redirectLeaf.mintIndex ?? null,
+    };
+
+    const leafRedirect = getLeafRedirectPDA({
+        distributorProgram: testEnv.program.programId,
+        distributionTree: redirectLeaf.distributionTreePda,
+        epoch,
+        index: redirectLeaf.index
+    });
+
+    const accounts = {
+        signer: redirectLeaf.signer.publicKey,
+        distributionTree: redirectLeaf.distributionTreePda,
+        leafRedirect,
+        systemProgram: SystemProgram.programId,
+    };
+
+    try {
+        await testEnv.program.methods.redirectLeaf(redirectLeafParams)
+            .accountsPartial(accounts)
+            .signers([redirectLeaf.signer])
+            .rpc({ commitment: "processed" });
+
+        // Fetch and assert the LeafRedirect account data
+        const leafRedirectData = await testEnv.program.account.leafRedirect.fetch(leafRedirect);
+        assert.strictEqual(leafRedirectData.distributionTree.toString(), redirectLeaf.distributionTreePda.toString());
+        assert.strictEqual(leafRedirectData.epoch.toNumber(), epoch);
+        assert.strictEqual(leafRedirectData.index.toNumber(), redirectLeaf.index);
+        assert.strictEqual(leafRedirectData.originalRecipient.toString(), redirectLeaf.originalRecipient.toString());
+        assert.strictEqual(leafRedirectData.destination.toString(), redirectLeaf.destination.toString());
+    } catch (error) {
+        throw error;
+    }
+}
@@ -0,0 +1,134 @@
+import { TestEnvironment } from "../../utils/environment/test-environment";
+import { web3 } from "@coral-xyz/anchor";
+import { airdropToMultiple } from "../../utils/solana-helpers";
+import { getAccountByIndex } from "../../utils/merkle-tree";
+import { getUserTokenAccountAddress } from "../../utils/pdas";
+import { createNewDistributionTree } from "../1-initialize/initialize";
+import { createDistributeParams, distribute } from "../2-distribute/distribute";
+import { claim } from "../5-claim/claim";
+import { assertInstructionWillFail } from "../helpers";
+import { RedirectLeaf, redirectLeaf } from "./redirectLeaf";
+
+/**
+ * REDIRECT LEAF INSTRUCTION TESTS
+ * 
+ * @param testEnv 
+ * 
+ * This test suite initializes a new Distribution Tree with Claims enabled and then:
+ *  1. Verifies a leaf cannot be redirected by a wallet that is neither the authority nor the leaf's key
+ *  2. Redirects leaf 0 as the authority
+ *  3. Verifies leaf 0 can no longer be paid to the original key, and distributes it to the new destination
+ *  4. Verifies a paid leaf cannot be redirected
+ *  5. Redirects leaf 1 as the leaf's key, which cannot then change the redirect itself
+ *  6. Claims leaf 1 with the new destination wallet
+ */
+export async function redirectTests(testEnv: TestEnvironment) {
+    const authorityDestination = web3.Keypair.generate();
+    const rotatedWallet = web3.Keypair.generate();
+
+    const createRedirectLeafParams = (index: number, signer: web3.Keypair, destination: web3.PublicKey): RedirectLeaf => {
+        const paymentInfo = getAccountByIndex(testEnv.merkleDistributorInfo, index);
+        if (!paymentInfo) {
+            throw new Error('No recipient found');
+        }
+        const originalRecipient = paymentInfo.keypair.publicKey;
+        return {
+            signer,
+            distributionTreePda: testEnv.distributionTreePda,
+            batchId: testEnv.distributionUniqueId,
+            index,
+            originalRecipient,
+            amount: paymentInfo.amount,
+            proof: testEnv.balanceTree.getProof(index, originalRecipient, paymentInfo.amount),
+            destination,
+        };
+    };
+
+    describe('Redirect unpaid leaves to a new wallet', () => {
+        before('Initializes a new distribution tree with claims enabled', async () => {
+            await createNewDistributionTree({ testEnv, allowClaims: true });
+            const originalKeypair = getAccountByIndex(testEnv.merkleDistributorInfo, 1)?.keypair;
+            if (!originalKeypair) {
+                throw new Error('No recipient found');
+            }
+            await airdropToMultiple([originalKeypair.publicKey, rotatedWallet.publicKey], testEnv.provider.connection, web3.LAMPORTS_PER_SOL);
+        });
+
+        it('Cannot redirect a leaf from an unrelated wallet', async () => {
+            await assertInstructionWillFail({
+                testEnv,
+                params: createRedirectLeafParams(0, testEnv.wrongAuthority, testEnv.wrongAuthority.publicKey),
+                executeInstruction: redirectLeaf,
+                expectedAnchorError: "SignerNotAuthorized"
+            });
+        });
+
+        it('Can redirect a leaf as the authority', async () => {
+            await redirectLeaf(testEnv, createRedirectLeafParams(0, testEnv.authority, authorityDestination.publicKey));
+        });
+
+        it('Cannot distribute a redirected leaf to the original key', async () => {
+            const distributeParams = await createDistributeParams({ testEnv, index: 0 });
+            await assertInstructionWillFail({
+                testEnv,
+                params: distributeParams,
+                executeInstruction: distribute,
+                expectedAnchorError: "PayeeNotRedirectDestination"
+            });
+        });
+
+        it('Can distribute a redirected leaf to the new destination', async () => {
+            const distributeParams = await createDistributeParams({ testEnv, index: 0 });
+            await distribute(testEnv, {
+                ...distributeParams,
+                recipient: authorityDestination.publicKey,
+                recipientTokenAccount: getUserTokenAccountAddress({
+                    recipient: authorityDestination.publicKey,
+                    mint: testEnv.pyUsdMint
+                }),
+            });
+        });
+
+        it('Cannot redirect a leaf that has been paid', async () => {
+            await assertInstructionWillFail({
+                testEnv,
+                params: createRedirectLeafParams(0, testEnv.authority, rotatedWallet.publicKey),
+                executeInstruction: redirectLeaf,
+                expectedAnchorError: "AlreadyClaimed"
+            });
+        });
+
+        it('Can redirect a leaf as the key committed in the leaf', async () => {
+            const originalKeypair = getAccountByIndex(testEnv.merkleDistributorInfo, 1)!.keypair;
+            await redirectLeaf(testEnv, createRedirectLeafParams(1, originalKeypair, rotatedWallet.publicKey));
+        });
+
+        it('Cannot change an existing redirect as the key committed in the leaf', async () => {
+            const originalKeypair = getAccountByIndex(testEnv.merkleDistributorInfo, 1)!.keypair;
+            await assertInstructionWillFail({
+                testEnv,
+                params: createRedirectLeafParams(1, originalKeypair, originalKeypair.publicKey),
+                executeInstruction: redirectLeaf,
+                expectedAnchorError: "SignerNotAuthorized"
+            });
+        });
+
+        it('Can claim a redirected leaf with the new wallet', async () => {
+            const { originalRecipient, amount, proof } = createRedirectLeafParams(1, testEnv.authority, rotatedWallet.publicKey);
+            await claim(testEnv, {
+                claimant: rotatedWallet,
+                distributionTreePda: testEnv.distributionTreePda,
+                mint: testEnv.pyUsdMint,
+                tokenVault: testEnv.tokenVault,
+                payeeTokenAccount: getUserTokenAccountAddress({
+                    recipient: rotatedWallet.publicKey,
+                    mint: testEnv.pyUsdMint
+                }),
+                amount,
+                proof,
+                batchId: testEnv.distributionUniqueId,
+                index: 1,
+            });
+        });
+    });
+}
@@ -0,0 +1,101 @@
+import { TestEnvironment } from "../../utils/environment/test-environment";
+import { PublicKey, Keypair, SystemProgram } from '@solana/web3.js';
+import { ASSOCIATED_TOKEN_PROGRAM_ID, TOKEN_2022_PROGRAM_ID } from "@solana/spl-token";
+import { BN } from '@coral-xyz/anchor';
+import { assert } from 'chai';
+import { InstallmentPlan, isBitSet, LeafSchedule, PayoutSplit } from "../../utils/merkle-tree";
+import { getDisputePDA, getInstallmentRecordPDA, getMintRegistryPDA, getUserTokenAccountAddress } from "../../utils/pdas";
+
+export interface Revoke {
+    authority: Keypair,
+    distributionTreePda: PublicKey,
+    mint: PublicKey,
+    tokenVault: PublicKey,
+    batchId: string,
+    index: number,
+    recipient: PublicKey,
+    amount: BN,
+    proof: Buffer[],
+    expectedClawback: BN,
+    epoch?: number,
+    splits?: PayoutSplit[],
+    schedule?: LeafSchedule,
+    installments?: InstallmentPlan,
+    mintIndex?: number,
+}
+
+export async function revoke(
+    testEnv: TestEnvironment,
+    revoke: Revoke,
+) {
+    const revokeParams = {
+        batchId: revoke.batchId,
+        epoch: new BN(revoke.epoch ?? testEnv.epoch),
+        index: new BN(revoke.index),
+        recipient: revoke.recipient,
+        amount: revoke.amount,
+        proof: revoke.proof.map(buffer => Array.from(buffer)),
+        splits: revoke.splits ?? null,
+        schedule: revoke.schedule ?? null,
+        installments: revoke.installments ?? null,
+        mintIndex: revoke.mintIndex ?? null,
+    };
+
+    const authorityTokenAccount = getUserTokenAccountAddress({
+        recipient: revoke.authority.publicKey,
+        mint: revoke.mint
+    });
+
+    const accounts = {
+        authority: revoke.authority.publicKey,
+        distributionTree: revoke.distributionTreePda,
+        mint: revoke.mint,
+        tokenVault: revoke.tokenVault,
+        authorityTokenAccount,
+        mintRegistry: getMintRegistryPDA({
+            distributorProgram: testEnv.program.programId,
+            distributionTree: revoke.distributionTreePda
+        }),
+        dispute: getDisputePDA({
+            distributorProgram: testEnv.program.programId,
+            distributionTree: revoke.distributionTreePda,
+            epoch: revokeParams.epoch.toNumber(),
+            index: revoke.index
+        }),
+        installmentRecord: getInstallmentRecordPDA({
+            distributorProgram: testEnv.program.programId,
+            distributionTree: revoke.distributionTreePda,
+            epoch: revokeParams.epoch.toNumber(),
+            index: revoke.index
+        }),
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+    };
+
+    try {
+        const initialDistributionTreeData = await testEnv.program.account.distributionTree.fetch(revoke.distributionTreePda);
+        const initialAuthorityBalance = await testEnv.program.provider.connection.getTokenAccountBalance(authorityTokenAccount).catch(() => ({ value: { amount: '0' } }));
+
+        await testEnv.program.methods.revoke(revokeParams)
+            .accountsPartial(accounts)
+            .signers([revoke.authority])
+            .rpc({ commitment: "processed" });
+
+        // Fetch and assert the DistributionTree account data
+        const distributionTreeData = await testEnv.program.account.distributionTree.fetch(revoke.distributionTreePda);
+        assert.isTrue(
+            isBitSet(distributionTreeData.recipientsDistributedBitmap, revoke.index),
+            `Bitmap not set for revoked leaf at index ${revoke.index}`
+        );
+        assert.strictEqual(distributionTreeData.numberRevoked.toNumber(), initialDistributionTreeData.numberRevoked.toNumber() + 1);
+        assert.strictEqual(distributionTreeData.numberDistributed.toNumber(), initialDistributionTreeData.numberDistributed.toNumber());
+
+        // Assert the clawback was returned to the authority
+        const authorityBalance = await testEnv.program.provider.connection.getTokenAccountBalance(authorityTokenAccount);
+        const authorityBalanceChange = BigInt(authorityBalance.value.amount) - BigInt(initialAuthorityBalance.value.amount);
+        assert.strictEqual(authorityBalanceChange.toString(), revoke.expectedClawback.toString());
+    } catch (error) {
+        throw error;
+    }
+}
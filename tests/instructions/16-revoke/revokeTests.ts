@@ -0,0 +1,142 @@
+import { TestEnvironment } from "../../utils/environment/test-environment";
+import { BN } from "@coral-xyz/anchor";
+import { assert } from 'chai';
+import { BASE_PAYMENT_AMOUNT } from "../../utils/constants";
+import { getAccountByIndex } from "../../utils/merkle-tree";
+import { createNewDistributionTree } from "../1-initialize/initialize";
+import { createDistributeParams, distribute } from "../2-distribute/distribute";
+import { assertInstructionWillFail } from "../helpers";
+import { Revoke, revoke } from "./revoke";
+
+/**
+ * REVOKE INSTRUCTION TESTS
+ * 
+ * @param testEnv 
+ * 
+ * This test suite initializes a new Distribution Tree and then:
+ *  1. Verifies a leaf cannot be revoked by the wrong authority
+ *  2. Revokes an unpaid leaf, clawing back its amount to the authority
+ *  3. Verifies a revoked leaf can neither be distributed nor revoked again
+ *  4. Distributes the remaining leaves, completing the distribution
+ *  5. Revokes a leaf of a pro-rata tree, releasing its shares to the remaining recipients
+ *  6. Revokes the last unpaid leaf of a pro-rata tree, clawing back the remaining vault balance
+ */
+export async function revokeTests(testEnv: TestEnvironment) {
+    const totalNumberRecipients = 3;
+    const revokedIndex = 1;
+
+    const createRevokeParams = (index: number, expectedClawback?: BN): Revoke => {
+        const paymentInfo = getAccountByIndex(testEnv.merkleDistributorInfo, index);
+        if (!paymentInfo) {
+            throw new Error('No recipient found');
+        }
+        const recipient = paymentInfo.keypair.publicKey;
+        return {
+            authority: testEnv.authority,
+            distributionTreePda: testEnv.distributionTreePda,
+            mint: testEnv.pyUsdMint,
+            tokenVault: testEnv.tokenVault,
+            batchId: testEnv.distributionUniqueId,
+            index,
+            recipient,
+            amount: paymentInfo.amount,
+            proof: testEnv.balanceTree.getProof(index, recipient, paymentInfo.amount),
+            expectedClawback: expectedClawback ?? paymentInfo.amount,
+        };
+    };
+
+    describe('Revoke unpaid leaves before payout', () => {
+        before('Initializes a new distribution tree', async () => {
+            await createNewDistributionTree({ testEnv, numPayments: totalNumberRecipients });
+        });
+
+        it('Cannot revoke a leaf with an incorrect authority', async () => {
+            await assertInstructionWillFail({
+                testEnv,
+                params: { ...createRevokeParams(revokedIndex), authority: testEnv.wrongAuthority },
+                executeInstruction: revoke,
+            });
+        });
+
+        it('Can revoke an unpaid leaf and claw back its amount', async () => {
+            await revoke(testEnv, createRevokeParams(revokedIndex));
+        });
+
+        it('Cannot distribute a revoked leaf', async () => {
+            const distributeParams = await createDistributeParams({ testEnv, index: revokedIndex });
+            await assertInstructionWillFail({
+                testEnv,
+                params: distributeParams,
+                executeInstruction: distribute,
+                expectedAnchorError: "AlreadyClaimed"
+            });
+        });
+
+        it('Cannot revoke a leaf twice', async () => {
+            await assertInstructionWillFail({
+                testEnv,
+                params: createRevokeParams(revokedIndex),
+                executeInstruction: revoke,
+                expectedAnchorError: "AlreadyClaimed"
+            });
+        });
+
+        it('Completes the distribution once the remaining leaves are paid', async () => {
+            for (const index of [0, 2]) {
+                const distributeParams = await createDistributeParams({ testEnv, index });
+                await distribute(testEnv, distributeParams);
+            }
+            const distributionTreeData = await testEnv.program.account.distributionTree.fetch(testEnv.distributionTreePda);
+            assert.strictEqual(distributionTreeData.numberDistributed.toNumber(), totalNumberRecipients - 1);
+            assert.strictEqual(distributionTreeData.numberRevoked.toNumber(), 1);
+            assert.deepStrictEqual(distributionTreeData.status, { complete: {} });
+            const vaultBalance = await testEnv.program.provider.connection.getTokenAccountBalance(testEnv.tokenVault);
+            assert.strictEqual(vaultBalance.value.amount, "0");
+        });
+    });
+
+    describe('Revoke leaves of a pro-rata distribution', () => {
+        before('Initializes a new pro-rata distribution tree', async () => {
+            // Shares are the leaf amounts: 1 and 2 x BASE_PAYMENT_AMOUNT
+            await createNewDistributionTree({
+                testEnv,
+                numPayments: 2,
+                totalShares: 3 * BASE_PAYMENT_AMOUNT,
+                transferToVaultAmount: 9 * BASE_PAYMENT_AMOUNT,
+            });
+        });
+
+        it('Can revoke a leaf, releasing its shares without a clawback', async () => {
+            await revoke(testEnv, createRevokeParams(0, new BN(0)));
+            const distributionTreeData = await testEnv.program.account.distributionTree.fetch(testEnv.distributionTreePda);
+            assert.strictEqual(distributionTreeData.totalShares?.toNumber(), 2 * BASE_PAYMENT_AMOUNT);
+        });
+
+        it('Pays the remaining recipient the whole vault', async () => {
+            const distributeParams = await createDistributeParams({ testEnv, index: 1 });
+            await distribute(testEnv, { ...distributeParams, expectedPayout: new BN(9 * BASE_PAYMENT_AMOUNT) });
+        });
+    });
+
+    describe('Revoke the last unpaid leaf of a pro-rata distribution', () => {
+        before('Initializes a new pro-rata distribution tree and pays the first leaf', async () => {
+            await createNewDistributionTree({
+                testEnv,
+                numPayments: 2,
+                totalShares: 3 * BASE_PAYMENT_AMOUNT,
+                transferToVaultAmount: 9 * BASE_PAYMENT_AMOUNT,
+            });
+            // 1 of 3 shares: a third of the vault
+            const distributeParams = await createDistributeParams({ testEnv, index: 0 });
+            await distribute(testEnv, { ...distributeParams, expectedPayout: new BN(3 * BASE_PAYMENT_AMOUNT) });
+        });
+
+        it('Can revoke the last unpaid leaf, clawing back the remaining vault balance', async () => {
+            await revoke(testEnv, createRevokeParams(1, new BN(6 * BASE_PAYMENT_AMOUNT)));
+            const distributionTreeData = await testEnv.program.account.distributionTree.fetch(testEnv.distributionTreePda);
+            assert.deepStrictEqual(distributionTreeData.status, { complete: {} });
+            const tokenVaultBalance = await testEnv.provider.connection.getTokenAccountBalance(testEnv.tokenVault);
+            assert.strictEqual(tokenVaultBalance.value.amount, '0');
+        });
+    });
+}
@@ -0,0 +1,117 @@
+import { TestEnvironment } from "../../utils/environment/test-environment";
+import { PublicKey, Keypair, SystemProgram } from '@solana/web3.js';
+import { TOKEN_2022_PROGRAM_ID } from "@solana/spl-token";
+import { BN } from '@coral-xyz/anchor';
+import { assert } from 'chai';
+import { InstallmentPlan, isBitSet, LeafSchedule, PayoutSplit } from "../../utils/merkle-tree";
+import { getDeclineRecordPDA, getDisputePDA, getInstallmentRecordPDA, getLeafRedirectPDA, getMintRegistryPDA } from "../../utils/pdas";
+
+export interface Decline {
+    recipient: Keypair,
+    distributionTreePda: PublicKey,
+    mint: PublicKey,
+    tokenVault: PublicKey,
+    batchId: string,
+    index: number,
+    amount: BN,
+    proof: Buffer[],
+    declineDestinationTokenAccount?: PublicKey,
+    expectedForwardedAmount: BN,
+    epoch?: number,
+    splits?: PayoutSplit[],
+    schedule?: LeafSchedule,
+    installments?: InstallmentPlan,
+    mintIndex?: number,
+}
+
+export async function decline(
+    testEnv: TestEnvironment,
+    decline: Decline,
+) {
+    const epoch = decline.epoch ?? testEnv.epoch;
+    const declineParams = {
+        batchId: decline.batchId,
+        epoch: new BN(epoch),
+        index: new BN(decline.index),
+        amount: decline.amount,
+        proof: decline.proof.map(buffer => Array.from(buffer)),
+        splits: decline.splits ?? null,
+        schedule: decline.schedule ?? null,
+        installments: decline.installments ?? null,
+        mintIndex: decline.mintIndex ?? null,
+    };
+
+    const declineRecord = getDeclineRecordPDA({
+        distributorProgram: testEnv.program.programId,
+        distributionTree: decline.distributionTreePda,
+        epoch,
+        index: decline.index
+    });
+
+    const accounts = {
+        recipient: decline.recipient.publicKey,
+        distributionTree: decline.distributionTreePda,
+        declineRecord,
+        leafRedirect: getLeafRedirectPDA({
+            distributorProgram: testEnv.program.programId,
+            distributionTree: decline.distributionTreePda,
+            epoch,
+            index: decline.index
+        }),
+        mintRegistry: getMintRegistryPDA({
+            distributorProgram: testEnv.program.programId,
+            distributionTree: decline.distributionTreePda
+        }),
+        dispute: getDisputePDA({
+            distributorProgram: testEnv.program.programId,
+            distributionTree: decline.distributionTreePda,
+            epoch,
+            index: decline.index
+        }),
+        installmentRecord: getInstallmentRecordPDA({
+            distributorProgram: testEnv.program.programId,
+            distributionTree: decline.distributionTreePda,
+            epoch,
+            index: decline.index
+        }),
+        mint: decline.mint,
+        tokenVault: decline.tokenVault,
+        declineDestinationTokenAccount: decline.declineDestinationTokenAccount ?? null,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+    };
+
+    try {
+        const initialDestinationBalance = decline.declineDestinationTokenAccount
+            ? await testEnv.program.provider.connection.getTokenAccountBalance(decline.declineDestinationTokenAccount)
+            : { value: { amount: '0' } };
+
+        await testEnv.program.methods.decline(declineParams)
+            .accountsPartial(accounts)
+            .signers([decline.recipient])
+            .rpc({ commitment: "processed" });
+
+        // Fetch and assert the DistributionTree account data
+        const distributionTreeData = await testEnv.program.account.distributionTree.fetch(decline.distributionTreePda);
+        assert.isTrue(
+            isBitSet(distributionTreeData.recipientsDistributedBitmap, decline.index),
+            `Bitmap not set for declined leaf at index ${decline.index}`
+        );
+
+        // Fetch and assert the DeclineRecord account data
+        const declineRecordData = await testEnv.program.account.declineRecord.fetch(declineRecord);
+        assert.strictEqual(declineRecordData.recipient.toString(), decline.recipient.publicKey.toString());
+        assert.strictEqual(declineRecordData.index.toNumber(), decline.index);
+        assert.strictEqual(declineRecordData.amount.toString(), decline.amount.toString());
+        assert.strictEqual(declineRecordData.forwardedAmount.toString(), decline.expectedForwardedAmount.toString());
+
+        // Assert the allocation was forwarded to the decline destination
+        if (decline.declineDestinationTokenAccount) {
+            const destinationBalance = await testEnv.program.provider.connection.getTokenAccountBalance(decline.declineDestinationTokenAccount);
+            const destinationBalanceChange = BigInt(destinationBalance.value.amount) - BigInt(initialDestinationBalance.value.amount);
+            assert.strictEqual(destinationBalanceChange.toString(), decline.expectedForwardedAmount.toString());
+        }
+    } catch (error) {
+        throw error;
+    }
+}
@@ -0,0 +1,145 @@
+import { TestEnvironment } from "../../utils/environment/test-environment";
+import { BN, web3 } from "@coral-xyz/anchor";
+import { assert } from 'chai';
+import { createAssociatedTokenAccountIdempotent, TOKEN_2022_PROGRAM_ID } from "@solana/spl-token";
+import { airdropToMultiple } from "../../utils/solana-helpers";
+import { getAccountByIndex } from "../../utils/merkle-tree";
+import { createNewDistributionTree } from "../1-initialize/initialize";
+import { createDistributeParams, distribute } from "../2-distribute/distribute";
+import { assertInstructionWillFail } from "../helpers";
+import { Decline, decline } from "./decline";
+import { close } from "../8-reclaim/reclaim";
+
+/**
+ * DECLINE INSTRUCTION TESTS
+ * 
+ * @param testEnv 
+ * 
+ * This test suite initializes a new Distribution Tree that forwards declined allocations to a charity and then:
+ *  1. Verifies a leaf cannot be declined by a wallet other than its recipient
+ *  2. Verifies the charity's token account is required
+ *  3. Declines a leaf, forwarding the allocation to the charity and recording the decline
+ *  4. Verifies a declined leaf can neither be distributed nor declined again
+ *
+ * It then initializes a Distribution Tree without a decline destination and:
+ *  5. Declines the last leaf, completing the tree with the allocation left in the residual pool
+ *  6. Closes the tree, recovering the residual pool to the authority
+ */
+export async function declineTests(testEnv: TestEnvironment) {
+    const declinedIndex = 0;
+    const charity = web3.Keypair.generate();
+    let charityTokenAccount: web3.PublicKey;
+    let correctParams: Decline;
+
+    describe('Recipients decline their allocation', () => {
+        before('Initializes a new distribution tree with a decline destination', async () => {
+            charityTokenAccount = await createAssociatedTokenAccountIdempotent(
+                testEnv.provider.connection,
+                testEnv.authority,
+                testEnv.pyUsdMint,
+                charity.publicKey,
+                { commitment: 'processed' },
+                TOKEN_2022_PROGRAM_ID
+            );
+            await createNewDistributionTree({ testEnv, declineDestination: charity.publicKey });
+
+            const paymentInfo = getAccountByIndex(testEnv.merkleDistributorInfo, declinedIndex);
+            if (!paymentInfo) {
+                throw new Error('No recipient found');
+            }
+            await airdropToMultiple([paymentInfo.keypair.publicKey], testEnv.provider.connection, web3.LAMPORTS_PER_SOL);
+            correctParams = {
+                recipient: paymentInfo.keypair,
+                distributionTreePda: testEnv.distributionTreePda,
+                mint: testEnv.pyUsdMint,
+                tokenVault: testEnv.tokenVault,
+                batchId: testEnv.distributionUniqueId,
+                index: declinedIndex,
+                amount: paymentInfo.amount,
+                proof: testEnv.balanceTree.getProof(declinedIndex, paymentInfo.keypair.publicKey, paymentInfo.amount),
+                declineDestinationTokenAccount: charityTokenAccount,
+                expectedForwardedAmount: paymentInfo.amount,
+            };
+        });
+
+        it('Cannot decline a leaf as another wallet', async () => {
+            await assertInstructionWillFail({
+                testEnv,
+                params: { ...correctParams, recipient: testEnv.wrongAuthority },
+                executeInstruction: decline,
+                expectedAnchorError: "InvalidProof"
+            });
+        });
+
+        it('Cannot decline without the decline destination token account', async () => {
+            await assertInstructionWillFail({
+                testEnv,
+                params: { ...correctParams, declineDestinationTokenAccount: undefined },
+                executeInstruction: decline,
+                expectedAnchorError: "InvalidDeclineDestination"
+            });
+        });
+
+        it('Can decline a leaf, forwarding the allocation to the decline destination', async () => {
+            await decline(testEnv, correctParams);
+            const distributionTreeData = await testEnv.program.account.distributionTree.fetch(testEnv.distributionTreePda);
+            assert.strictEqual(distributionTreeData.numberDeclined.toNumber(), 1);
+            assert.strictEqual(distributionTreeData.numberDistributed.toNumber(), 0);
+        });
+
+        it('Cannot distribute a declined leaf', async () => {
+            const distributeParams = await createDistributeParams({ testEnv, index: declinedIndex });
+            await assertInstructionWillFail({
+                testEnv,
+                params: distributeParams,
+                executeInstruction: distribute,
+                expectedAnchorError: "AlreadyClaimed"
+            });
+        });
+
+        it('Cannot decline a leaf twice', async () => {
+            await assertInstructionWillFail({
+                testEnv,
+                params: { ...correctParams, expectedForwardedAmount: new BN(0) },
+                executeInstruction: decline,
+            });
+        });
+    });
+
+    describe('Recovers the residual pool of declined allocations', () => {
+        const distributedIndex = 0;
+        const lastIndex = 1;
+
+        before('Initializes a new distribution tree without a decline destination', async () => {
+            await createNewDistributionTree({ testEnv, numPayments: 2 });
+            const recipient = getAccountByIndex(testEnv.merkleDistributorInfo, lastIndex)!.keypair.publicKey;
+            await airdropToMultiple([recipient], testEnv.provider.connection, web3.LAMPORTS_PER_SOL);
+            await distribute(testEnv, await createDistributeParams({ testEnv, index: distributedIndex }));
+        });
+
+        it('Can decline the last leaf, keeping the allocation in the residual pool', async () => {
+            const paymentInfo = getAccountByIndex(testEnv.merkleDistributorInfo, lastIndex)!;
+            await decline(testEnv, {
+                recipient: paymentInfo.keypair,
+                distributionTreePda: testEnv.distributionTreePda,
+                mint: testEnv.pyUsdMint,
+                tokenVault: testEnv.tokenVault,
+                batchId: testEnv.distributionUniqueId,
+                index: lastIndex,
+                amount: paymentInfo.amount,
+                proof: testEnv.balanceTree.getProof(lastIndex, paymentInfo.keypair.publicKey, paymentInfo.amount),
+                expectedForwardedAmount: new BN(0),
+            });
+
+            const distributionTreeData = await testEnv.program.account.distributionTree.fetch(testEnv.distributionTreePda);
+            assert.deepStrictEqual(distributionTreeData.status, { complete: {} });
+            const tokenVaultBalance = await testEnv.provider.connection.getTokenAccountBalance(testEnv.tokenVault);
+            assert.strictEqual(tokenVaultBalance.value.amount, paymentInfo.amount.toString());
+        });
+
+        it('Can close the tree, recovering the residual pool to the authority', async () => {
+            // close asserts the token vault is swept to the authority's token account and closed
+            await close(testEnv, { acknowledgeIrreversible: true });
+        });
+    });
+}
@@ -0,0 +1,211 @@
+import { TestEnvironment } from "../../utils/environment/test-environment";
+import { PublicKey, Keypair, SystemProgram } from '@solana/web3.js';
+import { ASSOCIATED_TOKEN_PROGRAM_ID, TOKEN_2022_PROGRAM_ID } from "@solana/spl-token";
+import { BN } from '@coral-xyz/anchor';
+import { assert } from 'chai';
+import { InstallmentPlan, isBitSet, LeafSchedule, PayoutSplit } from "../../utils/merkle-tree";
+import { getDisputePDA, getLeafRedirectPDA, getUserTokenAccountAddress } from "../../utils/pdas";
+
+export interface DisputeLeaf {
+    recipient: Keypair,
+    distributionTreePda: PublicKey,
+    batchId: string,
+    index: number,
+    amount: BN,
+    proof: Buffer[],
+    reasonHash: Buffer,
+    epoch?: number,
+    splits?: PayoutSplit[],
+    schedule?: LeafSchedule,
+    installments?: InstallmentPlan,
+    mintIndex?: number,
+}
+
+export interface ResolveDispute {
+    authority: Keypair,
+    recipient: PublicKey,
+    distributionTreePda: PublicKey,
+    mint: PublicKey,
+    tokenVault: PublicKey,
+    batchId: string,
+    index: number,
+    correctedAmount: BN,
+    resolutionHash: Buffer,
+    epoch?: number,
+}
+
+export interface RejectDispute {
+    authority: Keypair,
+    distributionTreePda: PublicKey,
+    batchId: string,
+    index: number,
+    resolutionHash: Buffer,
+    epoch?: number,
+}
+
+export async function disputeLeaf(
+    testEnv: TestEnvironment,
+    disputeLeaf: DisputeLeaf,
+) {
+    const epoch = disputeLeaf.epoch ?? testEnv.epoch;
+    const disputeLeafParams = {
+        batchId: disputeLeaf.batchId,
+        epoch: new BN(epoch),
+        index: new BN(disputeLeaf.index),
+        amount: disputeLeaf.amount,
+        proof: disputeLeaf.proof.map(buffer => Array.from(buffer)),
+        reasonHash: Array.from(disputeLeaf.reasonHash),
+        splits: disputeLeaf.splits ?? null,
+        schedule: disputeLeaf.schedule ?? null,
+        installments: disputeLeaf.installments ?? null,
+        mintIndex: disputeLeaf.mintIndex ?? null,
+    };
+
+    const dispute = getDisputePDA({
+        distributorProgram: testEnv.program.programId,
+        distributionTree: disputeLeaf.distributionTreePda,
+        epoch,
+        index: disputeLeaf.index
+    });
+
+    const accounts = {
+        recipient: disputeLeaf.recipient.publicKey,
+        distributionTree: disputeLeaf.distributionTreePda,
+        dispute,
+        leafRedirect: getLeafRedirectPDA({
+            distributorProgram: testEnv.program.programId,
+            distributionTree: disputeLeaf.distributionTreePda,
+            epoch,
+            index: disputeLeaf.index
+        }),
+        systemProgram: SystemProgram.programId,
+    };
+
+    try {
+        await testEnv.program.methods.dispute(disputeLeafParams)
+            .accountsPartial(accounts)
+            .signers([disputeLeaf.recipient])
+            .rpc({ commitment: "processed" });
+
+        // Fetch and assert the Dispute account data
+        const disputeData = await testEnv.program.account.dispute.fetch(dispute);
+        assert.strictEqual(disputeData.recipient.toString(), disputeLeaf.recipient.publicKey.toString());
+        assert.strictEqual(disputeData.index.toNumber(), disputeLeaf.index);
+        assert.strictEqual(disputeData.amount.toString(), disputeLeaf.amount.toString());
+        assert.deepEqual(disputeData.reasonHash, Array.from(disputeLeaf.reasonHash));
+        assert.deepEqual(disputeData.status, { open: {} });
+    } catch (error) {
+        throw error;
+    }
+}
+
+export async function resolveDispute(
+    testEnv: TestEnvironment,
+    resolveDispute: ResolveDispute,
+) {
+    const epoch = resolveDispute.epoch ?? testEnv.epoch;
+    const resolveDisputeParams = {
+        batchId: resolveDispute.batchId,
+        epoch: new BN(epoch),
+        index: new BN(resolveDispute.index),
+        correctedAmount: resolveDispute.correctedAmount,
+        resolutionHash: Array.from(resolveDispute.resolutionHash),
+    };
+
+    const dispute = getDisputePDA({
+        distributorProgram: testEnv.program.programId,
+        distributionTree: resolveDispute.distributionTreePda,
+        epoch,
+        index: resolveDispute.index
+    });
+
+    const recipientTokenAccount = getUserTokenAccountAddress({
+        recipient: resolveDispute.recipient,
+        mint: resolveDispute.mint
+    });
+
+    const accounts = {
+        authority: resolveDispute.authority.publicKey,
+        distributionTree: resolveDispute.distributionTreePda,
+        dispute,
+        recipient: resolveDispute.recipient,
+        mint: resolveDispute.mint,
+        tokenVault: resolveDispute.tokenVault,
+        tokenSource: getUserTokenAccountAddress({
+            recipient: resolveDispute.authority.publicKey,
+            mint: resolveDispute.mint
+        }),
+        recipientTokenAccount,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+    };
+
+    const initialRecipientBalance = await testEnv.program.provider.connection.getTokenAccountBalance(recipientTokenAccount).catch(() => ({ value: { amount: '0' } }));
+
+    try {
+        await testEnv.program.methods.resolveDispute(resolveDisputeParams)
+            .accountsPartial(accounts)
+            .signers([resolveDispute.authority])
+            .rpc({ commitment: "processed" });
+
+        // Fetch and assert the DistributionTree account data
+        const distributionTreeData = await testEnv.program.account.distributionTree.fetch(resolveDispute.distributionTreePda);
+        assert.isTrue(
+            isBitSet(distributionTreeData.recipientsDistributedBitmap, resolveDispute.index),
+            `Bitmap not set for resolved dispute at index ${resolveDispute.index}`
+        );
+
+        // Fetch and assert the Dispute account data
+        const disputeData = await testEnv.program.account.dispute.fetch(dispute);
+        assert.deepEqual(disputeData.status, { resolved: {} });
+        assert.strictEqual(disputeData.resolvedAmount.toString(), resolveDispute.correctedAmount.toString());
+
+        // Assert the corrected amount was paid to the recipient
+        const recipientBalance = await testEnv.program.provider.connection.getTokenAccountBalance(recipientTokenAccount);
+        const recipientBalanceChange = BigInt(recipientBalance.value.amount) - BigInt(initialRecipientBalance.value.amount);
+        assert.strictEqual(recipientBalanceChange.toString(), resolveDispute.correctedAmount.toString());
+    } catch (error) {
+        throw error;
+    }
+}
+
+export async function rejectDispute(
+    testEnv: TestEnvironment,
+    rejectDispute: RejectDispute,
+) {
+    const epoch = rejectDispute.epoch ?? testEnv.epoch;
+    const rejectDisputeParams = {
+        batchId: rejectDispute.batchId,
+        epoch: new BN(epoch),
+        index: new BN(rejectDispute.index),
+        resolutionHash: Array.from(rejectDispute.resolutionHash),
+    };
+
+    const dispute = getDisputePDA({
+        distributorProgram: testEnv.program.programId,
+        distributionTree: rejectDispute.distributionTreePda,
+        epoch,
+        index: rejectDispute.index
+    });
+
+    const accounts = {
+        authority: rejectDispute.authority.publicKey,
+        distributionTree: rejectDispute.distributionTreePda,
+        dispute,
+    };
+
+    try {
+        await testEnv.program.methods.rejectDispute(rejectDisputeParams)
+            .accountsPartial(accounts)
+            .signers([rejectDispute.authority])
+            .rpc({ commitment: "processed" });
+
+        // Fetch and assert the Dispute account data
+        const disputeData = await testEnv.program.account.dispute.fetch(dispute);
+        assert.deepEqual(disputeData.status, { rejected: {} });
+        assert.deepEqual(disputeData.resolutionHash, Array.from(rejectDispute.resolutionHash));
+    } catch (error) {
+        throw error;
+    }
+}
@@ -0,0 +1,197 @@
+import { TestEnvironment } from "../../utils/environment/test-environment";
+import { BN, web3 } from "@coral-xyz/anchor";
+import { assert } from 'chai';
+import { createHash } from "crypto";
+import { airdropToMultiple } from "../../utils/solana-helpers";
+import { BASE_PAYMENT_AMOUNT } from "../../utils/constants";
+import { getAccountByIndex } from "../../utils/merkle-tree";
+import { createNewDistributionTree } from "../1-initialize/initialize";
+import { createDistributeParams, distribute } from "../2-distribute/distribute";
+import { revoke } from "../16-revoke/revoke";
+import { decline } from "../17-decline/decline";
+import { assertInstructionWillFail } from "../helpers";
+import { DisputeLeaf, disputeLeaf, rejectDispute, resolveDispute } from "./dispute";
+
+/**
+ * DISPUTE INSTRUCTION TESTS
+ * 
+ * @param testEnv 
+ * 
+ * This test suite initializes a new Distribution Tree and then:
+ *  1. Verifies a leaf cannot be disputed by a wallet other than its recipient
+ *  2. Opens a dispute, freezing the leaf from being distributed, revoked or declined
+ *  3. Rejects the dispute, after which the leaf is distributed at its original amount
+ *  4. Resolves a dispute with a corrected amount, paying the correction from the authority's reserve
+ *  5. Verifies a closed dispute cannot be resolved again
+ *  6. Distributes the remaining leaf, completing the distribution
+ */
+export async function disputeTests(testEnv: TestEnvironment) {
+    const totalNumberRecipients = 3;
+    const rejectedIndex = 0;
+    const resolvedIndex = 1;
+    const hashOf = (text: string) => createHash('sha256').update(text).digest();
+
+    const createDisputeLeafParams = (index: number): DisputeLeaf => {
+        const paymentInfo = getAccountByIndex(testEnv.merkleDistributorInfo, index);
+        if (!paymentInfo) {
+            throw new Error('No recipient found');
+        }
+        return {
+            recipient: paymentInfo.keypair,
+            distributionTreePda: testEnv.distributionTreePda,
+            batchId: testEnv.distributionUniqueId,
+            index,
+            amount: paymentInfo.amount,
+            proof: testEnv.balanceTree.getProof(index, paymentInfo.keypair.publicKey, paymentInfo.amount),
+            reasonHash: hashOf(`claim form of recipient ${index}`),
+        };
+    };
+
+    describe('Recipients dispute their allocation', () => {
+        before('Initializes a new distribution tree', async () => {
+            await createNewDistributionTree({ testEnv, numPayments: totalNumberRecipients });
+            const recipients = [rejectedIndex, resolvedIndex].map(index => getAccountByIndex(testEnv.merkleDistributorInfo, index)!.keypair.publicKey);
+            await airdropToMultiple(recipients, testEnv.provider.connection, web3.LAMPORTS_PER_SOL);
+        });
+
+        it('Cannot dispute a leaf as another wallet', async () => {
+            await assertInstructionWillFail({
+                testEnv,
+                params: { ...createDisputeLeafParams(rejectedIndex), recipient: testEnv.wrongAuthority },
+                executeInstruction: disputeLeaf,
+                expectedAnchorError: "InvalidProof"
+            });
+        });
+
+        it('Can dispute a leaf', async () => {
+            await disputeLeaf(testEnv, createDisputeLeafParams(rejectedIndex));
+        });
+
+        it('Cannot distribute a disputed leaf', async () => {
+            const distributeParams = await createDistributeParams({ testEnv, index: rejectedIndex });
+            await assertInstructionWillFail({
+                testEnv,
+                params: distributeParams,
+                executeInstruction: distribute,
+                expectedAnchorError: "LeafDisputed"
+            });
+        });
+
+        it('Cannot revoke a disputed leaf', async () => {
+            const paymentInfo = getAccountByIndex(testEnv.merkleDistributorInfo, rejectedIndex)!;
+            await assertInstructionWillFail({
+                testEnv,
+                params: {
+                    authority: testEnv.authority,
+                    distributionTreePda: testEnv.distributionTreePda,
+                    mint: testEnv.pyUsdMint,
+                    tokenVault: testEnv.tokenVault,
+                    batchId: testEnv.distributionUniqueId,
+                    index: rejectedIndex,
+                    recipient: paymentInfo.keypair.publicKey,
+                    amount: paymentInfo.amount,
+                    proof: testEnv.balanceTree.getProof(rejectedIndex, paymentInfo.keypair.publicKey, paymentInfo.amount),
+                    expectedClawback: paymentInfo.amount,
+                },
+                executeInstruction: revoke,
+                expectedAnchorError: "LeafDisputed"
+            });
+        });
+
+        it('Cannot decline a disputed leaf', async () => {
+            const paymentInfo = getAccountByIndex(testEnv.merkleDistributorInfo, rejectedIndex)!;
+            await assertInstructionWillFail({
+                testEnv,
+                params: {
+                    recipient: paymentInfo.keypair,
+                    distributionTreePda: testEnv.distributionTreePda,
+                    mint: testEnv.pyUsdMint,
+                    tokenVault: testEnv.tokenVault,
+                    batchId: testEnv.distributionUniqueId,
+                    index: rejectedIndex,
+                    amount: paymentInfo.amount,
+                    proof: testEnv.balanceTree.getProof(rejectedIndex, paymentInfo.keypair.publicKey, paymentInfo.amount),
+                    expectedForwardedAmount: new BN(0),
+                },
+                executeInstruction: decline,
+                expectedAnchorError: "LeafDisputed"
+            });
+        });
+
+        it('Cannot reject a dispute with an incorrect authority', async () => {
+            await assertInstructionWillFail({
+                testEnv,
+                params: {
+                    authority: testEnv.wrongAuthority,
+                    distributionTreePda: testEnv.distributionTreePda,
+                    batchId: testEnv.distributionUniqueId,
+                    index: rejectedIndex,
+                    resolutionHash: hashOf('rejection'),
+                },
+                executeInstruction: rejectDispute,
+            });
+        });
+
+        it('Can reject a dispute and distribute the leaf at its original amount', async () => {
+            await rejectDispute(testEnv, {
+                authority: testEnv.authority,
+                distributionTreePda: testEnv.distributionTreePda,
+                batchId: testEnv.distributionUniqueId,
+                index: rejectedIndex,
+                resolutionHash: hashOf('rejection'),
+            });
+            const distributeParams = await createDistributeParams({ testEnv, index: rejectedIndex });
+            await distribute(testEnv, distributeParams);
+        });
+
+        it('Can resolve a dispute, paying the correction from the reserve', async () => {
+            const disputeLeafParams = createDisputeLeafParams(resolvedIndex);
+            await disputeLeaf(testEnv, disputeLeafParams);
+
+            const vaultBalanceBefore = await testEnv.program.provider.connection.getTokenAccountBalance(testEnv.tokenVault);
+            await resolveDispute(testEnv, {
+                authority: testEnv.authority,
+                recipient: disputeLeafParams.recipient.publicKey,
+                distributionTreePda: testEnv.distributionTreePda,
+                mint: testEnv.pyUsdMint,
+                tokenVault: testEnv.tokenVault,
+                batchId: testEnv.distributionUniqueId,
+                index: resolvedIndex,
+                correctedAmount: disputeLeafParams.amount.add(new BN(BASE_PAYMENT_AMOUNT)),
+                resolutionHash: hashOf('supplementary proof'),
+            });
+
+            // Only the leaf amount leaves the vault, the correction comes from the authority
+            const vaultBalanceAfter = await testEnv.program.provider.connection.getTokenAccountBalance(testEnv.tokenVault);
+            const vaultBalanceChange = BigInt(vaultBalanceBefore.value.amount) - BigInt(vaultBalanceAfter.value.amount);
+            assert.strictEqual(vaultBalanceChange.toString(), disputeLeafParams.amount.toString());
+        });
+
+        it('Cannot resolve a closed dispute', async () => {
+            await assertInstructionWillFail({
+                testEnv,
+                params: {
+                    authority: testEnv.authority,
+                    recipient: getAccountByIndex(testEnv.merkleDistributorInfo, resolvedIndex)!.keypair.publicKey,
+                    distributionTreePda: testEnv.distributionTreePda,
+                    mint: testEnv.pyUsdMint,
+                    tokenVault: testEnv.tokenVault,
+                    batchId: testEnv.distributionUniqueId,
+                    index: resolvedIndex,
+                    correctedAmount: new BN(BASE_PAYMENT_AMOUNT),
+                    resolutionHash: hashOf('supplementary proof'),
+                },
+                executeInstruction: resolveDispute,
+                expectedAnchorError: "DisputeNotOpen"
+            });
+        });
+
+        it('Completes the distribution once the remaining leaf is paid', async () => {
+            const distributeParams = await createDistributeParams({ testEnv, index: 2 });
+            await distribute(testEnv, distributeParams);
+            const distributionTreeData = await testEnv.program.account.distributionTree.fetch(testEnv.distributionTreePda);
+            assert.strictEqual(distributionTreeData.numberDistributed.toNumber(), totalNumberRecipients);
+            assert.deepStrictEqual(distributionTreeData.status, { complete: {} });
+        });
+    });
+}
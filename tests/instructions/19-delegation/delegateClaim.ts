@@ -0,0 +1,81 @@
+import { TestEnvironment } from "../../utils/environment/test-environment";
+import { PublicKey, Keypair, SystemProgram } from '@solana/web3.js';
+import { BN } from '@coral-xyz/anchor';
+import { assert } from 'chai';
+import { InstallmentPlan, LeafSchedule, PayoutSplit } from "../../utils/merkle-tree";
+import { getDelegationPDA, getLeafRedirectPDA } from "../../utils/pdas";
+
+export interface DelegateClaim {
+    signer: Keypair,
+    distributionTreePda: PublicKey,
+    batchId: string,
+    index: number,
+    recipient: PublicKey,
+    amount: BN,
+    proof: Buffer[],
+    delegate: PublicKey,
+    payee?: PublicKey,
+    epoch?: number,
+    splits?: PayoutSplit[],
+    schedule?: LeafSchedule,
+    installments?: InstallmentPlan,
+    mintIndex?: number,
+}
+
+export async function delegateClaim(
+    testEnv: TestEnvironment,
+    delegateClaim: DelegateClaim,
+) {
+    const epoch = delegateClaim.epoch ?? testEnv.epoch;
+    const delegateClaimParams = {
+        batchId: delegateClaim.batchId,
+        epoch: new BN(epoch),
+        index: new BN(delegateClaim.index),
+        recipient: delegateClaim.recipient,
+        amount: delegateClaim.amount,
+        proof: delegateClaim.proof.map(buffer => Array.from(buffer)),
+        delegate: delegateClaim.delegate,
+        payee: delegateClaim.payee ?? null,
+        splits: delegateClaim.splits ?? null,
+        schedule: delegateClaim.schedule ?? null,
+        installments: delegateClaim.installments ?? null,
+        mintIndex: delegateClaim.mintIndex ?? null,
+    };
+
+    const delegation = getDelegationPDA({
+        distributorProgram: testEnv.program.programId,
+        distributionTree: delegateClaim.distributionTreePda,
+        epoch,
+        index: delegateClaim.index
+    });
+
+    const accounts = {
+        signer: delegateClaim.signer.publicKey,
+        distributionTree: delegateClaim.distributionTreePda,
+        delegation,
+        leafRedirect: getLeafRedirectPDA({
+            distributorProgram: testEnv.program.programId,
+            distributionTree: delegateClaim.distributionTreePda,
+            epoch,
+            index: delegateClaim.index
+        }),
+        systemProgram: SystemProgram.programId,
+    };
+
+    try {
+        await testEnv.program.methods.delegateClaim(delegateClaimParams)
+            .accountsPartial(accounts)
+            .signers([delegateClaim.signer])
+            .rpc({ commitment: "processed" });
+
+        // Fetch and assert the Delegation account data
+        const delegationData = await testEnv.program.account.delegation.fetch(delegation);
+        assert.strictEqual(delegationData.index.toNumber(), delegateClaim.index);
+        assert.strictEqual(delegationData.recipient.toString(), delegateClaim.recipient.toString());
+        assert.strictEqual(delegationData.delegate.toString(), delegateClaim.delegate.toString());
+        assert.strictEqual(delegationData.payee.toString(), (delegateClaim.payee ?? delegateClaim.recipient).toString());
+        assert.strictEqual(delegationData.approvedBy.toString(), delegateClaim.signer.publicKey.toString());
+    } catch (error) {
+        throw error;
+    }
+}
@@ -0,0 +1,118 @@
+import { TestEnvironment } from "../../utils/environment/test-environment";
+import { web3 } from "@coral-xyz/anchor";
+import { airdropToMultiple } from "../../utils/solana-helpers";
+import { getAccountByIndex } from "../../utils/merkle-tree";
+import { getUserTokenAccountAddress } from "../../utils/pdas";
+import { createNewDistributionTree } from "../1-initialize/initialize";
+import { Claim, claim } from "../5-claim/claim";
+import { assertInstructionWillFail } from "../helpers";
+import { DelegateClaim, delegateClaim } from "./delegateClaim";
+
+/**
+ * DELEGATE CLAIM INSTRUCTION TESTS
+ * 
+ * @param testEnv 
+ * 
+ * This test suite initializes a new Distribution Tree with claims allowed and then:
+ *  1. Verifies a wallet other than the authority or the recipient cannot delegate a leaf
+ *  2. Delegates a leaf to a guardian, signed by the recipient
+ *  3. Verifies the guardian cannot pay themselves, then claims to the recipient's token account
+ *  4. Approves a delegation with a designated payee as the authority
+ *  5. Verifies the recipient cannot change a delegation approved by the authority
+ *  6. Claims as the delegate to the designated payee
+ */
+export async function delegationTests(testEnv: TestEnvironment) {
+    const guardian = web3.Keypair.generate();
+    const representative = web3.Keypair.generate();
+    const estate = web3.Keypair.generate();
+
+    const createDelegateClaimParams = (index: number, signer: web3.Keypair, delegate: web3.PublicKey, payee?: web3.PublicKey): DelegateClaim => {
+        const paymentInfo = getAccountByIndex(testEnv.merkleDistributorInfo, index);
+        if (!paymentInfo) {
+            throw new Error('No recipient found');
+        }
+        const recipient = paymentInfo.keypair.publicKey;
+        return {
+            signer,
+            distributionTreePda: testEnv.distributionTreePda,
+            batchId: testEnv.distributionUniqueId,
+            index,
+            recipient,
+            amount: paymentInfo.amount,
+            proof: testEnv.balanceTree.getProof(index, recipient, paymentInfo.amount),
+            delegate,
+            payee,
+        };
+    };
+
+    const createDelegatedClaimParams = (index: number, delegate: web3.Keypair, payee: web3.PublicKey): Claim => {
+        const { recipient, amount, proof } = createDelegateClaimParams(index, delegate, delegate.publicKey);
+        return {
+            claimant: delegate,
+            payee,
+            distributionTreePda: testEnv.distributionTreePda,
+            mint: testEnv.pyUsdMint,
+            tokenVault: testEnv.tokenVault,
+            payeeTokenAccount: getUserTokenAccountAddress({ recipient: payee, mint: testEnv.pyUsdMint }),
+            amount,
+            proof,
+            batchId: testEnv.distributionUniqueId,
+            index,
+        };
+    };
+
+    describe('Delegates claim on behalf of recipients', () => {
+        before('Initializes a new distribution tree with claims allowed', async () => {
+            await createNewDistributionTree({ testEnv, allowClaims: true });
+            const recipient = getAccountByIndex(testEnv.merkleDistributorInfo, 0)!.keypair.publicKey;
+            await airdropToMultiple([recipient, guardian.publicKey, representative.publicKey], testEnv.provider.connection, web3.LAMPORTS_PER_SOL);
+        });
+
+        it('Cannot delegate a leaf as another wallet', async () => {
+            await assertInstructionWillFail({
+                testEnv,
+                params: createDelegateClaimParams(0, guardian, guardian.publicKey),
+                executeInstruction: delegateClaim,
+                expectedAnchorError: "SignerNotAuthorized"
+            });
+        });
+
+        it('Can delegate a leaf as its recipient', async () => {
+            const recipientKeypair = getAccountByIndex(testEnv.merkleDistributorInfo, 0)!.keypair;
+            await delegateClaim(testEnv, createDelegateClaimParams(0, recipientKeypair, guardian.publicKey));
+        });
+
+        it('Cannot claim a delegated leaf to the delegate', async () => {
+            await assertInstructionWillFail({
+                testEnv,
+                params: createDelegatedClaimParams(0, guardian, guardian.publicKey),
+                executeInstruction: claim,
+                expectedAnchorError: "InvalidPayee"
+            });
+        });
+
+        it('Can claim a delegated leaf to the recipient', async () => {
+            const recipient = getAccountByIndex(testEnv.merkleDistributorInfo, 0)!.keypair.publicKey;
+            await claim(testEnv, createDelegatedClaimParams(0, guardian, recipient));
+        });
+
+        it('Can approve a delegation with a designated payee as the authority', async () => {
+            await delegateClaim(testEnv, createDelegateClaimParams(1, testEnv.authority, representative.publicKey, estate.publicKey));
+        });
+
+        it('Cannot change a delegation approved by the authority as the recipient', async () => {
+            const recipientKeypair = getAccountByIndex(testEnv.merkleDistributorInfo, 1)!.keypair;
+            await airdropToMultiple([recipientKeypair.publicKey], testEnv.provider.connection, web3.LAMPORTS_PER_SOL);
+            await assertInstructionWillFail({
+                testEnv,
+                params: createDelegateClaimParams(1, recipientKeypair, guardian.publicKey),
+                executeInstruction: delegateClaim,
+                expectedAnchorError: "SignerNotAuthorized"
+            });
+        });
+
+        it('Can claim a delegated leaf to the designated payee', async () => {
+            await claim(testEnv, createDelegatedClaimParams(1, representative, estate.publicKey));
+        });
+    });
+}
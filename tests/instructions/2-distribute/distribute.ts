@@ -1,11 +1,11 @@
 import { TestEnvironment } from "../../utils/environment/test-environment";
-import { PublicKey, Keypair, SystemProgram } from '@solana/web3.js';
+import { PublicKey, Keypair, SystemProgram, AccountMeta } from '@solana/web3.js';
 import { ASSOCIATED_TOKEN_PROGRAM_ID, TOKEN_2022_PROGRAM_ID } from "@solana/spl-token";
 import { BN, web3 } from '@coral-xyz/anchor';
 import { assert } from 'chai';
 import { getSimulationComputeUnits } from "../../utils/solana-helpers";
-import { getAccountByIndex, isBitSet } from "../../utils/merkle-tree";
-import { getUserTokenAccountAddress } from "../../utils/pdas";
+import { getAccountByIndex, InstallmentPlan, isBitSet, LeafSchedule, PayoutSplit } from "../../utils/merkle-tree";
+import { getDisputePDA, getInstallmentRecordPDA, getLeafRedirectPDA, getMintRegistryPDA, getUserTokenAccountAddress } from "../../utils/pdas";
 import { verifyTreeComplete } from "../helpers";
 
 export interface Distribute {
@@ -20,6 +20,17 @@ export interface Distribute {
     batchId: string,
     numberDistributedBefore: number,
     gatewayToken?: PublicKey,
+    epoch?: number,
+    expectedPayout?: BN,
+    splits?: PayoutSplit[],
+    schedule?: LeafSchedule,
+    installments?: InstallmentPlan,
+    // Index of the registered mint paid by the leaf, `mint` and `tokenVault` must be that mint's
+    mintIndex?: number,
+    // Token accounts of the split destinations, defaults to their ATAs
+    splitTokenAccounts?: PublicKey[],
+    // Extra accounts of the mint's transfer hook, after the split token accounts
+    transferHookAccounts?: AccountMeta[],
 }
 
 export async function distribute(
@@ -35,6 +46,11 @@ export async function distribute(
         batchId: distribute.batchId,
         proof: distribute.proof.map(buffer => Array.from(buffer)),
         index: new BN(distribute.numberDistributedBefore),
+        epoch: new BN(distribute.epoch ?? testEnv.epoch),
+        splits: distribute.splits ?? null,
+        schedule: distribute.schedule ?? null,
+        installments: distribute.installments ?? null,
+        mintIndex: distribute.mintIndex ?? null,
     };
 
     const accounts = {
@@ -43,16 +59,48 @@ export async function distribute(
         distributionTree: distribute.distributionTreePda,
         mint: distribute.mint,
         tokenVault: distribute.tokenVault,
-        recipientTokenAccount: distribute.recipientTokenAccount,
+        recipientTokenAccount: distribute.splits ? null : distribute.recipientTokenAccount,
+        mintRegistry: getMintRegistryPDA({
+            distributorProgram: testEnv.program.programId,
+            distributionTree: distribute.distributionTreePda
+        }),
+        leafRedirect: getLeafRedirectPDA({
+            distributorProgram: testEnv.program.programId,
+            distributionTree: distribute.distributionTreePda,
+            epoch: distributeParams.epoch.toNumber(),
+            index: distributeParams.index.toNumber()
+        }),
+        dispute: getDisputePDA({
+            distributorProgram: testEnv.program.programId,
+            distributionTree: distribute.distributionTreePda,
+            epoch: distributeParams.epoch.toNumber(),
+            index: distributeParams.index.toNumber()
+        }),
+        installmentRecord: distribute.installments ? getInstallmentRecordPDA({
+            distributorProgram: testEnv.program.programId,
+            distributionTree: distribute.distributionTreePda,
+            epoch: distributeParams.epoch.toNumber(),
+            index: distributeParams.index.toNumber()
+        }) : null,
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
         tokenProgram: TOKEN_2022_PROGRAM_ID,
         systemProgram: SystemProgram.programId,
         gatewayToken: distribute.gatewayToken ?? null,
     }
 
+    // Split token accounts lead the remaining accounts, in the order of the splits
+    const splitTokenAccounts = distribute.splitTokenAccounts ?? (distribute.splits ?? []).map(({ destination }) =>
+        getUserTokenAccountAddress({ recipient: destination, mint: distribute.mint })
+    );
+    const remainingAccounts = [
+        ...splitTokenAccounts.map(pubkey => ({ pubkey, isWritable: true, isSigner: false })),
+        ...(distribute.transferHookAccounts ?? []),
+    ];
+
     if (simulate) {
         const ix = await testEnv.program.methods.distribute(distributeParams)
             .accountsPartial(accounts)
+            .remainingAccounts(remainingAccounts)
             .signers([distribute.authority])
             .instruction();
         const computeUnits = await getSimulationComputeUnits(testEnv.program.provider.connection, [ix], distribute.authority.publicKey, []);
@@ -69,24 +117,33 @@ export async function distribute(
     try {
         const txid = await testEnv.program.methods.distribute(distributeParams)
             .accountsPartial(accounts)
+            .remainingAccounts(remainingAccounts)
             .preInstructions([computeUnitIx], !!overRideComputeUnits)
             .signers([distribute.authority])
             .rpc({ commitment: "processed", skipPreflight });
         // Fetch and assert the DistributionTree account data
         let distributionTreeData = await testEnv.program.account.distributionTree.fetch(distribute.distributionTreePda);
         assert.strictEqual(distributionTreeData.authority.toString(), distribute.authority.publicKey.toString());
-        assert.strictEqual(distributionTreeData.mint.toString(), distribute.mint.toString());
-        assert.strictEqual(distributionTreeData.tokenVault.toString(), distribute.tokenVault.toString());
+        if (distribute.mintIndex === undefined) {
+            assert.strictEqual(distributionTreeData.mint.toString(), distribute.mint.toString());
+            assert.strictEqual(distributionTreeData.tokenVault.toString(), distribute.tokenVault.toString());
+        }
 
-        assert.isTrue(
-            isBitSet(distributionTreeData.recipientsDistributedBitmap, distributeParams.index.toNumber()),
-            `Bitmap not set for recipient at index ${distributeParams.index.toString()}`
-        );
+        // Installment leaves are only marked once their last installment has been paid
+        if (!distributeParams.installments) {
+            assert.isTrue(
+                isBitSet(distributionTreeData.recipientsDistributedBitmap, distributeParams.index.toNumber()),
+                `Bitmap not set for recipient at index ${distributeParams.index.toString()}`
+            );
+        }
 
-        // Fetch and assert the recipient token account data
-        let recipientTokenAccountData = await testEnv.program.provider.connection.getTokenAccountBalance(distribute.recipientTokenAccount);
-        const recipientBalanceChange = BigInt(recipientTokenAccountData.value.amount) - BigInt(initialRecipientBalance.value.amount);
-        assert.strictEqual(recipientBalanceChange.toString(), distribute.amount.toString());
+        // Fetch and assert the recipient token account data (split payouts are asserted by the caller)
+        const expectedPayout = distribute.expectedPayout ?? distribute.amount;
+        if (!distribute.splits) {
+            let recipientTokenAccountData = await testEnv.program.provider.connection.getTokenAccountBalance(distribute.recipientTokenAccount);
+            const recipientBalanceChange = BigInt(recipientTokenAccountData.value.amount) - BigInt(initialRecipientBalance.value.amount);
+            assert.strictEqual(recipientBalanceChange.toString(), expectedPayout.toString());
+        }
 
         // When running in parallel, the tests are run in parallel and the vault balance and number of recipients distributed checks are not deterministic
         // Instead we run verification after all the distributions have been completed
@@ -94,7 +151,7 @@ export async function distribute(
             // Fetch and assert the token vault token account data
             let tokenVaultTokenAccountData = await testEnv.program.provider.connection.getTokenAccountBalance(distribute.tokenVault);
             const vaultBalanceChange = BigInt(initialVaultBalance.value.amount) - BigInt(tokenVaultTokenAccountData.value.amount);
-            assert.strictEqual(vaultBalanceChange.toString(), distribute.amount.toString());
+            assert.strictEqual(vaultBalanceChange.toString(), expectedPayout.toString());
 
             // Verify the number of recipients distributed is incremented by 1
             assert.strictEqual(distributionTreeData.numberDistributed.toNumber(), distribute.numberDistributedBefore + 1);
@@ -173,9 +230,13 @@ export async function createDistributeParams({
             mint: testEnv.pyUsdMint
         }),
         amount: paymentInfo.amount,
-        proof: testEnv.balanceTree.getProof(index, recipient, paymentInfo.amount),
+        proof: testEnv.balanceTree.getProof(index, recipient, paymentInfo.amount, paymentInfo),
         batchId: testEnv.distributionUniqueId,
-        numberDistributedBefore: index
+        numberDistributedBefore: index,
+        splits: paymentInfo.splits,
+        schedule: paymentInfo.schedule,
+        installments: paymentInfo.installments,
+        mintIndex: paymentInfo.mintIndex,
     };
     return distributeParams;
 }
\ No newline at end of file
@@ -4,6 +4,7 @@ import { BN, web3 } from "@coral-xyz/anchor";
 import { assertInstructionWillFail } from "../helpers";
 import { createNewDistributionTree } from "../1-initialize/initialize";
 import { getUserTokenAccountAddress } from "../../utils/pdas";
+import { PaymentTree } from "../../utils/merkle-tree";
 
 /**
  * DISTRIBUTE INSTRUCTION TESTS
@@ -89,6 +90,22 @@ export async function distributeTests(testEnv: TestEnvironment) {
                 expectedAnchorError: "InvalidProof"
             });
         });
+        it('Cannot distribute with a proof built without domain separation (v1 hashing)', async () => {
+            const legacyTree = new PaymentTree(
+                testEnv.merkleDistributorInfo.payments.map(({ keypair, amount }) => ({ account: keypair, amount })),
+                { domainSeparated: false }
+            );
+            const incorrectParams: Distribute = {
+                ...correctParams,
+                proof: legacyTree.getProof(0, correctParams.recipient, correctParams.amount)
+            };
+            await assertInstructionWillFail({
+                testEnv,
+                params: incorrectParams,
+                executeInstruction: distribute,
+                expectedAnchorError: "InvalidProof"
+            });
+        });
         it('Cannot distribute by unauthorized account', async () => {
             const incorrectParams: Distribute = {
                 ...correctParams,
@@ -0,0 +1,75 @@
+import { TestEnvironment } from "../../utils/environment/test-environment";
+import { web3 } from "@coral-xyz/anchor";
+import { assert } from 'chai';
+import { createAccount, TOKEN_2022_PROGRAM_ID } from "@solana/spl-token";
+import { airdropToMultiple } from "../../utils/solana-helpers";
+import { getAccountByIndex } from "../../utils/merkle-tree";
+import { getUserTokenAccountAddress } from "../../utils/pdas";
+import { createNewDistributionTree } from "../1-initialize/initialize";
+import { Claim, claim } from "../5-claim/claim";
+import { assertInstructionWillFail } from "../helpers";
+
+/**
+ * CLAIM DESTINATION TESTS
+ * 
+ * @param testEnv 
+ * 
+ * This test suite initializes a new Distribution Tree with claims enabled and a custodial
+ * (non-associated) token account owned by an exchange, then:
+ *  1. Verifies a claim cannot pay the token vault
+ *  2. Claims to the exchange's token account without creating the claimant's associated token account
+ */
+export async function claimDestinationTests(testEnv: TestEnvironment) {
+    const claimIndex = 0;
+    const exchange = web3.Keypair.generate();
+    let exchangeTokenAccount: web3.PublicKey;
+    let correctParams: Claim;
+
+    describe('Claims paid to a destination token account', () => {
+        before('Initializes a new distribution tree and an exchange token account', async () => {
+            await createNewDistributionTree({ testEnv, allowClaims: true });
+            exchangeTokenAccount = await createAccount(
+                testEnv.provider.connection,
+                testEnv.authority,
+                testEnv.pyUsdMint,
+                exchange.publicKey,
+                web3.Keypair.generate(),
+                { commitment: 'processed' },
+                TOKEN_2022_PROGRAM_ID
+            );
+
+            const paymentInfo = getAccountByIndex(testEnv.merkleDistributorInfo, claimIndex);
+            if (!paymentInfo) {
+                throw new Error('No recipient found');
+            }
+            await airdropToMultiple([paymentInfo.keypair.publicKey], testEnv.provider.connection, web3.LAMPORTS_PER_SOL);
+            correctParams = {
+                claimant: paymentInfo.keypair,
+                distributionTreePda: testEnv.distributionTreePda,
+                mint: testEnv.pyUsdMint,
+                tokenVault: testEnv.tokenVault,
+                payeeTokenAccount: getUserTokenAccountAddress({ recipient: paymentInfo.keypair.publicKey, mint: testEnv.pyUsdMint }),
+                destinationTokenAccount: exchangeTokenAccount,
+                amount: paymentInfo.amount,
+                proof: testEnv.balanceTree.getProof(claimIndex, paymentInfo.keypair.publicKey, paymentInfo.amount),
+                batchId: testEnv.distributionUniqueId,
+                index: claimIndex,
+            };
+        });
+
+        it('Cannot claim to the token vault', async () => {
+            await assertInstructionWillFail({
+                testEnv,
+                params: { ...correctParams, destinationTokenAccount: testEnv.tokenVault },
+                executeInstruction: claim,
+                expectedAnchorError: "InvalidDestinationTokenAccount"
+            });
+        });
+
+        it('Can claim to a custodial token account', async () => {
+            await claim(testEnv, correctParams);
+            const claimantTokenAccount = await testEnv.provider.connection.getAccountInfo(correctParams.payeeTokenAccount);
+            assert.isNull(claimantTokenAccount, "Claimant's associated token account should not be created");
+        });
+    });
+}
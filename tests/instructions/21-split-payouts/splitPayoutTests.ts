@@ -0,0 +1,178 @@
+import { TestEnvironment } from "../../utils/environment/test-environment";
+import { BN, web3 } from "@coral-xyz/anchor";
+import { assert } from 'chai';
+import { createAssociatedTokenAccountIdempotent, TOKEN_2022_PROGRAM_ID } from "@solana/spl-token";
+import { airdropToMultiple } from "../../utils/solana-helpers";
+import { getAccountByIndex, PayoutSplit } from "../../utils/merkle-tree";
+import { createNewDistributionTree } from "../1-initialize/initialize";
+import { createDistributeParams, distribute } from "../2-distribute/distribute";
+import { Claim, claim } from "../5-claim/claim";
+import { disputeLeaf } from "../18-dispute/dispute";
+import { getUserTokenAccountAddress } from "../../utils/pdas";
+import { assertInstructionWillFail } from "../helpers";
+
+/**
+ * SPLIT PAYOUT TESTS
+ *
+ * @param testEnv
+ *
+ * This test suite initializes a new Distribution Tree with claims enabled where the first two
+ * leaves split their payout across multiple wallets, then:
+ *  1. Verifies splits whose bps do not add up to 10,000 are rejected
+ *  2. Verifies missing or out-of-order split token accounts are rejected
+ *  3. Distributes a 70/30 split leaf
+ *  4. Verifies a split leaf cannot be disputed, as resolutions pay the disputant
+ *  5. Claims a three-way split leaf
+ */
+export async function splitPayoutTests(testEnv: TestEnvironment) {
+    const distributeIndex = 0;
+    const claimIndex = 1;
+    const wallets = Array.from({ length: 3 }, () => web3.Keypair.generate().publicKey);
+    const distributeSplits: PayoutSplit[] = [
+        { destination: wallets[0], bps: 7_000 },
+        { destination: wallets[1], bps: 3_000 },
+    ];
+    const claimSplits: PayoutSplit[] = [
+        { destination: wallets[0], bps: 3_333 },
+        { destination: wallets[1], bps: 3_333 },
+        { destination: wallets[2], bps: 3_334 },
+    ];
+
+    // Mirrors the on-chain rounding: each share is floored, the remainder goes to the first destination
+    const expectedSplitAmounts = (payout: BN, splits: PayoutSplit[]) => {
+        const amounts = splits.map(({ bps }) => payout.muln(bps).divn(10_000));
+        const paid = amounts.reduce((sum, amount) => sum.add(amount), new BN(0));
+        amounts[0] = amounts[0].add(payout.sub(paid));
+        return amounts;
+    };
+
+    const getBalances = (splits: PayoutSplit[]) => Promise.all(splits.map(({ destination }) =>
+        testEnv.provider.connection
+            .getTokenAccountBalance(getUserTokenAccountAddress({ recipient: destination, mint: testEnv.pyUsdMint }))
+            .then(({ value }) => new BN(value.amount))
+    ));
+
+    const assertSplitPayout = (before: BN[], after: BN[], payout: BN, splits: PayoutSplit[]) => {
+        expectedSplitAmounts(payout, splits).forEach((amount, i) => {
+            assert.strictEqual(after[i].sub(before[i]).toString(), amount.toString(), `Wrong payout to split ${i}`);
+        });
+    };
+
+    describe('Leaves that split their payout across multiple wallets', () => {
+        before('Initializes a new distribution tree with split leaves', async () => {
+            await Promise.all(wallets.map(wallet => createAssociatedTokenAccountIdempotent(
+                testEnv.provider.connection,
+                testEnv.authority,
+                testEnv.pyUsdMint,
+                wallet,
+                { commitment: 'processed' },
+                TOKEN_2022_PROGRAM_ID
+            )));
+            await createNewDistributionTree({
+                testEnv,
+                allowClaims: true,
+                leafExtensions: {
+                    [distributeIndex]: { splits: distributeSplits },
+                    [claimIndex]: { splits: claimSplits },
+                },
+            });
+        });
+
+        it('Cannot distribute splits whose bps do not add up to 10,000', async () => {
+            const correctParams = await createDistributeParams({ testEnv, index: distributeIndex });
+            await assertInstructionWillFail({
+                testEnv,
+                params: {
+                    ...correctParams,
+                    splits: [distributeSplits[0], { ...distributeSplits[1], bps: 2_000 }],
+                },
+                executeInstruction: distribute,
+                expectedAnchorError: "InvalidPayoutSplits"
+            });
+        });
+
+        it('Cannot distribute without the split token accounts', async () => {
+            const correctParams = await createDistributeParams({ testEnv, index: distributeIndex });
+            await assertInstructionWillFail({
+                testEnv,
+                params: { ...correctParams, splitTokenAccounts: [] },
+                executeInstruction: distribute,
+                expectedAnchorError: "InvalidSplitTokenAccount"
+            });
+        });
+
+        it('Cannot distribute with split token accounts out of order', async () => {
+            const correctParams = await createDistributeParams({ testEnv, index: distributeIndex });
+            await assertInstructionWillFail({
+                testEnv,
+                params: {
+                    ...correctParams,
+                    splitTokenAccounts: [...distributeSplits].reverse().map(({ destination }) =>
+                        getUserTokenAccountAddress({ recipient: destination, mint: testEnv.pyUsdMint })
+                    ),
+                },
+                executeInstruction: distribute,
+                expectedAnchorError: "InvalidSplitTokenAccount"
+            });
+        });
+
+        it('Can distribute a split leaf', async () => {
+            const correctParams = await createDistributeParams({ testEnv, index: distributeIndex });
+            const before = await getBalances(distributeSplits);
+            await distribute(testEnv, correctParams);
+            const after = await getBalances(distributeSplits);
+            assertSplitPayout(before, after, correctParams.amount, distributeSplits);
+        });
+
+        it('Cannot dispute a split leaf', async () => {
+            const paymentInfo = getAccountByIndex(testEnv.merkleDistributorInfo, claimIndex);
+            if (!paymentInfo) {
+                throw new Error('No recipient found');
+            }
+            await airdropToMultiple([paymentInfo.keypair.publicKey], testEnv.provider.connection, web3.LAMPORTS_PER_SOL);
+            await assertInstructionWillFail({
+                testEnv,
+                params: {
+                    recipient: paymentInfo.keypair,
+                    distributionTreePda: testEnv.distributionTreePda,
+                    batchId: testEnv.distributionUniqueId,
+                    index: claimIndex,
+                    amount: paymentInfo.amount,
+                    proof: testEnv.balanceTree.getProof(claimIndex, paymentInfo.keypair.publicKey, paymentInfo.amount, { splits: claimSplits }),
+                    reasonHash: Buffer.alloc(32),
+                    splits: claimSplits,
+                },
+                executeInstruction: disputeLeaf,
+                expectedAnchorError: "UnsupportedLeafExtension"
+            });
+        });
+
+        it('Can claim a split leaf', async () => {
+            const paymentInfo = getAccountByIndex(testEnv.merkleDistributorInfo, claimIndex);
+            if (!paymentInfo) {
+                throw new Error('No recipient found');
+            }
+            await airdropToMultiple([paymentInfo.keypair.publicKey], testEnv.provider.connection, web3.LAMPORTS_PER_SOL);
+            const claimParams: Claim = {
+                claimant: paymentInfo.keypair,
+                distributionTreePda: testEnv.distributionTreePda,
+                mint: testEnv.pyUsdMint,
+                tokenVault: testEnv.tokenVault,
+                payeeTokenAccount: getUserTokenAccountAddress({ recipient: paymentInfo.keypair.publicKey, mint: testEnv.pyUsdMint }),
+                amount: paymentInfo.amount,
+                proof: testEnv.balanceTree.getProof(claimIndex, paymentInfo.keypair.publicKey, paymentInfo.amount, { splits: claimSplits }),
+                batchId: testEnv.distributionUniqueId,
+                index: claimIndex,
+                splits: claimSplits,
+            };
+
+            const before = await getBalances(claimSplits);
+            await claim(testEnv, claimParams);
+            const after = await getBalances(claimSplits);
+            assertSplitPayout(before, after, paymentInfo.amount, claimSplits);
+
+            const claimantTokenAccount = await testEnv.provider.connection.getAccountInfo(claimParams.payeeTokenAccount);
+            assert.isNull(claimantTokenAccount, "Claimant's associated token account should not be created");
+        });
+    });
+}
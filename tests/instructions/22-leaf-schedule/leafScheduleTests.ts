@@ -0,0 +1,137 @@
+import { TestEnvironment } from "../../utils/environment/test-environment";
+import { BN, web3 } from "@coral-xyz/anchor";
+import { airdropToMultiple } from "../../utils/solana-helpers";
+import { getAccountByIndex, LeafSchedule } from "../../utils/merkle-tree";
+import { createNewDistributionTree } from "../1-initialize/initialize";
+import { createDistributeParams, distribute } from "../2-distribute/distribute";
+import { Claim, claim } from "../5-claim/claim";
+import { revoke } from "../16-revoke/revoke";
+import { getUserTokenAccountAddress } from "../../utils/pdas";
+import { assertInstructionWillFail } from "../helpers";
+
+/**
+ * LEAF SCHEDULE TESTS
+ *
+ * @param testEnv
+ *
+ * This test suite initializes a new Distribution Tree with claims enabled where the first four
+ * leaves commit their own unlock and expiry timestamps, then:
+ *  1. Verifies a leaf cannot be paid before it unlocks
+ *  2. Verifies a leaf cannot be paid after it expires
+ *  3. Verifies a schedule that differs from the committed one fails the proof
+ *  4. Distributes and claims leaves within their window
+ *  5. Revokes a leaf that has not unlocked yet
+ */
+export async function leafScheduleTests(testEnv: TestEnvironment) {
+    const lockedIndex = 0;
+    const expiredIndex = 1;
+    const distributeIndex = 2;
+    const claimIndex = 3;
+
+    const now = Math.floor(Date.now() / 1000);
+    const neverExpires = new BN("9223372036854775807");
+    const leafSchedules: Record<number, LeafSchedule> = {
+        [lockedIndex]: { unlockTs: new BN(now + 30 * 24 * 60 * 60), expiryTs: neverExpires },
+        [expiredIndex]: { unlockTs: new BN(now - 2_000), expiryTs: new BN(now - 1_000) },
+        [distributeIndex]: { unlockTs: new BN(now - 1_000), expiryTs: neverExpires },
+        [claimIndex]: { unlockTs: new BN(now - 1_000), expiryTs: new BN(now + 24 * 60 * 60) },
+    };
+
+    describe('Leaves with their own unlock and expiry timestamps', () => {
+        before('Initializes a new distribution tree with scheduled leaves', async () => {
+            await createNewDistributionTree({
+                testEnv,
+                allowClaims: true,
+                leafExtensions: Object.fromEntries(
+                    Object.entries(leafSchedules).map(([index, schedule]) => [index, { schedule }])
+                ),
+            });
+        });
+
+        it('Cannot distribute a leaf before it unlocks', async () => {
+            const correctParams = await createDistributeParams({ testEnv, index: lockedIndex });
+            await assertInstructionWillFail({
+                testEnv,
+                params: correctParams,
+                executeInstruction: distribute,
+                expectedAnchorError: "LeafLocked"
+            });
+        });
+
+        it('Cannot distribute a leaf after it expires', async () => {
+            const correctParams = await createDistributeParams({ testEnv, index: expiredIndex });
+            await assertInstructionWillFail({
+                testEnv,
+                params: correctParams,
+                executeInstruction: distribute,
+                expectedAnchorError: "LeafExpired"
+            });
+        });
+
+        it('Cannot distribute a locked leaf with an earlier unlock timestamp', async () => {
+            const correctParams = await createDistributeParams({ testEnv, index: lockedIndex });
+            await assertInstructionWillFail({
+                testEnv,
+                params: { ...correctParams, schedule: { ...leafSchedules[lockedIndex], unlockTs: new BN(now - 1_000) } },
+                executeInstruction: distribute,
+                expectedAnchorError: "InvalidProof"
+            });
+        });
+
+        it('Cannot distribute a scheduled leaf without its schedule', async () => {
+            const correctParams = await createDistributeParams({ testEnv, index: distributeIndex });
+            await assertInstructionWillFail({
+                testEnv,
+                params: { ...correctParams, schedule: undefined },
+                executeInstruction: distribute,
+                expectedAnchorError: "InvalidProof"
+            });
+        });
+
+        it('Can distribute an unlocked leaf', async () => {
+            const correctParams = await createDistributeParams({ testEnv, index: distributeIndex });
+            await distribute(testEnv, correctParams);
+        });
+
+        it('Can claim an unlocked leaf before it expires', async () => {
+            const paymentInfo = getAccountByIndex(testEnv.merkleDistributorInfo, claimIndex);
+            if (!paymentInfo) {
+                throw new Error('No recipient found');
+            }
+            await airdropToMultiple([paymentInfo.keypair.publicKey], testEnv.provider.connection, web3.LAMPORTS_PER_SOL);
+            const claimParams: Claim = {
+                claimant: paymentInfo.keypair,
+                distributionTreePda: testEnv.distributionTreePda,
+                mint: testEnv.pyUsdMint,
+                tokenVault: testEnv.tokenVault,
+                payeeTokenAccount: getUserTokenAccountAddress({ recipient: paymentInfo.keypair.publicKey, mint: testEnv.pyUsdMint }),
+                amount: paymentInfo.amount,
+                proof: testEnv.balanceTree.getProof(claimIndex, paymentInfo.keypair.publicKey, paymentInfo.amount, paymentInfo),
+                batchId: testEnv.distributionUniqueId,
+                index: claimIndex,
+                schedule: paymentInfo.schedule,
+            };
+            await claim(testEnv, claimParams);
+        });
+
+        it('Can revoke a locked leaf with its schedule', async () => {
+            const paymentInfo = getAccountByIndex(testEnv.merkleDistributorInfo, lockedIndex);
+            if (!paymentInfo) {
+                throw new Error('No recipient found');
+            }
+            await revoke(testEnv, {
+                authority: testEnv.authority,
+                distributionTreePda: testEnv.distributionTreePda,
+                mint: testEnv.pyUsdMint,
+                tokenVault: testEnv.tokenVault,
+                batchId: testEnv.distributionUniqueId,
+                index: lockedIndex,
+                recipient: paymentInfo.keypair.publicKey,
+                amount: paymentInfo.amount,
+                proof: testEnv.balanceTree.getProof(lockedIndex, paymentInfo.keypair.publicKey, paymentInfo.amount, paymentInfo),
+                expectedClawback: paymentInfo.amount,
+                schedule: paymentInfo.schedule,
+            });
+        });
+    });
+}
@@ -0,0 +1,175 @@
+import { TestEnvironment } from "../../utils/environment/test-environment";
+import { BN, web3 } from "@coral-xyz/anchor";
+import { assert } from 'chai';
+import { airdropToMultiple } from "../../utils/solana-helpers";
+import { getAccountByIndex, InstallmentPlan, isBitSet, LeafSchedule } from "../../utils/merkle-tree";
+import { createNewDistributionTree } from "../1-initialize/initialize";
+import { createDistributeParams, distribute } from "../2-distribute/distribute";
+import { Claim, claim } from "../5-claim/claim";
+import { revoke } from "../16-revoke/revoke";
+import { getInstallmentRecordPDA, getUserTokenAccountAddress } from "../../utils/pdas";
+import { BASE_PAYMENT_AMOUNT } from "../../utils/constants";
+import { assertInstructionWillFail } from "../helpers";
+
+/**
+ * INSTALLMENT TESTS
+ *
+ * @param testEnv
+ *
+ * This test suite initializes a new Distribution Tree with claims enabled where the first three
+ * leaves are paid in installments, then:
+ *  1. Verifies an installment plan that doesn't add up to the leaf amount is rejected
+ *  2. Verifies a plan whose last installment is due after the leaf expires is rejected
+ *  3. Distributes the only installment due of a monthly leaf, leaving the leaf unmarked
+ *  4. Verifies the next installment can't be paid before it is due
+ *  5. Claims every overdue installment of a leaf at once, completing the leaf
+ *  6. Revokes the monthly leaf, clawing back only its unpaid installments
+ */
+export async function installmentTests(testEnv: TestEnvironment) {
+    const monthlyIndex = 0;
+    const overdueIndex = 1;
+    const expiringIndex = 2;
+    const startOffset = -1000;
+
+    // Leaf amounts are (index + 1) * BASE_PAYMENT_AMOUNT
+    const monthlyPlan: InstallmentPlan = {
+        amountPerInstallment: new BN(BASE_PAYMENT_AMOUNT / 4),
+        count: 4,
+        interval: new BN(30 * 24 * 60 * 60),
+    };
+    // Every installment is due by the time the tree has started
+    const overduePlan: InstallmentPlan = {
+        amountPerInstallment: new BN(2 * BASE_PAYMENT_AMOUNT / 4),
+        count: 4,
+        interval: new BN(100),
+    };
+
+    // The last of 3 monthly installments is due 60 days after the first, but the leaf expires after a day
+    const expiringPlan: InstallmentPlan = {
+        amountPerInstallment: new BN(BASE_PAYMENT_AMOUNT),
+        count: 3,
+        interval: new BN(30 * 24 * 60 * 60),
+    };
+    const now = Math.floor(Date.now() / 1000);
+    const expiringSchedule: LeafSchedule = { unlockTs: new BN(now - 1_000), expiryTs: new BN(now + 24 * 60 * 60) };
+
+    const fetchInstallmentRecord = (index: number) => testEnv.program.account.installmentRecord.fetch(
+        getInstallmentRecordPDA({
+            distributorProgram: testEnv.program.programId,
+            distributionTree: testEnv.distributionTreePda,
+            epoch: testEnv.epoch,
+            index
+        })
+    );
+
+    describe('Leaves paid in installments', () => {
+        before('Initializes a new distribution tree with installment leaves', async () => {
+            await createNewDistributionTree({
+                testEnv,
+                startOffset,
+                allowClaims: true,
+                leafExtensions: {
+                    [monthlyIndex]: { installments: monthlyPlan },
+                    [overdueIndex]: { installments: overduePlan },
+                    [expiringIndex]: { installments: expiringPlan, schedule: expiringSchedule },
+                },
+            });
+        });
+
+        it('Cannot distribute an installment plan that does not add up to the leaf amount', async () => {
+            const correctParams = await createDistributeParams({ testEnv, index: monthlyIndex });
+            await assertInstructionWillFail({
+                testEnv,
+                params: { ...correctParams, installments: { ...monthlyPlan, count: 3 } },
+                executeInstruction: distribute,
+                expectedAnchorError: "InvalidInstallmentPlan"
+            });
+        });
+
+        it('Cannot distribute an installment plan that ends after the leaf expires', async () => {
+            const correctParams = await createDistributeParams({ testEnv, index: expiringIndex });
+            await assertInstructionWillFail({
+                testEnv,
+                params: correctParams,
+                executeInstruction: distribute,
+                expectedAnchorError: "InstallmentsPastEnd"
+            });
+        });
+
+        it('Can distribute the first installment of a monthly leaf', async () => {
+            const correctParams = await createDistributeParams({ testEnv, index: monthlyIndex });
+            await distribute(testEnv, { ...correctParams, expectedPayout: monthlyPlan.amountPerInstallment });
+
+            const installmentRecord = await fetchInstallmentRecord(monthlyIndex);
+            assert.strictEqual(installmentRecord.installmentsPaid, 1);
+            assert.strictEqual(installmentRecord.count, monthlyPlan.count);
+            assert.strictEqual(installmentRecord.firstDueTs.toNumber(), testEnv.distributionStartTs);
+
+            const distributionTreeData = await testEnv.program.account.distributionTree.fetch(testEnv.distributionTreePda);
+            assert.isFalse(
+                isBitSet(distributionTreeData.recipientsDistributedBitmap, monthlyIndex),
+                "Leaf should not be marked before its last installment"
+            );
+        });
+
+        it('Cannot distribute an installment before it is due', async () => {
+            const correctParams = await createDistributeParams({ testEnv, index: monthlyIndex });
+            await assertInstructionWillFail({
+                testEnv,
+                params: correctParams,
+                executeInstruction: distribute,
+                expectedAnchorError: "NoInstallmentsDue"
+            });
+        });
+
+        it('Can claim every overdue installment at once', async () => {
+            const paymentInfo = getAccountByIndex(testEnv.merkleDistributorInfo, overdueIndex);
+            if (!paymentInfo) {
+                throw new Error('No recipient found');
+            }
+            await airdropToMultiple([paymentInfo.keypair.publicKey], testEnv.provider.connection, web3.LAMPORTS_PER_SOL);
+            const claimParams: Claim = {
+                claimant: paymentInfo.keypair,
+                distributionTreePda: testEnv.distributionTreePda,
+                mint: testEnv.pyUsdMint,
+                tokenVault: testEnv.tokenVault,
+                payeeTokenAccount: getUserTokenAccountAddress({ recipient: paymentInfo.keypair.publicKey, mint: testEnv.pyUsdMint }),
+                amount: paymentInfo.amount,
+                proof: testEnv.balanceTree.getProof(overdueIndex, paymentInfo.keypair.publicKey, paymentInfo.amount, paymentInfo),
+                batchId: testEnv.distributionUniqueId,
+                index: overdueIndex,
+                installments: overduePlan,
+            };
+            await claim(testEnv, claimParams);
+
+            const installmentRecord = await fetchInstallmentRecord(overdueIndex);
+            assert.strictEqual(installmentRecord.installmentsPaid, overduePlan.count);
+
+            const distributionTreeData = await testEnv.program.account.distributionTree.fetch(testEnv.distributionTreePda);
+            assert.isTrue(
+                isBitSet(distributionTreeData.recipientsDistributedBitmap, overdueIndex),
+                "Leaf should be marked once every installment has been paid"
+            );
+        });
+
+        it('Can revoke a partially paid leaf, clawing back its unpaid installments', async () => {
+            const paymentInfo = getAccountByIndex(testEnv.merkleDistributorInfo, monthlyIndex);
+            if (!paymentInfo) {
+                throw new Error('No recipient found');
+            }
+            await revoke(testEnv, {
+                authority: testEnv.authority,
+                distributionTreePda: testEnv.distributionTreePda,
+                mint: testEnv.pyUsdMint,
+                tokenVault: testEnv.tokenVault,
+                batchId: testEnv.distributionUniqueId,
+                index: monthlyIndex,
+                recipient: paymentInfo.keypair.publicKey,
+                amount: paymentInfo.amount,
+                proof: testEnv.balanceTree.getProof(monthlyIndex, paymentInfo.keypair.publicKey, paymentInfo.amount, paymentInfo),
+                expectedClawback: paymentInfo.amount.sub(monthlyPlan.amountPerInstallment),
+                installments: monthlyPlan,
+            });
+        });
+    });
+}
@@ -0,0 +1,79 @@
+import { TestEnvironment } from "../../utils/environment/test-environment";
+import { PublicKey, Keypair, SystemProgram, AccountMeta } from '@solana/web3.js';
+import { ASSOCIATED_TOKEN_PROGRAM_ID, createAssociatedTokenAccountIdempotent, TOKEN_2022_PROGRAM_ID } from "@solana/spl-token";
+import { BN } from '@coral-xyz/anchor';
+import { assert } from 'chai';
+import { getMintRegistryPDA, getTokenVaultAddress, getUserTokenAccountAddress } from "../../utils/pdas";
+
+export interface AddMint {
+    authority: Keypair,
+    distributionTreePda: PublicKey,
+    mint: PublicKey,
+    batchId: string,
+    transferToVaultAmount: BN,
+    acknowledgeMintRisks?: boolean,
+    // Extra accounts of the mint's transfer hook
+    transferHookAccounts?: AccountMeta[],
+}
+
+export async function addMint(
+    testEnv: TestEnvironment,
+    addMint: AddMint,
+) {
+    const addMintParams = {
+        batchId: addMint.batchId,
+        transferToVaultAmount: addMint.transferToVaultAmount,
+        acknowledgeMintRisks: addMint.acknowledgeMintRisks ?? true,
+    };
+
+    const mintRegistry = getMintRegistryPDA({
+        distributorProgram: testEnv.program.programId,
+        distributionTree: addMint.distributionTreePda
+    });
+    const tokenVault = getTokenVaultAddress({ mint: addMint.mint, distributionTreePDA: addMint.distributionTreePda });
+    // Fees on the funding are paid to the fees wallet's token account of the mint
+    const feesTokenAccount = await createAssociatedTokenAccountIdempotent(
+        testEnv.provider.connection,
+        addMint.authority,
+        addMint.mint,
+        testEnv.feesWallet.publicKey,
+        { commitment: 'processed' },
+        TOKEN_2022_PROGRAM_ID
+    );
+
+    const accounts = {
+        authority: addMint.authority.publicKey,
+        distributionTree: addMint.distributionTreePda,
+        mintRegistry,
+        mint: addMint.mint,
+        tokenSource: getUserTokenAccountAddress({ recipient: addMint.authority.publicKey, mint: addMint.mint }),
+        tokenVault,
+        feesTokenAccount,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+    };
+
+    try {
+        await testEnv.program.methods.addMint(addMintParams)
+            .accountsPartial(accounts)
+            .remainingAccounts(addMint.transferHookAccounts ?? [])
+            .signers([addMint.authority])
+            .rpc({ commitment: "processed" });
+
+        // Fetch and assert the MintRegistry account data
+        const mintRegistryData = await testEnv.program.account.mintRegistry.fetch(mintRegistry);
+        assert.strictEqual(mintRegistryData.distributionTree.toString(), addMint.distributionTreePda.toString());
+        assert.isTrue(
+            mintRegistryData.mints.some(mint => mint.equals(addMint.mint)),
+            "Mint should be registered"
+        );
+        assert.strictEqual(mintRegistryData.mintRisks.length, mintRegistryData.mints.length);
+
+        // Fetch and assert the token vault token account data
+        const tokenVaultData = await testEnv.program.provider.connection.getTokenAccountBalance(tokenVault);
+        assert.strictEqual(tokenVaultData.value.amount, addMint.transferToVaultAmount.toString());
+    } catch (error) {
+        throw error;
+    }
+}
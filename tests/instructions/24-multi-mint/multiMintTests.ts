@@ -0,0 +1,226 @@
+import { TestEnvironment } from "../../utils/environment/test-environment";
+import { BN, web3 } from "@coral-xyz/anchor";
+import { assert } from 'chai';
+import { createAssociatedTokenAccountIdempotent, mintTo, TOKEN_2022_PROGRAM_ID } from "@solana/spl-token";
+import { airdropToMultiple, makeTokenMint } from "../../utils/solana-helpers";
+import { getAccountByIndex } from "../../utils/merkle-tree";
+import { createNewDistributionTree } from "../1-initialize/initialize";
+import { createDistributeParams, distribute } from "../2-distribute/distribute";
+import { cancel, createCancelParams } from "../3-cancel/cancel";
+import { Claim, claim } from "../5-claim/claim";
+import { revoke } from "../16-revoke/revoke";
+import { getMintRegistryPDA, getTokenVaultAddress, getUserTokenAccountAddress } from "../../utils/pdas";
+import { BASE_PAYMENT_AMOUNT } from "../../utils/constants";
+import { assertInstructionWillFail } from "../helpers";
+import { close } from "../8-reclaim/reclaim";
+import { AddMint, addMint } from "./addMint";
+
+/**
+ * MULTI-MINT TESTS
+ *
+ * @param testEnv
+ *
+ * This test suite initializes a new Distribution Tree with claims enabled where the first three
+ * leaves pay a loyalty token (mint index 1) instead of PYUSD, then:
+ *  1. Verifies the tree's own mint cannot be registered again
+ *  2. Verifies the loyalty mint's freeze authority must be acknowledged
+ *  3. Registers and funds the loyalty mint, recording its risks
+ *  4. Verifies a loyalty leaf cannot be paid in PYUSD, nor without its mint index
+ *  5. Distributes and claims loyalty leaves from the loyalty vault
+ *  6. Revokes a loyalty leaf, clawing back from the loyalty vault
+ *  7. Cancels the tree, sweeping both the PYUSD and the loyalty vault to the authority
+ *  8. Closes the tree, closing both vaults
+ */
+export async function multiMintTests(testEnv: TestEnvironment) {
+    const distributeIndex = 0;
+    const claimIndex = 1;
+    const revokeIndex = 2;
+    const loyaltyMintIndex = 1;
+    const loyaltyVaultAmount = new BN(8 * BASE_PAYMENT_AMOUNT);
+
+    let loyaltyMint: web3.PublicKey;
+    let loyaltyVault: web3.PublicKey;
+    let correctAddMintParams: AddMint;
+
+    const withLoyaltyMint = <T extends { mint: web3.PublicKey, tokenVault: web3.PublicKey }>(params: T): T => ({
+        ...params,
+        mint: loyaltyMint,
+        tokenVault: loyaltyVault,
+    });
+
+    describe('Leaves paid in a registered mint', () => {
+        before('Initializes a new distribution tree and a loyalty mint', async () => {
+            await createNewDistributionTree({
+                testEnv,
+                allowClaims: true,
+                leafExtensions: {
+                    [distributeIndex]: { mintIndex: loyaltyMintIndex },
+                    [claimIndex]: { mintIndex: loyaltyMintIndex },
+                    [revokeIndex]: { mintIndex: loyaltyMintIndex },
+                },
+            });
+
+            loyaltyMint = await makeTokenMint({
+                connection: testEnv.provider.connection,
+                mintAuthority: testEnv.pyUsdMintAuthorityKeypair,
+                name: "Loyalty Points",
+                symbol: "LOYAL",
+                decimals: 6,
+                uri: "",
+                freezeAuthority: testEnv.pyUsdMintAuthorityKeypair.publicKey,
+            });
+            loyaltyVault = getTokenVaultAddress({ mint: loyaltyMint, distributionTreePDA: testEnv.distributionTreePda });
+
+            const authorityLoyaltyAccount = await createAssociatedTokenAccountIdempotent(
+                testEnv.provider.connection,
+                testEnv.authority,
+                loyaltyMint,
+                testEnv.authority.publicKey,
+                { commitment: 'processed', skipPreflight: true },
+                TOKEN_2022_PROGRAM_ID
+            );
+            await mintTo(
+                testEnv.provider.connection,
+                testEnv.authority,
+                loyaltyMint,
+                authorityLoyaltyAccount,
+                testEnv.pyUsdMintAuthorityKeypair,
+                loyaltyVaultAmount.toNumber(),
+                [],
+                { commitment: 'processed', skipPreflight: true },
+                TOKEN_2022_PROGRAM_ID
+            );
+
+            correctAddMintParams = {
+                authority: testEnv.authority,
+                distributionTreePda: testEnv.distributionTreePda,
+                mint: loyaltyMint,
+                batchId: testEnv.distributionUniqueId,
+                transferToVaultAmount: loyaltyVaultAmount,
+            };
+        });
+
+        it('Cannot register the tree\'s own mint', async () => {
+            await assertInstructionWillFail({
+                testEnv,
+                params: { ...correctAddMintParams, mint: testEnv.pyUsdMint },
+                executeInstruction: addMint,
+                expectedAnchorError: "MintAlreadyRegistered"
+            });
+        });
+
+        it('Cannot register a mint without acknowledging its freeze authority', async () => {
+            await assertInstructionWillFail({
+                testEnv,
+                params: { ...correctAddMintParams, acknowledgeMintRisks: false },
+                executeInstruction: addMint,
+                expectedAnchorError: "UnacknowledgedMintRisks"
+            });
+        });
+
+        it('Can register and fund an additional mint, recording its risks', async () => {
+            await addMint(testEnv, correctAddMintParams);
+            const mintRegistryData = await testEnv.program.account.mintRegistry.fetch(
+                getMintRegistryPDA({ distributorProgram: testEnv.program.programId, distributionTree: testEnv.distributionTreePda })
+            );
+            assert.deepStrictEqual(mintRegistryData.mintRisks[loyaltyMintIndex - 1], {
+                permanentDelegate: false,
+                defaultFrozen: false,
+                freezeAuthority: true,
+            });
+        });
+
+        it('Cannot distribute a loyalty leaf in PYUSD', async () => {
+            const correctParams = await createDistributeParams({ testEnv, index: distributeIndex });
+            await assertInstructionWillFail({
+                testEnv,
+                params: correctParams,
+                executeInstruction: distribute,
+                expectedAnchorError: "InvalidTokenMint"
+            });
+        });
+
+        it('Cannot distribute a loyalty leaf without its mint index', async () => {
+            const correctParams = await createDistributeParams({ testEnv, index: distributeIndex });
+            await assertInstructionWillFail({
+                testEnv,
+                params: { ...correctParams, mintIndex: undefined },
+                executeInstruction: distribute,
+                expectedAnchorError: "InvalidProof"
+            });
+        });
+
+        it('Can distribute a loyalty leaf from the loyalty vault', async () => {
+            const correctParams = await createDistributeParams({ testEnv, index: distributeIndex });
+            await distribute(testEnv, {
+                ...withLoyaltyMint(correctParams),
+                recipientTokenAccount: getUserTokenAccountAddress({ recipient: correctParams.recipient, mint: loyaltyMint }),
+            });
+        });
+
+        it('Can claim a loyalty leaf from the loyalty vault', async () => {
+            const paymentInfo = getAccountByIndex(testEnv.merkleDistributorInfo, claimIndex);
+            if (!paymentInfo) {
+                throw new Error('No recipient found');
+            }
+            await airdropToMultiple([paymentInfo.keypair.publicKey], testEnv.provider.connection, web3.LAMPORTS_PER_SOL);
+            const claimParams: Claim = withLoyaltyMint({
+                claimant: paymentInfo.keypair,
+                distributionTreePda: testEnv.distributionTreePda,
+                mint: testEnv.pyUsdMint,
+                tokenVault: testEnv.tokenVault,
+                payeeTokenAccount: getUserTokenAccountAddress({ recipient: paymentInfo.keypair.publicKey, mint: loyaltyMint }),
+                amount: paymentInfo.amount,
+                proof: testEnv.balanceTree.getProof(claimIndex, paymentInfo.keypair.publicKey, paymentInfo.amount, paymentInfo),
+                batchId: testEnv.distributionUniqueId,
+                index: claimIndex,
+                mintIndex: paymentInfo.mintIndex,
+            });
+            await claim(testEnv, claimParams);
+        });
+
+        it('Can revoke a loyalty leaf, clawing back from the loyalty vault', async () => {
+            const paymentInfo = getAccountByIndex(testEnv.merkleDistributorInfo, revokeIndex);
+            if (!paymentInfo) {
+                throw new Error('No recipient found');
+            }
+            await revoke(testEnv, withLoyaltyMint({
+                authority: testEnv.authority,
+                distributionTreePda: testEnv.distributionTreePda,
+                mint: testEnv.pyUsdMint,
+                tokenVault: testEnv.tokenVault,
+                batchId: testEnv.distributionUniqueId,
+                index: revokeIndex,
+                recipient: paymentInfo.keypair.publicKey,
+                amount: paymentInfo.amount,
+                proof: testEnv.balanceTree.getProof(revokeIndex, paymentInfo.keypair.publicKey, paymentInfo.amount, paymentInfo),
+                expectedClawback: paymentInfo.amount,
+                mintIndex: paymentInfo.mintIndex,
+            }));
+        });
+
+        it('Can cancel, sweeping every vault to the authority', async () => {
+            const authorityLoyaltyAccount = getUserTokenAccountAddress({ recipient: testEnv.authority.publicKey, mint: loyaltyMint });
+            const [initialLoyaltyVaultBalance, initialAuthorityLoyaltyBalance] = await Promise.all([
+                testEnv.provider.connection.getTokenAccountBalance(loyaltyVault),
+                testEnv.provider.connection.getTokenAccountBalance(authorityLoyaltyAccount),
+            ]);
+
+            const cancelParams = await createCancelParams({ testEnv });
+            await cancel(testEnv, { ...cancelParams, registeredMints: [loyaltyMint] });
+
+            const [loyaltyVaultBalance, authorityLoyaltyBalance] = await Promise.all([
+                testEnv.provider.connection.getTokenAccountBalance(loyaltyVault),
+                testEnv.provider.connection.getTokenAccountBalance(authorityLoyaltyAccount),
+            ]);
+            assert.strictEqual(loyaltyVaultBalance.value.amount, '0');
+            const authorityBalanceChange = BigInt(authorityLoyaltyBalance.value.amount) - BigInt(initialAuthorityLoyaltyBalance.value.amount);
+            assert.strictEqual(authorityBalanceChange.toString(), initialLoyaltyVaultBalance.value.amount);
+        });
+
+        it('Can close the tree, closing every vault', async () => {
+            // close asserts the registered vaults are closed and their rent returned to the authority
+            await close(testEnv, { acknowledgeIrreversible: true, registeredMints: [loyaltyMint] });
+        });
+    });
+}
@@ -0,0 +1,240 @@
+import { TestEnvironment } from "../../utils/environment/test-environment";
+import { PublicKey, Keypair, SystemProgram } from '@solana/web3.js';
+import { NATIVE_MINT } from "@solana/spl-token";
+import { BN } from '@coral-xyz/anchor';
+import { assert } from 'chai';
+import { isBitSet } from "../../utils/merkle-tree";
+import { getDisputePDA, getLeafRedirectPDA, getSolVaultPDA } from "../../utils/pdas";
+
+export interface InitializeNative {
+    authority: Keypair,
+    distributionTreePda: PublicKey,
+    merkleRoot: Buffer,
+    batchId: string,
+    totalNumberRecipients: number,
+    transferToVaultAmount: number,
+    startTs: number,
+    allowClaims?: boolean,
+}
+
+export async function initializeNative(
+    testEnv: TestEnvironment,
+    initialize: InitializeNative,
+) {
+    const initializeParams = {
+        merkleRoot: Array.from(initialize.merkleRoot),
+        batchId: initialize.batchId,
+        allowClaims: initialize.allowClaims ?? false,
+        totalNumberRecipients: new BN(initialize.totalNumberRecipients),
+        transferToVaultAmount: new BN(initialize.transferToVaultAmount),
+        startTs: new BN(initialize.startTs),
+        endTs: null,
+        gatekeeperNetwork: null,
+        hashAlgorithm: null,
+    };
+
+    const solVault = getSolVaultPDA({
+        distributorProgram: testEnv.program.programId,
+        distributionTree: initialize.distributionTreePda
+    });
+
+    const accounts = {
+        authority: initialize.authority.publicKey,
+        distributionTree: initialize.distributionTreePda,
+        solVault,
+        feesWallet: testEnv.feesWallet.publicKey,
+        systemProgram: SystemProgram.programId,
+    };
+
+    try {
+        await testEnv.program.methods.initializeNative(initializeParams)
+            .accountsPartial(accounts)
+            .signers([initialize.authority])
+            .rpc({ commitment: "processed" });
+
+        // Fetch and assert the DistributionTree account data
+        const distributionTreeData = await testEnv.program.account.distributionTree.fetch(initialize.distributionTreePda);
+        assert.strictEqual(distributionTreeData.mint.toString(), NATIVE_MINT.toString());
+        assert.strictEqual(distributionTreeData.tokenVault.toString(), solVault.toString());
+
+        // The SOL vault holds the distributed lamports plus its rent-exempt minimum
+        const rentReserve = await testEnv.provider.connection.getMinimumBalanceForRentExemption(0);
+        const solVaultBalance = await testEnv.provider.connection.getBalance(solVault, "processed");
+        assert.strictEqual(solVaultBalance, initialize.transferToVaultAmount + rentReserve);
+    } catch (error) {
+        throw error;
+    }
+}
+
+export interface DistributeNative {
+    authority: Keypair,
+    recipient: PublicKey,
+    distributionTreePda: PublicKey,
+    amount: BN,
+    proof: Buffer[],
+    batchId: string,
+    index: number,
+    epoch?: number,
+}
+
+export async function distributeNative(
+    testEnv: TestEnvironment,
+    distribute: DistributeNative,
+) {
+    const distributeParams = {
+        amount: distribute.amount,
+        proof: distribute.proof.map(buffer => Array.from(buffer)),
+        batchId: distribute.batchId,
+        index: new BN(distribute.index),
+        epoch: new BN(distribute.epoch ?? testEnv.epoch),
+    };
+
+    const accounts = {
+        authority: distribute.authority.publicKey,
+        recipient: distribute.recipient,
+        distributionTree: distribute.distributionTreePda,
+        solVault: getSolVaultPDA({
+            distributorProgram: testEnv.program.programId,
+            distributionTree: distribute.distributionTreePda
+        }),
+        leafRedirect: getLeafRedirectPDA({
+            distributorProgram: testEnv.program.programId,
+            distributionTree: distribute.distributionTreePda,
+            epoch: distributeParams.epoch.toNumber(),
+            index: distribute.index
+        }),
+        dispute: getDisputePDA({
+            distributorProgram: testEnv.program.programId,
+            distributionTree: distribute.distributionTreePda,
+            epoch: distributeParams.epoch.toNumber(),
+            index: distribute.index
+        }),
+        systemProgram: SystemProgram.programId,
+        gatewayToken: null,
+    };
+
+    const initialRecipientBalance = await testEnv.provider.connection.getBalance(distribute.recipient, "processed");
+
+    try {
+        await testEnv.program.methods.distributeNative(distributeParams)
+            .accountsPartial(accounts)
+            .signers([distribute.authority])
+            .rpc({ commitment: "processed" });
+
+        const distributionTreeData = await testEnv.program.account.distributionTree.fetch(distribute.distributionTreePda);
+        assert.isTrue(
+            isBitSet(distributionTreeData.recipientsDistributedBitmap, distribute.index),
+            `Bitmap not set for recipient at index ${distribute.index}`
+        );
+
+        const recipientBalance = await testEnv.provider.connection.getBalance(distribute.recipient, "processed");
+        assert.strictEqual(recipientBalance - initialRecipientBalance, distribute.amount.toNumber());
+    } catch (error) {
+        throw error;
+    }
+}
+
+export interface ClaimNative {
+    claimant: Keypair,
+    distributionTreePda: PublicKey,
+    amount: BN,
+    proof: Buffer[],
+    batchId: string,
+    index: number,
+    epoch?: number,
+}
+
+export async function claimNative(
+    testEnv: TestEnvironment,
+    claim: ClaimNative,
+) {
+    const claimParams = {
+        amount: claim.amount,
+        proof: claim.proof.map(buffer => Array.from(buffer)),
+        batchId: claim.batchId,
+        index: new BN(claim.index),
+        epoch: new BN(claim.epoch ?? testEnv.epoch),
+    };
+
+    const solVault = getSolVaultPDA({
+        distributorProgram: testEnv.program.programId,
+        distributionTree: claim.distributionTreePda
+    });
+
+    const accounts = {
+        claimant: claim.claimant.publicKey,
+        distributionTree: claim.distributionTreePda,
+        solVault,
+        leafRedirect: getLeafRedirectPDA({
+            distributorProgram: testEnv.program.programId,
+            distributionTree: claim.distributionTreePda,
+            epoch: claimParams.epoch.toNumber(),
+            index: claim.index
+        }),
+        dispute: getDisputePDA({
+            distributorProgram: testEnv.program.programId,
+            distributionTree: claim.distributionTreePda,
+            epoch: claimParams.epoch.toNumber(),
+            index: claim.index
+        }),
+        systemProgram: SystemProgram.programId,
+        gatewayToken: null,
+    };
+
+    const initialVaultBalance = await testEnv.provider.connection.getBalance(solVault, "processed");
+
+    try {
+        await testEnv.program.methods.claimNative(claimParams)
+            .accountsPartial(accounts)
+            .signers([claim.claimant])
+            .rpc({ commitment: "processed" });
+
+        const distributionTreeData = await testEnv.program.account.distributionTree.fetch(claim.distributionTreePda);
+        assert.isTrue(
+            isBitSet(distributionTreeData.recipientsDistributedBitmap, claim.index),
+            `Bitmap not set for claimant at index ${claim.index}`
+        );
+
+        // The claimant pays the transaction fee, so the payout is asserted on the vault
+        const vaultBalance = await testEnv.provider.connection.getBalance(solVault, "processed");
+        assert.strictEqual(initialVaultBalance - vaultBalance, claim.amount.toNumber());
+    } catch (error) {
+        throw error;
+    }
+}
+
+export interface CancelNative {
+    authority: Keypair,
+    distributionTreePda: PublicKey,
+    batchId: string,
+}
+
+export async function cancelNative(
+    testEnv: TestEnvironment,
+    cancel: CancelNative,
+) {
+    const solVault = getSolVaultPDA({
+        distributorProgram: testEnv.program.programId,
+        distributionTree: cancel.distributionTreePda
+    });
+
+    try {
+        await testEnv.program.methods.cancelNative({ batchId: cancel.batchId })
+            .accountsPartial({
+                authority: cancel.authority.publicKey,
+                distributionTree: cancel.distributionTreePda,
+                solVault,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([cancel.authority])
+            .rpc({ commitment: "processed" });
+
+        const distributionTreeData = await testEnv.program.account.distributionTree.fetch(cancel.distributionTreePda);
+        assert.deepStrictEqual(distributionTreeData.status, { cancelled: {} });
+
+        const solVaultBalance = await testEnv.provider.connection.getBalance(solVault, "processed");
+        assert.strictEqual(solVaultBalance, 0);
+    } catch (error) {
+        throw error;
+    }
+}
@@ -0,0 +1,105 @@
+import { TestEnvironment } from "../../utils/environment/test-environment";
+import { web3 } from "@coral-xyz/anchor";
+import { airdropToMultiple } from "../../utils/solana-helpers";
+import { getAccountByIndex } from "../../utils/merkle-tree";
+import { BASE_PAYMENT_AMOUNT } from "../../utils/constants";
+import { assertInstructionWillFail } from "../helpers";
+import { cancelNative, claimNative, DistributeNative, distributeNative, initializeNative } from "./native";
+
+/**
+ * NATIVE SOL TESTS
+ *
+ * @param testEnv
+ *
+ * This test suite initializes a native SOL Distribution Tree with claims enabled, funded for
+ * the first two leaves only (amounts in lamports), then:
+ *  1. Distributes lamports to a recipient with a system transfer from the SOL vault
+ *  2. Verifies a leaf cannot be distributed twice
+ *  3. Claims lamports as a recipient
+ *  4. Verifies a payout that would leave the SOL vault below its rent-exempt minimum is rejected
+ *  5. Cancels the tree, refunding the SOL vault to the authority
+ */
+export async function nativeSolTests(testEnv: TestEnvironment) {
+    const distributeIndex = 0;
+    const claimIndex = 1;
+    const unfundedIndex = 2;
+
+    const createDistributeNativeParams = (index: number): DistributeNative => {
+        const paymentInfo = getAccountByIndex(testEnv.merkleDistributorInfo, index);
+        if (!paymentInfo) {
+            throw new Error('No recipient found');
+        }
+        return {
+            authority: testEnv.authority,
+            recipient: paymentInfo.keypair.publicKey,
+            distributionTreePda: testEnv.distributionTreePda,
+            amount: paymentInfo.amount,
+            proof: testEnv.balanceTree.getProof(index, paymentInfo.keypair.publicKey, paymentInfo.amount),
+            batchId: testEnv.distributionUniqueId,
+            index,
+        };
+    };
+
+    describe('Native SOL distribution trees', () => {
+        before('Initializes a native SOL distribution tree', async () => {
+            await testEnv.newTree();
+            // Leaf amounts are (index + 1) * BASE_PAYMENT_AMOUNT lamports
+            await initializeNative(testEnv, {
+                authority: testEnv.authority,
+                distributionTreePda: testEnv.distributionTreePda,
+                merkleRoot: testEnv.balanceTree.getRoot(),
+                batchId: testEnv.distributionUniqueId,
+                totalNumberRecipients: Object.keys(testEnv.merkleDistributorInfo.payments).length,
+                transferToVaultAmount: 3 * BASE_PAYMENT_AMOUNT,
+                startTs: testEnv.distributionStartTs,
+                allowClaims: true,
+            });
+        });
+
+        it('Can distribute lamports to a recipient', async () => {
+            await distributeNative(testEnv, createDistributeNativeParams(distributeIndex));
+        });
+
+        it('Cannot distribute a leaf twice', async () => {
+            await assertInstructionWillFail({
+                testEnv,
+                params: createDistributeNativeParams(distributeIndex),
+                executeInstruction: distributeNative,
+                expectedAnchorError: "AlreadyClaimed"
+            });
+        });
+
+        it('Can claim lamports', async () => {
+            const paymentInfo = getAccountByIndex(testEnv.merkleDistributorInfo, claimIndex);
+            if (!paymentInfo) {
+                throw new Error('No recipient found');
+            }
+            await airdropToMultiple([paymentInfo.keypair.publicKey], testEnv.provider.connection, web3.LAMPORTS_PER_SOL);
+            await claimNative(testEnv, {
+                claimant: paymentInfo.keypair,
+                distributionTreePda: testEnv.distributionTreePda,
+                amount: paymentInfo.amount,
+                proof: testEnv.balanceTree.getProof(claimIndex, paymentInfo.keypair.publicKey, paymentInfo.amount),
+                batchId: testEnv.distributionUniqueId,
+                index: claimIndex,
+            });
+        });
+
+        it('Cannot distribute into the SOL vault\'s rent reserve', async () => {
+            await assertInstructionWillFail({
+                testEnv,
+                params: createDistributeNativeParams(unfundedIndex),
+                executeInstruction: distributeNative,
+                expectedAnchorError: "InsufficientVaultBalance"
+            });
+        });
+
+        it('Can cancel, refunding the SOL vault to the authority', async () => {
+            await cancelNative(testEnv, {
+                authority: testEnv.authority,
+                distributionTreePda: testEnv.distributionTreePda,
+                batchId: testEnv.distributionUniqueId,
+            });
+        });
+    });
+}
@@ -0,0 +1,137 @@
+import { TestEnvironment } from "../../utils/environment/test-environment";
+import { BN, web3 } from "@coral-xyz/anchor";
+import { assert } from 'chai';
+import {
+    createAssociatedTokenAccountIdempotent,
+    getAccount,
+    getTransferFeeAmount,
+    mintTo,
+    TOKEN_2022_PROGRAM_ID,
+} from "@solana/spl-token";
+import { makeTransferFeeMint } from "../../utils/solana-helpers";
+import { createNewDistributionTree } from "../1-initialize/initialize";
+import { createDistributeParams, distribute } from "../2-distribute/distribute";
+import { getTokenVaultAddress, getUserTokenAccountAddress } from "../../utils/pdas";
+import { BASE_PAYMENT_AMOUNT } from "../../utils/constants";
+import { addMint } from "../24-multi-mint/addMint";
+
+/**
+ * TRANSFER FEE TESTS
+ *
+ * @param testEnv
+ *
+ * This test suite initializes a new Distribution Tree whose first leaf pays a registered
+ * Token-2022 mint with a 1% TransferFee extension, then:
+ *  1. Verifies funding a vault grosses up the deposit so the vault receives the full amount
+ *  2. Verifies a fixed amount payout is grossed up so the recipient receives the full leaf amount,
+ *     with the fee withheld in the recipient's token account
+ *  3. Verifies deposits at the fee's rounding edges (a fractional fee rounded up, a capped fee and
+ *     a high fee rate) are accepted, with the vault receiving at least the deposit amount
+ */
+export async function transferFeeTests(testEnv: TestEnvironment) {
+    const feeIndex = 0;
+    const feeMintIndex = 1;
+    const transferFeeBasisPoints = 100;
+    const vaultAmount = new BN(2 * BASE_PAYMENT_AMOUNT);
+
+    let feeMint: web3.PublicKey;
+    let feeVault: web3.PublicKey;
+
+    const createFundedFeeMint = async (basisPoints: number, maximumFee: bigint) => {
+        const mint = await makeTransferFeeMint({
+            connection: testEnv.provider.connection,
+            mintAuthority: testEnv.pyUsdMintAuthorityKeypair,
+            decimals: 6,
+            transferFeeBasisPoints: basisPoints,
+            maximumFee,
+        });
+        const authorityTokenAccount = await createAssociatedTokenAccountIdempotent(
+            testEnv.provider.connection,
+            testEnv.authority,
+            mint,
+            testEnv.authority.publicKey,
+            { commitment: 'processed', skipPreflight: true },
+            TOKEN_2022_PROGRAM_ID
+        );
+        await mintTo(
+            testEnv.provider.connection,
+            testEnv.authority,
+            mint,
+            authorityTokenAccount,
+            testEnv.pyUsdMintAuthorityKeypair,
+            10 * BASE_PAYMENT_AMOUNT,
+            [],
+            { commitment: 'processed', skipPreflight: true },
+            TOKEN_2022_PROGRAM_ID
+        );
+        return mint;
+    };
+
+    describe('Mints with a transfer fee', () => {
+        before('Initializes a new distribution tree and a transfer fee mint', async () => {
+            await createNewDistributionTree({
+                testEnv,
+                leafExtensions: { [feeIndex]: { mintIndex: feeMintIndex } },
+            });
+
+            feeMint = await createFundedFeeMint(transferFeeBasisPoints, BigInt(BASE_PAYMENT_AMOUNT));
+            feeVault = getTokenVaultAddress({ mint: feeMint, distributionTreePDA: testEnv.distributionTreePda });
+        });
+
+        it('Grosses up the deposit so the vault receives the full amount', async () => {
+            // addMint asserts the vault balance equals the deposit amount
+            await addMint(testEnv, {
+                authority: testEnv.authority,
+                distributionTreePda: testEnv.distributionTreePda,
+                mint: feeMint,
+                batchId: testEnv.distributionUniqueId,
+                transferToVaultAmount: vaultAmount,
+            });
+        });
+
+        it('Grosses up a fixed amount payout so the recipient receives the full leaf amount', async () => {
+            const correctParams = await createDistributeParams({ testEnv, index: feeIndex });
+            const recipientTokenAccount = getUserTokenAccountAddress({ recipient: correctParams.recipient, mint: feeMint });
+
+            // distribute asserts the recipient received exactly the leaf amount
+            await distribute(testEnv, {
+                ...correctParams,
+                mint: feeMint,
+                tokenVault: feeVault,
+                recipientTokenAccount,
+            });
+
+            const [vaultBalance, recipientAccount] = await Promise.all([
+                testEnv.provider.connection.getTokenAccountBalance(feeVault),
+                getAccount(testEnv.provider.connection, recipientTokenAccount, 'processed', TOKEN_2022_PROGRAM_ID),
+            ]);
+            const vaultBalanceChange = BigInt(vaultAmount.toString()) - BigInt(vaultBalance.value.amount);
+            const withheldAmount = getTransferFeeAmount(recipientAccount)?.withheldAmount ?? BigInt(0);
+            assert.isTrue(withheldAmount > BigInt(0), "Transfer fee should be withheld");
+            assert.strictEqual(vaultBalanceChange.toString(), (BigInt(correctParams.amount.toString()) + withheldAmount).toString());
+        });
+
+        it('Accepts deposits at the rounding edges of the transfer fee', async () => {
+            const roundingEdges = [
+                // 1% of a single unit rounds the fee up to a whole unit
+                { basisPoints: transferFeeBasisPoints, maximumFee: BigInt(BASE_PAYMENT_AMOUNT), amount: new BN(1) },
+                // The fee is capped at the mint's maximum fee
+                { basisPoints: transferFeeBasisPoints, maximumFee: BigInt(5), amount: new BN(BASE_PAYMENT_AMOUNT) },
+                // A high fee rate on an amount that does not divide evenly
+                { basisPoints: 3_333, maximumFee: BigInt(BASE_PAYMENT_AMOUNT), amount: new BN(7) },
+            ];
+
+            for (const { basisPoints, maximumFee, amount } of roundingEdges) {
+                const mint = await createFundedFeeMint(basisPoints, maximumFee);
+                // addMint asserts the vault balance equals the deposit amount
+                await addMint(testEnv, {
+                    authority: testEnv.authority,
+                    distributionTreePda: testEnv.distributionTreePda,
+                    mint,
+                    batchId: testEnv.distributionUniqueId,
+                    transferToVaultAmount: amount,
+                });
+            }
+        });
+    });
+}
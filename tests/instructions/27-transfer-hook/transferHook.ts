@@ -0,0 +1,51 @@
+import * as anchor from '@coral-xyz/anchor';
+import { AccountMeta, PublicKey, Keypair, SystemProgram } from '@solana/web3.js';
+import { SampleTransferHook } from "../../../target/types/sample_transfer_hook";
+
+export const getSampleTransferHookProgram = () =>
+    anchor.workspace.SampleTransferHook as anchor.Program<SampleTransferHook>;
+
+export const getExtraAccountMetaListPDA = ({ transferHookProgram, mint }: { transferHookProgram: PublicKey, mint: PublicKey }) =>
+    PublicKey.findProgramAddressSync(
+        [Buffer.from("extra-account-metas"), mint.toBuffer()],
+        transferHookProgram
+    )[0];
+
+export const getTransferCounterPDA = ({ transferHookProgram, mint }: { transferHookProgram: PublicKey, mint: PublicKey }) =>
+    PublicKey.findProgramAddressSync(
+        [Buffer.from("TRANSFER_COUNTER"), mint.toBuffer()],
+        transferHookProgram
+    )[0];
+
+// Remaining accounts forwarded to the sample transfer hook: its extra-account-metas PDA,
+// the TransferCounter listed in it and the hook program
+export function getTransferHookAccounts(mint: PublicKey): AccountMeta[] {
+    const transferHookProgram = getSampleTransferHookProgram().programId;
+    return [
+        { pubkey: getExtraAccountMetaListPDA({ transferHookProgram, mint }), isWritable: false, isSigner: false },
+        { pubkey: getTransferCounterPDA({ transferHookProgram, mint }), isWritable: true, isSigner: false },
+        { pubkey: transferHookProgram, isWritable: false, isSigner: false },
+    ];
+}
+
+export async function initializeTransferHook(payer: Keypair, mint: PublicKey) {
+    const transferHookProgram = getSampleTransferHookProgram();
+    await transferHookProgram.methods.initializeExtraAccountMetaList()
+        .accountsPartial({
+            payer: payer.publicKey,
+            extraAccountMetaList: getExtraAccountMetaListPDA({ transferHookProgram: transferHookProgram.programId, mint }),
+            transferCounter: getTransferCounterPDA({ transferHookProgram: transferHookProgram.programId, mint }),
+            mint,
+            systemProgram: SystemProgram.programId,
+        })
+        .signers([payer])
+        .rpc({ commitment: "processed" });
+}
+
+export async function fetchTransferCounter(mint: PublicKey) {
+    const transferHookProgram = getSampleTransferHookProgram();
+    return transferHookProgram.account.transferCounter.fetch(
+        getTransferCounterPDA({ transferHookProgram: transferHookProgram.programId, mint }),
+        "processed"
+    );
+}
@@ -0,0 +1,136 @@
+import { TestEnvironment } from "../../utils/environment/test-environment";
+import { BN, web3 } from "@coral-xyz/anchor";
+import { assert } from 'chai';
+import { createAssociatedTokenAccountIdempotent, mintTo, TOKEN_2022_PROGRAM_ID } from "@solana/spl-token";
+import { makeTransferHookMint } from "../../utils/solana-helpers";
+import { createNewDistributionTree } from "../1-initialize/initialize";
+import { createDistributeParams, distribute } from "../2-distribute/distribute";
+import { cancel, createCancelParams } from "../3-cancel/cancel";
+import { getTokenVaultAddress, getUserTokenAccountAddress } from "../../utils/pdas";
+import { BASE_PAYMENT_AMOUNT } from "../../utils/constants";
+import { addMint, AddMint } from "../24-multi-mint/addMint";
+import { assertInstructionWillFail } from "../helpers";
+import {
+    fetchTransferCounter,
+    getSampleTransferHookProgram,
+    getTransferHookAccounts,
+    initializeTransferHook,
+} from "./transferHook";
+
+/**
+ * TRANSFER HOOK TESTS
+ *
+ * @param testEnv
+ *
+ * This test suite initializes a new Distribution Tree whose first leaf pays a registered
+ * Token-2022 mint with a TransferHook extension pointing at the sample transfer hook program,
+ * which counts the mint's transfers, then:
+ *  1. Verifies funding the vault fails without the hook's extra accounts
+ *  2. Funds the vault, distributes the leaf and cancels the tree, forwarding the hook's
+ *     extra accounts from the remaining accounts, and verifies the hook ran on every transfer
+ */
+export async function transferHookTests(testEnv: TestEnvironment) {
+    const hookIndex = 0;
+    const hookMintIndex = 1;
+    const vaultAmount = new BN(2 * BASE_PAYMENT_AMOUNT);
+
+    let hookMint: web3.PublicKey;
+    let hookVault: web3.PublicKey;
+    let correctAddMintParams: AddMint;
+
+    describe('Mints with a transfer hook', () => {
+        before('Initializes a new distribution tree and a transfer hook mint', async () => {
+            await createNewDistributionTree({
+                testEnv,
+                leafExtensions: { [hookIndex]: { mintIndex: hookMintIndex } },
+            });
+
+            hookMint = await makeTransferHookMint({
+                connection: testEnv.provider.connection,
+                mintAuthority: testEnv.pyUsdMintAuthorityKeypair,
+                decimals: 6,
+                transferHookProgramId: getSampleTransferHookProgram().programId,
+            });
+            await initializeTransferHook(testEnv.authority, hookMint);
+            hookVault = getTokenVaultAddress({ mint: hookMint, distributionTreePDA: testEnv.distributionTreePda });
+
+            const authorityHookAccount = await createAssociatedTokenAccountIdempotent(
+                testEnv.provider.connection,
+                testEnv.authority,
+                hookMint,
+                testEnv.authority.publicKey,
+                { commitment: 'processed', skipPreflight: true },
+                TOKEN_2022_PROGRAM_ID
+            );
+            await mintTo(
+                testEnv.provider.connection,
+                testEnv.authority,
+                hookMint,
+                authorityHookAccount,
+                testEnv.pyUsdMintAuthorityKeypair,
+                10 * BASE_PAYMENT_AMOUNT,
+                [],
+                { commitment: 'processed', skipPreflight: true },
+                TOKEN_2022_PROGRAM_ID
+            );
+
+            correctAddMintParams = {
+                authority: testEnv.authority,
+                distributionTreePda: testEnv.distributionTreePda,
+                mint: hookMint,
+                batchId: testEnv.distributionUniqueId,
+                transferToVaultAmount: vaultAmount,
+                transferHookAccounts: getTransferHookAccounts(hookMint),
+            };
+        });
+
+        it('Cannot fund a vault without the transfer hook accounts', async () => {
+            await assertInstructionWillFail({
+                testEnv,
+                params: { ...correctAddMintParams, transferHookAccounts: undefined },
+                executeInstruction: addMint,
+                // TransferHookError::IncorrectAccount
+                expectedTransactionError: "0x7dc8348c"
+            });
+        });
+
+        it('Can fund a vault with the transfer hook accounts', async () => {
+            await addMint(testEnv, correctAddMintParams);
+
+            const transferCounter = await fetchTransferCounter(hookMint);
+            assert.strictEqual(transferCounter.transfers.toNumber(), 1);
+            assert.strictEqual(transferCounter.amount.toString(), vaultAmount.toString());
+        });
+
+        it('Can distribute a leaf with the transfer hook accounts', async () => {
+            const correctParams = await createDistributeParams({ testEnv, index: hookIndex });
+            await distribute(testEnv, {
+                ...correctParams,
+                mint: hookMint,
+                tokenVault: hookVault,
+                recipientTokenAccount: getUserTokenAccountAddress({ recipient: correctParams.recipient, mint: hookMint }),
+                transferHookAccounts: getTransferHookAccounts(hookMint),
+            });
+
+            const transferCounter = await fetchTransferCounter(hookMint);
+            assert.strictEqual(transferCounter.transfers.toNumber(), 2);
+        });
+
+        it('Can cancel and sweep a vault with the transfer hook accounts', async () => {
+            const cancelParams = await createCancelParams({ testEnv });
+            await cancel(testEnv, {
+                ...cancelParams,
+                registeredMints: [hookMint],
+                transferHookAccounts: getTransferHookAccounts(hookMint),
+            });
+
+            const transferCounter = await fetchTransferCounter(hookMint);
+            assert.strictEqual(transferCounter.transfers.toNumber(), 3);
+            // The deposit, then the leaf and the rest of the vault, which add up to the deposit
+            assert.strictEqual(transferCounter.amount.toString(), vaultAmount.muln(2).toString());
+
+            const hookVaultBalance = await testEnv.provider.connection.getTokenAccountBalance(hookVault);
+            assert.strictEqual(hookVaultBalance.value.amount, '0');
+        });
+    });
+}
@@ -0,0 +1,101 @@
+import { TestEnvironment } from "../../utils/environment/test-environment";
+import { assert } from 'chai';
+import { isBitSet } from "../../utils/merkle-tree";
+import { assertDispatchFails, buildDispatch, dispatch, DispatchFiles, writeDispatchFiles } from "./dispatch";
+
+/**
+ * DISPATCH CLI TESTS
+ *
+ * @param testEnv
+ *
+ * This test suite runs the `dispatch` binary against the local validator with a recipients CSV
+ * of a new set of payments, then:
+ *  1. Initializes a tree and verifies its merkle root matches the TypeScript tree
+ *  2. Distributes leaves, skipping leaves that have already been distributed
+ *  3. Verifies a recipients file that does not match the tree is rejected
+ *  4. Pauses and resumes the distribution
+ *  5. Cancels, reclaims and closes the tree
+ */
+export async function cliTests(testEnv: TestEnvironment) {
+    const numPayments = 4;
+    let files: DispatchFiles;
+    let batchId: string;
+
+    describe('Running the distribution lifecycle with the dispatch CLI', () => {
+        before('Builds the CLI and writes the recipients file', async () => {
+            buildDispatch();
+            await testEnv.newTree({ numPayments });
+            batchId = testEnv.distributionUniqueId;
+            files = writeDispatchFiles(testEnv.authority, testEnv.merkleDistributorInfo);
+        });
+
+        it('Can initialize a tree from a recipients file', async () => {
+            const report = dispatch(files, [
+                "initialize",
+                "--batch-id", batchId,
+                "--recipients", files.recipients,
+                "--start-ts", testEnv.distributionStartTs.toString(),
+                "--acknowledge-mint-risks",
+            ]);
+            assert.strictEqual(report.distribution_tree, testEnv.distributionTreePda.toBase58());
+            assert.strictEqual(report.merkle_root, testEnv.balanceTree.getRoot().toString('hex'));
+            assert.strictEqual(report.total_number_recipients, numPayments);
+            assert.strictEqual(report.status, "Active");
+        });
+
+        it('Cannot expand an active tree', async () => {
+            assertDispatchFails(files, ["expand", "--batch-id", batchId], "does not need to be expanded");
+        });
+
+        it('Can distribute leaves and skips those already distributed', async () => {
+            const report = dispatch(files, ["distribute", "--batch-id", batchId, "--recipients", files.recipients, "--index", "0", "1"]);
+            assert.strictEqual(report.distributed.length, 2);
+            assert.strictEqual(report.number_distributed, 2);
+
+            const repeated = dispatch(files, ["distribute", "--batch-id", batchId, "--recipients", files.recipients, "--index", "1", "2"]);
+            assert.deepStrictEqual(repeated.skipped, [1]);
+            assert.strictEqual(repeated.distributed[0].index, 2);
+
+            const distributionTreeData = await testEnv.program.account.distributionTree.fetch(testEnv.distributionTreePda);
+            [0, 1, 2].forEach(index => assert.isTrue(isBitSet(distributionTreeData.recipientsDistributedBitmap, index)));
+            assert.isFalse(isBitSet(distributionTreeData.recipientsDistributedBitmap, 3));
+        });
+
+        it('Cannot distribute with a recipients file that does not match the tree', async () => {
+            const otherFiles = writeDispatchFiles(
+                testEnv.authority,
+                { ...testEnv.merkleDistributorInfo, payments: testEnv.merkleDistributorInfo.payments.slice(1) }
+            );
+            assertDispatchFails(
+                otherFiles,
+                ["distribute", "--batch-id", batchId, "--recipients", otherFiles.recipients, "--index", "3"],
+                "does not match the tree's merkle root"
+            );
+        });
+
+        it('Can pause and resume the distribution', async () => {
+            assert.strictEqual(dispatch(files, ["pause", "--batch-id", batchId]).status, "Paused");
+            assert.strictEqual(dispatch(files, ["resume", "--batch-id", batchId]).status, "Active");
+        });
+
+        it('Can cancel the distribution', async () => {
+            const report = dispatch(files, ["cancel", "--batch-id", batchId]);
+            assert.strictEqual(report.status, "Cancelled");
+            assert.strictEqual(report.vault_balance, "0");
+        });
+
+        it('Can reclaim the cancelled tree', async () => {
+            dispatch(files, ["reclaim", "--batch-id", batchId]);
+        });
+
+        it('Cannot close the tree without confirming', async () => {
+            assertDispatchFails(files, ["close", "--batch-id", batchId], "pass --yes to confirm");
+        });
+
+        it('Can close the tree', async () => {
+            const report = dispatch(files, ["close", "--batch-id", batchId, "--yes"]);
+            assert.isTrue(report.closed);
+            assert.isNull(await testEnv.program.account.distributionTree.fetchNullable(testEnv.distributionTreePda));
+        });
+    });
+}
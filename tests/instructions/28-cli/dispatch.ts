@@ -0,0 +1,55 @@
+import { execFileSync } from "child_process";
+import { mkdtempSync, writeFileSync } from "fs";
+import { tmpdir } from "os";
+import { join, resolve } from "path";
+import { Keypair } from "@solana/web3.js";
+import { assert } from "chai";
+import { MerkleDistributorInfo } from "../../utils/merkle-tree";
+
+const REPO_ROOT = resolve(__dirname, "../../..");
+const DISPATCH_BINARY = join(REPO_ROOT, "target", "debug", "dispatch");
+
+// Builds the dispatch CLI, which `anchor build` does not build
+export function buildDispatch() {
+    execFileSync("cargo", ["build", "-p", "dispatch-cli"], { cwd: REPO_ROOT, stdio: "inherit" });
+}
+
+// Files read by the CLI: the authority keypair and the recipients CSV of the tree
+export interface DispatchFiles {
+    directory: string,
+    keypair: string,
+    recipients: string,
+}
+
+export function writeDispatchFiles(authority: Keypair, merkleDistributorInfo: MerkleDistributorInfo): DispatchFiles {
+    const directory = mkdtempSync(join(tmpdir(), "dispatch-"));
+    const keypair = join(directory, "authority.json");
+    writeFileSync(keypair, JSON.stringify(Array.from(authority.secretKey)));
+
+    const recipients = join(directory, "recipients.csv");
+    const rows = merkleDistributorInfo.payments.map(
+        payment => `${payment.index},${payment.keypair.publicKey.toBase58()},${payment.amount.toString()}`
+    );
+    writeFileSync(recipients, ["index,recipient,amount", ...rows].join("\n"));
+    return { directory, keypair, recipients };
+}
+
+// Runs a dispatch command against the local validator and returns its JSON report
+export function dispatch(files: DispatchFiles, args: string[]): any {
+    const output = execFileSync(
+        DISPATCH_BINARY,
+        [...args, "--keypair", files.keypair, "--output", "json"],
+        { cwd: REPO_ROOT, encoding: "utf8", stdio: ["ignore", "pipe", "pipe"] }
+    );
+    return JSON.parse(output);
+}
+
+export function assertDispatchFails(files: DispatchFiles, args: string[], expectedError: string) {
+    try {
+        dispatch(files, args);
+    } catch (error: any) {
+        assert.include(error.stderr.toString(), expectedError);
+        return;
+    }
+    assert.fail(`dispatch ${args[0]} should have failed with ${expectedError}`);
+}
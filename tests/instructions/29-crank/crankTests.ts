@@ -0,0 +1,88 @@
+import { TestEnvironment } from "../../utils/environment/test-environment";
+import { assert } from 'chai';
+import { readFileSync, writeFileSync } from "fs";
+import { join } from "path";
+import { isBitSet } from "../../utils/merkle-tree";
+import { assertDispatchFails, buildDispatch, dispatch, DispatchFiles, writeDispatchFiles } from "../28-cli/dispatch";
+
+/**
+ * DISTRIBUTION CRANK TESTS
+ *
+ * @param testEnv
+ *
+ * This test suite initializes a new Distribution Tree with the dispatch CLI and distributes its
+ * first leaf, then:
+ *  1. Cranks the remaining leaves from a state file that wrongly records an undistributed leaf,
+ *     verifying the leaf is reconciled with the recipients bitmap and distributed
+ *  2. Verifies the state file records every leaf
+ *  3. Verifies a completed tree cannot be cranked
+ */
+export async function crankTests(testEnv: TestEnvironment) {
+    const numPayments = 6;
+    const staleIndex = 4;
+    let files: DispatchFiles;
+    let batchId: string;
+    let statePath: string;
+
+    describe('Cranking a distribution from a state file', () => {
+        before('Initializes a new distribution tree and distributes its first leaf', async () => {
+            buildDispatch();
+            await testEnv.newTree({ numPayments });
+            batchId = testEnv.distributionUniqueId;
+            files = writeDispatchFiles(testEnv.authority, testEnv.merkleDistributorInfo);
+            statePath = join(files.directory, "crank-state.json");
+
+            dispatch(files, [
+                "initialize",
+                "--batch-id", batchId,
+                "--recipients", files.recipients,
+                "--start-ts", testEnv.distributionStartTs.toString(),
+                "--acknowledge-mint-risks",
+            ]);
+            dispatch(files, ["distribute", "--batch-id", batchId, "--recipients", files.recipients, "--index", "0"]);
+
+            // A state file left by a crash after leaf 4 was sent but before it landed
+            writeFileSync(statePath, JSON.stringify({
+                distribution_tree: testEnv.distributionTreePda.toBase58(),
+                epoch: 0,
+                merkle_root: testEnv.balanceTree.getRoot().toString('hex'),
+                distributed: [staleIndex],
+                failed: {},
+            }));
+        });
+
+        it('Can crank every leaf not marked in the recipients bitmap', async () => {
+            const report = dispatch(files, [
+                "crank",
+                "--batch-id", batchId,
+                "--recipients", files.recipients,
+                "--state", statePath,
+                "--concurrency", "2",
+                "--checkpoint-interval", "2",
+            ]);
+            assert.strictEqual(report.already_distributed, 1);
+            assert.deepStrictEqual(report.reconciled, [staleIndex]);
+            assert.strictEqual(report.distributed, numPayments - 1);
+            assert.deepStrictEqual(report.failed, {});
+            assert.strictEqual(report.status, "Complete");
+
+            const distributionTreeData = await testEnv.program.account.distributionTree.fetch(testEnv.distributionTreePda);
+            for (let index = 0; index < numPayments; index++) {
+                assert.isTrue(isBitSet(distributionTreeData.recipientsDistributedBitmap, index));
+            }
+        });
+
+        it('Records every leaf in the state file', async () => {
+            const state = JSON.parse(readFileSync(statePath, "utf8"));
+            assert.deepStrictEqual(state.distributed, Array.from({ length: numPayments }, (_, index) => index));
+        });
+
+        it('Cannot crank a completed tree', async () => {
+            assertDispatchFails(
+                files,
+                ["crank", "--batch-id", batchId, "--recipients", files.recipients, "--state", statePath],
+                "only active trees can be cranked"
+            );
+        });
+    });
+}
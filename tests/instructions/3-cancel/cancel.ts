@@ -1,8 +1,9 @@
 
 import { TestEnvironment } from "../../utils/environment/test-environment";
-import { PublicKey, Keypair, SystemProgram } from '@solana/web3.js';
+import { PublicKey, Keypair, SystemProgram, AccountMeta } from '@solana/web3.js';
 import { ASSOCIATED_TOKEN_PROGRAM_ID, TOKEN_2022_PROGRAM_ID } from "@solana/spl-token";
 import { assert } from 'chai';
+import { getMintRegistryPDA, getTokenVaultAddress, getUserTokenAccountAddress } from "../../utils/pdas";
 
 export interface Cancel {
     authority: Keypair,
@@ -11,6 +12,25 @@ export interface Cancel {
     tokenVault: PublicKey,
     authorityTokenAccount: PublicKey,
     batchId: string,
+    // Mints registered with add_mint, in mint index order, whose vaults are swept to the authority
+    registeredMints?: PublicKey[],
+    // Extra accounts of the mints' transfer hooks, after the registered vault accounts
+    transferHookAccounts?: AccountMeta[],
+}
+
+// Remaining accounts sweeping the vault of each registered mint:
+// [mint, token_vault, authority_token_account, token_program] per mint, in mint index order
+export function getRegisteredVaultAccounts(
+    distributionTreePda: PublicKey,
+    authority: PublicKey,
+    registeredMints: PublicKey[],
+) {
+    return registeredMints.flatMap(mint => [
+        { pubkey: mint, isWritable: true, isSigner: false },
+        { pubkey: getTokenVaultAddress({ mint, distributionTreePDA: distributionTreePda }), isWritable: true, isSigner: false },
+        { pubkey: getUserTokenAccountAddress({ recipient: authority, mint }), isWritable: true, isSigner: false },
+        { pubkey: TOKEN_2022_PROGRAM_ID, isWritable: false, isSigner: false },
+    ]);
 }
 
 export async function cancel(
@@ -27,6 +47,10 @@ export async function cancel(
         mint: cancel.mint,
         tokenVault: cancel.tokenVault,
         authorityTokenAccount: cancel.authorityTokenAccount,
+        mintRegistry: getMintRegistryPDA({
+            distributorProgram: testEnv.program.programId,
+            distributionTree: cancel.distributionTreePda
+        }),
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
         tokenProgram: TOKEN_2022_PROGRAM_ID,
         systemProgram: SystemProgram.programId,
@@ -40,6 +64,14 @@ export async function cancel(
     try {
         await testEnv.program.methods.cancel(cancelParams)
             .accountsPartial(accounts)
+            .remainingAccounts([
+                ...getRegisteredVaultAccounts(
+                    cancel.distributionTreePda,
+                    cancel.authority.publicKey,
+                    cancel.registeredMints ?? []
+                ),
+                ...(cancel.transferHookAccounts ?? []),
+            ])
             .signers([cancel.authority])
             .rpc({ commitment: "processed", skipPreflight: false });
 
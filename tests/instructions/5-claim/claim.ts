@@ -4,8 +4,8 @@ import { ASSOCIATED_TOKEN_PROGRAM_ID, TOKEN_2022_PROGRAM_ID } from "@solana/spl-
 import { BN, web3 } from '@coral-xyz/anchor';
 import { assert } from 'chai';
 import { airdropToMultiple, getSimulationComputeUnits } from "../../utils/solana-helpers";
-import { getAccountByIndex, isBitSet } from "../../utils/merkle-tree";
-import { getUserTokenAccountAddress } from "../../utils/pdas";
+import { getAccountByIndex, InstallmentPlan, isBitSet, LeafSchedule, PayoutSplit } from "../../utils/merkle-tree";
+import { getDelegationPDA, getDisputePDA, getInstallmentRecordPDA, getLeafRedirectPDA, getMintRegistryPDA, getUserTokenAccountAddress } from "../../utils/pdas";
 import { verifyTreeComplete } from "../helpers";
 
 export interface Claim {
@@ -13,12 +13,23 @@ export interface Claim {
     distributionTreePda: PublicKey,
     mint: PublicKey,
     tokenVault: PublicKey,
-    claimantTokenAccount: PublicKey,
+    payeeTokenAccount: PublicKey,
+    payee?: PublicKey,
+    destinationTokenAccount?: PublicKey,
     amount: BN,
     proof: Buffer[],
     batchId: string,
     index: number,
     gatewayToken?: PublicKey,
+    epoch?: number,
+    expectedPayout?: BN,
+    splits?: PayoutSplit[],
+    schedule?: LeafSchedule,
+    installments?: InstallmentPlan,
+    // Index of the registered mint paid by the leaf, `mint` and `tokenVault` must be that mint's
+    mintIndex?: number,
+    // Token accounts of the split destinations, defaults to their ATAs
+    splitTokenAccounts?: PublicKey[],
 }
 
 export async function claim(
@@ -34,23 +45,69 @@ export async function claim(
         batchId: claim.batchId,
         proof: claim.proof.map(buffer => Array.from(buffer)),
         index: new BN(claim.index),
+        epoch: new BN(claim.epoch ?? testEnv.epoch),
+        destinationTokenAccount: claim.destinationTokenAccount ?? null,
+        splits: claim.splits ?? null,
+        schedule: claim.schedule ?? null,
+        installments: claim.installments ?? null,
+        mintIndex: claim.mintIndex ?? null,
     };
 
+    // The payout goes to the destination token account when one is chosen
+    const paidTokenAccount = claim.destinationTokenAccount ?? claim.payeeTokenAccount;
+
     const accounts = {
         claimant: claim.claimant.publicKey,
+        payee: claim.payee ?? claim.claimant.publicKey,
         distributionTree: claim.distributionTreePda,
         mint: claim.mint,
         tokenVault: claim.tokenVault,
-        claimantTokenAccount: claim.claimantTokenAccount,
+        payeeTokenAccount: claim.destinationTokenAccount || claim.splits ? null : claim.payeeTokenAccount,
+        destinationTokenAccount: claim.destinationTokenAccount ?? null,
+        mintRegistry: getMintRegistryPDA({
+            distributorProgram: testEnv.program.programId,
+            distributionTree: claim.distributionTreePda
+        }),
+        leafRedirect: getLeafRedirectPDA({
+            distributorProgram: testEnv.program.programId,
+            distributionTree: claim.distributionTreePda,
+            epoch: claimParams.epoch.toNumber(),
+            index: claimParams.index.toNumber()
+        }),
+        dispute: getDisputePDA({
+            distributorProgram: testEnv.program.programId,
+            distributionTree: claim.distributionTreePda,
+            epoch: claimParams.epoch.toNumber(),
+            index: claimParams.index.toNumber()
+        }),
+        delegation: getDelegationPDA({
+            distributorProgram: testEnv.program.programId,
+            distributionTree: claim.distributionTreePda,
+            epoch: claimParams.epoch.toNumber(),
+            index: claimParams.index.toNumber()
+        }),
+        installmentRecord: claim.installments ? getInstallmentRecordPDA({
+            distributorProgram: testEnv.program.programId,
+            distributionTree: claim.distributionTreePda,
+            epoch: claimParams.epoch.toNumber(),
+            index: claimParams.index.toNumber()
+        }) : null,
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
         tokenProgram: TOKEN_2022_PROGRAM_ID,
         systemProgram: SystemProgram.programId,
         gatewayToken: claim.gatewayToken ?? null,
     }
 
+    // Split token accounts lead the remaining accounts, in the order of the splits
+    const splitTokenAccounts = claim.splitTokenAccounts ?? (claim.splits ?? []).map(({ destination }) =>
+        getUserTokenAccountAddress({ recipient: destination, mint: claim.mint })
+    );
+    const remainingAccounts = splitTokenAccounts.map(pubkey => ({ pubkey, isWritable: true, isSigner: false }));
+
     if (simulate) {
         const ix = await testEnv.program.methods.claim(claimParams)
             .accountsPartial(accounts)
+            .remainingAccounts(remainingAccounts)
             .signers([claim.claimant])
             .instruction();
         const computeUnits = await getSimulationComputeUnits(testEnv.program.provider.connection, [ix], claim.claimant.publicKey, []);
@@ -58,38 +115,47 @@ export async function claim(
     }
 
     const initialVaultBalancePromise = testEnv.program.provider.connection.getTokenAccountBalance(claim.tokenVault).catch(() => ({ value: { amount: '0' } }));
-    const initialClaimantBalancePromise = testEnv.program.provider.connection.getTokenAccountBalance(claim.claimantTokenAccount).catch(() => ({ value: { amount: '0' } }));
-    const [initialClaimantBalance, initialVaultBalance] = await Promise.all([initialClaimantBalancePromise, initialVaultBalancePromise]);
+    const initialPaidBalancePromise = testEnv.program.provider.connection.getTokenAccountBalance(paidTokenAccount).catch(() => ({ value: { amount: '0' } }));
+    const [initialPaidBalance, initialVaultBalance] = await Promise.all([initialPaidBalancePromise, initialVaultBalancePromise]);
 
     const computeUnitIx = web3.ComputeBudgetProgram.setComputeUnitLimit({ units: overRideComputeUnits });
     try {
         const txid = await testEnv.program.methods.claim(claimParams)
             .accountsPartial(accounts)
+            .remainingAccounts(remainingAccounts)
             .preInstructions([computeUnitIx], !!overRideComputeUnits)
             .signers([claim.claimant])
             .rpc({ commitment: "processed", skipPreflight });
 
         // Fetch and assert the DistributionTree account data
         let distributionTreeData = await testEnv.program.account.distributionTree.fetch(claim.distributionTreePda);
-        assert.strictEqual(distributionTreeData.mint.toString(), claim.mint.toString());
-        assert.strictEqual(distributionTreeData.tokenVault.toString(), claim.tokenVault.toString());
+        if (claim.mintIndex === undefined) {
+            assert.strictEqual(distributionTreeData.mint.toString(), claim.mint.toString());
+            assert.strictEqual(distributionTreeData.tokenVault.toString(), claim.tokenVault.toString());
+        }
 
-        assert.isTrue(
-            isBitSet(distributionTreeData.recipientsDistributedBitmap, claimParams.index.toNumber()),
-            `Bitmap not set for claimant at index ${claimParams.index.toString()}`
-        );
+        // Installment leaves are only marked once their last installment has been paid
+        if (!claimParams.installments) {
+            assert.isTrue(
+                isBitSet(distributionTreeData.recipientsDistributedBitmap, claimParams.index.toNumber()),
+                `Bitmap not set for claimant at index ${claimParams.index.toString()}`
+            );
+        }
 
-        // Fetch and assert the claimant token account data
-        let claimantTokenAccountData = await testEnv.program.provider.connection.getTokenAccountBalance(claim.claimantTokenAccount);
-        const claimantBalanceChange = BigInt(claimantTokenAccountData.value.amount) - BigInt(initialClaimantBalance.value.amount);
-        assert.strictEqual(claimantBalanceChange.toString(), claim.amount.toString());
+        // Fetch and assert the paid token account data (split payouts are asserted by the caller)
+        const expectedPayout = claim.expectedPayout ?? claim.amount;
+        if (!claim.splits) {
+            let paidTokenAccountData = await testEnv.program.provider.connection.getTokenAccountBalance(paidTokenAccount);
+            const paidBalanceChange = BigInt(paidTokenAccountData.value.amount) - BigInt(initialPaidBalance.value.amount);
+            assert.strictEqual(paidBalanceChange.toString(), expectedPayout.toString());
+        }
 
         // Fetch and assert the token vault token account data
         let tokenVaultTokenAccountData = await testEnv.program.provider.connection.getTokenAccountBalance(claim.tokenVault);
         const vaultBalanceChange = BigInt(initialVaultBalance.value.amount) - BigInt(tokenVaultTokenAccountData.value.amount);
 
         if (!skipSequenceChecks) {
-            assert.strictEqual(vaultBalanceChange.toString(), claim.amount.toString());
+            assert.strictEqual(vaultBalanceChange.toString(), expectedPayout.toString());
             assert.strictEqual(distributionTreeData.numberDistributed.toNumber(), claim.index + 1);
         }
     } catch (error) {
@@ -132,7 +198,7 @@ export async function createClaimParams({
         distributionTreePda: testEnv.distributionTreePda,
         mint: testEnv.pyUsdMint,
         tokenVault: testEnv.tokenVault,
-        claimantTokenAccount: getUserTokenAccountAddress({
+        payeeTokenAccount: getUserTokenAccountAddress({
             recipient: claimantKeypair.publicKey,
             mint: testEnv.pyUsdMint
         }),
@@ -179,7 +245,7 @@ export async function claimAllPayments({
             distributionTreePda: testEnv.distributionTreePda,
             mint: testEnv.pyUsdMint,
             tokenVault: testEnv.tokenVault,
-            claimantTokenAccount: getUserTokenAccountAddress({
+            payeeTokenAccount: getUserTokenAccountAddress({
                 recipient: claimantKey.publicKey,
                 mint: testEnv.pyUsdMint
             }),
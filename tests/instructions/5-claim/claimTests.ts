@@ -101,7 +101,7 @@ export async function claimTests(testEnv: TestEnvironment) {
             const incorrectParams: Claim = {
                 ...correctParams,
                 claimant: wrongRecipient,
-                claimantTokenAccount: wrongDestination,
+                payeeTokenAccount: wrongDestination,
                 index: unClaimedIndex
             };
             await assertInstructionWillFail({
@@ -8,6 +8,8 @@ import { gatewayAuthorizeAccount } from "../../utils/civic/authorize";
 import { claim, Claim } from "../5-claim/claim";
 import { airdropToMultiple } from "../../utils/solana-helpers";
 import { createNewDistributionTree } from "../1-initialize/initialize";
+import { delegateClaim } from "../19-delegation/delegateClaim";
+import { getUserTokenAccountAddress } from "../../utils/pdas";
 
 
 /**
@@ -20,7 +22,9 @@ import { createNewDistributionTree } from "../1-initialize/initialize";
  *  2. Authorizes the recipient with a gateway token and distributes to them
  *  3. Fails to claim a recipient without a gateway token
  *  4. Authorizes the recipient with a gateway token and claims them
- *  5. Authorizes and distributes remainder of participants
+ *  5. Fails a delegated claim when only the delegate holds a gateway token
+ *  6. Claims a delegated leaf with the recipient's gateway token
+ *  7. Authorizes and distributes remainder of participants
  * 
  */
 export async function gatekeeperTests(testEnv: TestEnvironment) {
@@ -28,8 +32,11 @@ export async function gatekeeperTests(testEnv: TestEnvironment) {
     let claimParams: Claim;
     let distributeIndex = 0;
     let claimIndex = 1;
+    let delegateIndex = 2;
     let distributionRecipient: web3.PublicKey;
     let claimantKeypair: web3.Keypair;
+    let delegatedClaimParams: Claim;
+    const guardian = web3.Keypair.generate();
 
     describe('Restrict access to authorized users (Civic Gatekeeper)', async () => {
         before('Initializes a new Distribution Tree with Gatekeeper Network', async () => {
@@ -79,7 +86,7 @@ export async function gatekeeperTests(testEnv: TestEnvironment) {
                 distributionTreePda: testEnv.distributionTreePda,
                 mint: testEnv.pyUsdMint,
                 tokenVault: testEnv.tokenVault,
-                claimantTokenAccount: getAssociatedTokenAddressSync(
+                payeeTokenAccount: getAssociatedTokenAddressSync(
                     testEnv.pyUsdMint,
                     claimantKeypair.publicKey,
                     false,
@@ -119,8 +126,50 @@ export async function gatekeeperTests(testEnv: TestEnvironment) {
             claimParams.gatewayToken = gatewayToken.publicKey;
             await claim(testEnv, claimParams);
         });
+        it('Cannot claim a delegated leaf with only the delegate authorized', async () => {
+            const paymentInfo = getAccountByIndex(testEnv.merkleDistributorInfo, delegateIndex)!;
+            const recipient = paymentInfo.keypair.publicKey;
+            await airdropToMultiple([recipient, guardian.publicKey], testEnv.provider.connection, web3.LAMPORTS_PER_SOL);
+            const proof = testEnv.balanceTree.getProof(delegateIndex, recipient, paymentInfo.amount);
+            await delegateClaim(testEnv, {
+                signer: paymentInfo.keypair,
+                distributionTreePda: testEnv.distributionTreePda,
+                batchId: testEnv.distributionUniqueId,
+                index: delegateIndex,
+                recipient,
+                amount: paymentInfo.amount,
+                proof,
+                delegate: guardian.publicKey,
+            });
+
+            const guardianGatewayToken = await gatewayAuthorizeAccount({ testEnv, account: guardian.publicKey });
+            delegatedClaimParams = {
+                claimant: guardian,
+                payee: recipient,
+                distributionTreePda: testEnv.distributionTreePda,
+                mint: testEnv.pyUsdMint,
+                tokenVault: testEnv.tokenVault,
+                payeeTokenAccount: getUserTokenAccountAddress({ recipient, mint: testEnv.pyUsdMint }),
+                amount: new BN(paymentInfo.amount),
+                proof,
+                batchId: testEnv.distributionUniqueId,
+                index: delegateIndex,
+                gatewayToken: guardianGatewayToken.publicKey
+            };
+            await assertInstructionWillFail({
+                testEnv,
+                params: delegatedClaimParams,
+                executeInstruction: claim,
+                expectedAnchorError: "InvalidGatewayToken"
+            });
+        });
+        it('Can claim a delegated leaf with the recipient\'s Gateway Token', async () => {
+            const gatewayToken = await gatewayAuthorizeAccount({ testEnv, account: delegatedClaimParams.payee! });
+            delegatedClaimParams.gatewayToken = gatewayToken.publicKey;
+            await claim(testEnv, delegatedClaimParams);
+        });
         it('Can authorize and distribute tokens to remaining accounts', async () => {
-            let processedIndices = [distributeIndex, claimIndex];
+            let processedIndices = [distributeIndex, claimIndex, delegateIndex];
             const remainingPayments = testEnv.merkleDistributorInfo.payments.filter((_, paymentIndex) => !processedIndices.includes(paymentIndex));
             const remainingPaymentsWithAuth = await Promise.all(remainingPayments.map(async (paymentInfo) => {
                 const recipient = paymentInfo.keypair.publicKey;
@@ -2,6 +2,9 @@ import { TestEnvironment } from "../../utils/environment/test-environment";
 import { web3 } from "@coral-xyz/anchor";
 import { calculateAccountSize } from "../helpers";
 import { assert } from "chai";
+import { NATIVE_MINT, TOKEN_2022_PROGRAM_ID } from "@solana/spl-token";
+import { getMintRegistryPDA, getSolVaultPDA, getTokenVaultAddress, getUserTokenAccountAddress } from "../../utils/pdas";
+import { getRegisteredVaultAccounts } from "../3-cancel/cancel";
 
 export interface Reclaim {
     overRideAuthority?: web3.Keypair
@@ -9,11 +12,15 @@ export interface Reclaim {
 
 export interface Close {
     acknowledgeIrreversible: boolean,
-    overRideAuthority?: web3.Keypair
+    overRideAuthority?: web3.Keypair,
+    // Mints registered with add_mint, in mint index order, whose vaults are swept to the authority
+    registeredMints?: web3.PublicKey[]
 }
 
 export async function reclaim(testEnv: TestEnvironment, { overRideAuthority }: Reclaim) {
     try {
+        const registeredVaults = registeredMints.map(mint => getTokenVaultAddress({ mint, distributionTreePDA: testEnv.distributionTreePda }));
+        const registeredVaultInfos = await testEnv.program.provider.connection.getMultipleAccountsInfo(registeredVaults);
         let initialAccountInfo = await testEnv.program.provider.connection.getAccountInfo(testEnv.distributionTreePda);
         let initialAuthorityInfo = await testEnv.program.provider.connection.getAccountInfo(testEnv.authority.publicKey);
         if (!initialAccountInfo || !initialAuthorityInfo) {
@@ -64,23 +71,51 @@ export async function reclaim(testEnv: TestEnvironment, { overRideAuthority }: R
 }
 
 
-export async function close(testEnv: TestEnvironment, { acknowledgeIrreversible, overRideAuthority }: Close) {
+export async function close(testEnv: TestEnvironment, { acknowledgeIrreversible, overRideAuthority, registeredMints = [] }: Close) {
     try {
+        const mintRegistry = getMintRegistryPDA({
+            distributorProgram: testEnv.program.programId,
+            distributionTree: testEnv.distributionTreePda
+        });
+        const solVault = getSolVaultPDA({
+            distributorProgram: testEnv.program.programId,
+            distributionTree: testEnv.distributionTreePda
+        });
+        const authorityKey = overRideAuthority ? overRideAuthority : testEnv.authority;
+        const distributionTreeData = await testEnv.program.account.distributionTree.fetch(testEnv.distributionTreePda);
+        // Token trees sweep what is left in the token vault to the authority's token account and close the vault
+        const isNative = distributionTreeData.mint.equals(NATIVE_MINT);
+        const authorityTokenAccount = getUserTokenAccountAddress({ recipient: authorityKey.publicKey, mint: distributionTreeData.mint });
+        const [mintRegistryInfo, solVaultInfo, tokenVaultInfo] = await Promise.all([
+            testEnv.program.provider.connection.getAccountInfo(mintRegistry),
+            testEnv.program.provider.connection.getAccountInfo(solVault),
+            isNative ? null : testEnv.program.provider.connection.getAccountInfo(distributionTreeData.tokenVault),
+        ]);
+        const [tokenVaultBalance, initialAuthorityTokenBalance] = await Promise.all([
+            tokenVaultInfo ? testEnv.program.provider.connection.getTokenAccountBalance(distributionTreeData.tokenVault) : null,
+            isNative ? null : testEnv.program.provider.connection.getTokenAccountBalance(authorityTokenAccount).catch(() => ({ value: { amount: '0' } })),
+        ]);
         let initialAccountInfo = await testEnv.program.provider.connection.getAccountInfo(testEnv.distributionTreePda);
         let initialAuthorityInfo = await testEnv.program.provider.connection.getAccountInfo(testEnv.authority.publicKey);
         if (!initialAccountInfo || !initialAuthorityInfo) {
             throw new Error("Initial account info not found");
         }
         const initialRent = await testEnv.program.provider.connection.getMinimumBalanceForRentExemption(initialAccountInfo.data.length);
-        const authorityKey = overRideAuthority ? overRideAuthority : testEnv.authority;
 
         await testEnv.program.methods
             .close({ batchId: testEnv.distributionUniqueId, acknowledgeIrreversible })
-            .accounts({
+            .accountsPartial({
                 authority: authorityKey.publicKey,
                 distributionTree: testEnv.distributionTreePda,
+                mintRegistry,
+                mint: isNative ? null : distributionTreeData.mint,
+                tokenVault: isNative ? null : distributionTreeData.tokenVault,
+                authorityTokenAccount: isNative ? null : authorityTokenAccount,
+                tokenProgram: isNative ? null : TOKEN_2022_PROGRAM_ID,
+                solVault,
                 systemProgram: web3.SystemProgram.programId,
             })
+            .remainingAccounts(getRegisteredVaultAccounts(testEnv.distributionTreePda, authorityKey.publicKey, registeredMints))
             .signers([authorityKey])
             .rpc();
 
@@ -95,7 +130,25 @@ export async function close(testEnv: TestEnvironment, { acknowledgeIrreversible,
             throw new Error("Updated account info not found");
         }
 
-        const rentRecovered = initialRent - 0;
+        // The token vault is swept to the authority's token account
+        if (tokenVaultBalance && initialAuthorityTokenBalance) {
+            const authorityTokenBalance = await testEnv.program.provider.connection.getTokenAccountBalance(authorityTokenAccount);
+            const sweptAmount = BigInt(authorityTokenBalance.value.amount) - BigInt(initialAuthorityTokenBalance.value.amount);
+            assert.strictEqual(sweptAmount.toString(), tokenVaultBalance.value.amount, "Token vault should be swept to the authority");
+            const closedTokenVault = await testEnv.program.provider.connection.getAccountInfo(distributionTreeData.tokenVault);
+            assert.isNull(closedTokenVault, "Token vault should be closed");
+        }
+
+        // The vaults of registered mints are closed
+        const closedRegisteredVaults = await testEnv.program.provider.connection.getMultipleAccountsInfo(registeredVaults);
+        assert.isTrue(closedRegisteredVaults.every(info => info === null), "Registered vaults should be closed");
+
+        // The token vaults and the MintRegistry, if any, are closed along with the tree, and the SOL vault of native trees is swept
+        const rentRecovered = initialRent
+            + (tokenVaultInfo?.lamports ?? 0)
+            + registeredVaultInfos.reduce((lamports, info) => lamports + (info?.lamports ?? 0), 0)
+            + (mintRegistryInfo?.lamports ?? 0)
+            + (solVaultInfo?.lamports ?? 0);
         const expectedLamports = initialAuthorityInfo.lamports + rentRecovered;
         assert.strictEqual(expectedLamports, updatedAuthorityInfo.lamports, "Auth should gain rent less transaction fee");
 
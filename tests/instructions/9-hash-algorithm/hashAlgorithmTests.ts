@@ -0,0 +1,184 @@
+import { TestEnvironment } from "../../utils/environment/test-environment";
+import { BN, web3 } from "@coral-xyz/anchor";
+import { createAssociatedTokenAccountIdempotent, mintTo, TOKEN_2022_PROGRAM_ID } from "@solana/spl-token";
+import { createNewDistributionTree } from "../1-initialize/initialize";
+import { createDistributeParams, distribute, distributeAllPayments } from "../2-distribute/distribute";
+import { claim } from "../5-claim/claim";
+import { addMint } from "../24-multi-mint/addMint";
+import { assertInstructionWillFail } from "../helpers";
+import {
+    getAccountByIndex,
+    HashAlgorithm,
+    InstallmentPlan,
+    isBitSet,
+    LeafSchedule,
+    PaymentTree,
+    PayoutSplit,
+    toAnchorHashAlgorithm,
+} from "../../utils/merkle-tree";
+import { airdropToMultiple, makeTokenMint } from "../../utils/solana-helpers";
+import { getTokenVaultAddress, getUserTokenAccountAddress } from "../../utils/pdas";
+import { BASE_PAYMENT_AMOUNT } from "../../utils/constants";
+import { assert } from "chai";
+
+/**
+ * HASH ALGORITHM TESTS
+ * 
+ * @param testEnv 
+ * 
+ * For each supported hash algorithm (Keccak, Sha256, Poseidon) this test suite:
+ *  1. Initializes a new Distribution Tree with a root built by the off-chain reference builder
+ *  2. Verifies a proof built with a different algorithm is rejected
+ *  3. Verifies every off-chain proof is accepted on-chain by distributing to all recipients
+ *
+ * It then initializes a Poseidon Distribution Tree whose first leaf has all four extensions
+ * (splits, a schedule, installments and a mint index) and:
+ *  4. Claims the leaf, whose extensions are folded to stay within the Poseidon syscall's 12 inputs
+ */
+export async function hashAlgorithmTests(testEnv: TestEnvironment) {
+    const totalNumberRecipients = 5;
+    const hashAlgorithms: HashAlgorithm[] = ["keccak", "sha256", "poseidon"];
+
+    hashAlgorithms.forEach((hashAlgorithm) => {
+        describe(`Distribution tree hashed with ${hashAlgorithm}`, () => {
+            before('Initializes a new distribution tree', async () => {
+                await createNewDistributionTree({
+                    testEnv,
+                    numPayments: totalNumberRecipients,
+                    hashAlgorithm
+                });
+            });
+
+            it('Stores the off-chain root and hash algorithm', async () => {
+                const distributionTreeData = await testEnv.program.account.distributionTree.fetch(testEnv.distributionTreePda);
+                const root = testEnv.balanceTree.getRoot();
+                assert.isTrue(Buffer.from(distributionTreeData.merkleRoot).equals(root));
+                assert.deepStrictEqual(distributionTreeData.hashAlgorithm, toAnchorHashAlgorithm(hashAlgorithm));
+                testEnv.merkleDistributorInfo.payments.forEach(({ index, keypair, amount, proof }) => {
+                    assert.isTrue(PaymentTree.verifyProof(index, keypair.publicKey, amount, proof, root, { hashAlgorithm }));
+                });
+            });
+
+            it('Cannot distribute with a proof built by a different hash algorithm', async () => {
+                const otherAlgorithm = hashAlgorithms.find(algorithm => algorithm !== hashAlgorithm)!;
+                const otherTree = new PaymentTree(
+                    testEnv.merkleDistributorInfo.payments.map(({ keypair, amount }) => ({ account: keypair, amount })),
+                    { hashAlgorithm: otherAlgorithm }
+                );
+                const correctParams = await createDistributeParams({ testEnv, index: 0 });
+                await assertInstructionWillFail({
+                    testEnv,
+                    params: {
+                        ...correctParams,
+                        proof: otherTree.getProof(0, correctParams.recipient, correctParams.amount)
+                    },
+                    executeInstruction: distribute,
+                    expectedAnchorError: "InvalidProof"
+                });
+            });
+
+            it('Can distribute to all recipients using off-chain proofs', async () => {
+                await distributeAllPayments({ testEnv, totalNumberRecipients });
+            });
+        });
+    });
+
+    describe('Poseidon leaf with every extension', () => {
+        const extendedIndex = 0;
+        const loyaltyMintIndex = 1;
+        const wallets = Array.from({ length: 2 }, () => web3.Keypair.generate().publicKey);
+        const splits: PayoutSplit[] = [
+            { destination: wallets[0], bps: 5_000 },
+            { destination: wallets[1], bps: 5_000 },
+        ];
+        const now = Math.floor(Date.now() / 1000);
+        const schedule: LeafSchedule = { unlockTs: new BN(now - 1_000), expiryTs: new BN(now + 24 * 60 * 60) };
+        // Every installment of the leaf (BASE_PAYMENT_AMOUNT) is due by the time the tree has started
+        const installments: InstallmentPlan = {
+            amountPerInstallment: new BN(BASE_PAYMENT_AMOUNT / 4),
+            count: 4,
+            interval: new BN(100),
+        };
+        let loyaltyMint: web3.PublicKey;
+
+        before('Initializes a new Poseidon distribution tree and registers a loyalty mint', async () => {
+            await createNewDistributionTree({
+                testEnv,
+                startOffset: -1000,
+                allowClaims: true,
+                hashAlgorithm: "poseidon",
+                leafExtensions: {
+                    [extendedIndex]: { splits, schedule, installments, mintIndex: loyaltyMintIndex },
+                },
+            });
+
+            loyaltyMint = await makeTokenMint({
+                connection: testEnv.provider.connection,
+                mintAuthority: testEnv.pyUsdMintAuthorityKeypair,
+                name: "Loyalty Points",
+                symbol: "LOYAL",
+                decimals: 6,
+                uri: "",
+            });
+            const [authorityLoyaltyAccount] = await Promise.all([testEnv.authority.publicKey, ...wallets].map(owner =>
+                createAssociatedTokenAccountIdempotent(
+                    testEnv.provider.connection,
+                    testEnv.authority,
+                    loyaltyMint,
+                    owner,
+                    { commitment: 'processed' },
+                    TOKEN_2022_PROGRAM_ID
+                )
+            ));
+            await mintTo(
+                testEnv.provider.connection,
+                testEnv.authority,
+                loyaltyMint,
+                authorityLoyaltyAccount,
+                testEnv.pyUsdMintAuthorityKeypair,
+                BASE_PAYMENT_AMOUNT,
+                [],
+                { commitment: 'processed' },
+                TOKEN_2022_PROGRAM_ID
+            );
+            await addMint(testEnv, {
+                authority: testEnv.authority,
+                distributionTreePda: testEnv.distributionTreePda,
+                mint: loyaltyMint,
+                batchId: testEnv.distributionUniqueId,
+                transferToVaultAmount: new BN(BASE_PAYMENT_AMOUNT),
+            });
+        });
+
+        it('Can claim a leaf with splits, a schedule, installments and a mint index', async () => {
+            const paymentInfo = getAccountByIndex(testEnv.merkleDistributorInfo, extendedIndex);
+            if (!paymentInfo) {
+                throw new Error('No recipient found');
+            }
+            await airdropToMultiple([paymentInfo.keypair.publicKey], testEnv.provider.connection, web3.LAMPORTS_PER_SOL);
+
+            await claim(testEnv, {
+                claimant: paymentInfo.keypair,
+                distributionTreePda: testEnv.distributionTreePda,
+                mint: loyaltyMint,
+                tokenVault: getTokenVaultAddress({ mint: loyaltyMint, distributionTreePDA: testEnv.distributionTreePda }),
+                payeeTokenAccount: getUserTokenAccountAddress({ recipient: paymentInfo.keypair.publicKey, mint: loyaltyMint }),
+                amount: paymentInfo.amount,
+                proof: testEnv.balanceTree.getProof(extendedIndex, paymentInfo.keypair.publicKey, paymentInfo.amount, paymentInfo),
+                batchId: testEnv.distributionUniqueId,
+                index: extendedIndex,
+                splits,
+                schedule,
+                installments,
+                mintIndex: loyaltyMintIndex,
+            }, 400_000);
+
+            const distributionTreeData = await testEnv.program.account.distributionTree.fetch(testEnv.distributionTreePda);
+            assert.isTrue(isBitSet(distributionTreeData.recipientsDistributedBitmap, extendedIndex), "Leaf should be fully paid");
+            const splitBalances = await Promise.all(wallets.map(wallet =>
+                testEnv.provider.connection.getTokenAccountBalance(getUserTokenAccountAddress({ recipient: wallet, mint: loyaltyMint }))
+            ));
+            splitBalances.forEach(({ value }) => assert.strictEqual(value.amount, (BASE_PAYMENT_AMOUNT / 2).toString()));
+        });
+    });
+}
@@ -63,7 +63,7 @@ export function calculateFutureBitmapSize(currentSize: number, numberRecipients:
     return Math.min(currentSize + BITMAP_ARRAY_STEP, Math.ceil(numberRecipients / 64));
 }
 
-export function calculateAccountSize(bitmapSize: number, gatekeeperNetwork = false) {
+export function calculateAccountSize(bitmapSize: number, gatekeeperNetwork = false, totalShares = false, openClaimAmount = false, declineDestination = false) {
     return 8 // discriminator
         + 1 // bump
         + 8 // version
@@ -81,7 +81,19 @@ export function calculateAccountSize(bitmapSize: number, gatekeeperNetwork = fal
         + 4 // recipients_distributed_bitmap length
         + (bitmapSize * 8)
         + 1 // Option for gatekeeper network
-        + (gatekeeperNetwork ? 32 : 0);
+        + (gatekeeperNetwork ? 32 : 0)
+        + 1 // hash_algorithm (enum)
+        + 1 // domain_separated
+        + 8 // epoch
+        + 1 // Option for total shares
+        + (totalShares ? 8 : 0)
+        + 8 // distributed_shares
+        + 1 // Option for open claim amount
+        + (openClaimAmount ? 8 : 0)
+        + 8 // number_revoked
+        + 8 // number_declined
+        + 1 // Option for decline destination
+        + (declineDestination ? 32 : 0);
 }
 
 export async function verifyTreeComplete(testEnv: TestEnvironment, totalNumberRecipients: number) {
@@ -0,0 +1,36 @@
+import { utils } from '@coral-xyz/anchor';
+import { Keypair, PublicKey } from '@solana/web3.js';
+
+export interface ClaimLinkDetails {
+    authority: PublicKey,
+    batchId: string,
+    index: number,
+    linkKeypair: Keypair,
+}
+
+/**
+ * Encodes a claim link that an authority can email to a recipient without a wallet.
+ * The leaf commits to the public key of the ephemeral link keypair, the link carries its 32-byte seed (the secret).
+ * The secret is kept in the URL fragment so it is never sent to the web server.
+ */
+export function createClaimLink(baseUrl: string, { authority, batchId, index, linkKeypair }: ClaimLinkDetails): string {
+    const secret = utils.bytes.bs58.encode(linkKeypair.secretKey.slice(0, 32));
+    return `${baseUrl}?authority=${authority.toBase58()}&batch=${encodeURIComponent(batchId)}&index=${index}#${secret}`;
+}
+
+export function parseClaimLink(link: string): ClaimLinkDetails {
+    const url = new URL(link);
+    const authority = url.searchParams.get('authority');
+    const batchId = url.searchParams.get('batch');
+    const index = url.searchParams.get('index');
+    const secret = url.hash.slice(1);
+    if (!authority || !batchId || !index || !secret) {
+        throw new Error('Invalid claim link');
+    }
+    return {
+        authority: new PublicKey(authority),
+        batchId,
+        index: Number(index),
+        linkKeypair: Keypair.fromSeed(utils.bytes.bs58.decode(secret)),
+    };
+}
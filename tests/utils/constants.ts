@@ -15,12 +15,18 @@ export const PY_USD_AUTH_SECRET = [247,10,235,35,153,158,192,113,238,61,182,91,1
 // FEESqUnJ5LEZgNpChDAbq2bDf5na3HTECAZhLUBMof3z
 export const FEES_WALLET_SECRET = [218,48,156,55,207,193,209,68,252,127,28,109,254,98,134,96,252,30,98,237,101,125,92,168,121,10,17,119,131,7,74,30,211,103,94,204,7,1,29,236,58,180,203,220,150,190,83,105,135,71,4,166,207,12,211,152,42,88,95,44,146,241,40,245];
 
+// G1FiNpdp5p9JjWVNAe4BDYWZcwep1LCTqJbQZNx82hW2
+// Authority of the v1 Distribution Tree loaded from tests/genesis/legacy-distribution-tree-v1.json
+export const LEGACY_AUTHORITY_SECRET = [29,92,231,52,27,186,227,111,88,104,245,241,23,188,81,207,215,246,40,92,69,174,243,167,206,22,172,220,229,255,22,132,222,239,240,188,35,161,35,163,153,44,77,8,172,107,50,203,242,246,149,188,77,212,138,56,226,45,251,41,147,196,17,107];
+export const LEGACY_BATCH_ID = "legacy-v1-tree";
+
 export const INITIAL_SOL_BALANCE = 100;
 export const INITIAL_TOKEN_BALANCE = 10_000_000_000_000_000; // 10 Billion tokens with 6 decimals
 export const NUM_SAMPLE_BALANCES = 10;
 export const BASE_PAYMENT_AMOUNT = 1_000_000; // $1 with 6 decimals
 export const MAX_COMPUTE_UNITS = 1_400_000;
 export const BITMAP_ARRAY_STEP = 1000;
+export const CURRENT_VERSION = 2;
 
 export const OFFSET_24_HOURS = 24 * 60 * 60;
 
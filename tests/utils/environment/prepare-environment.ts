@@ -53,6 +53,8 @@ export async function initEnviroment({
                 decimals: 6,
                 uri: "https://token-metadata.paxos.com/pyusd_metadata/prod/solana/pyusd_metadata.json",
                 mint: web3.Keypair.fromSecretKey(new Uint8Array(PY_USD_SECRET)),
+                // Like mainnet PYUSD, the test mint has a freeze authority
+                freezeAuthority: testEnv.pyUsdMintAuthorityKeypair.publicKey,
             });
         }
 
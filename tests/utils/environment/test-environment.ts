@@ -1,6 +1,6 @@
 import * as anchor from '@coral-xyz/anchor';
 import { CashDispatch } from "../../../target/types/cash_dispatch";
-import { PaymentTree, MerkleDistributorInfo, PaymentsImport, parsePaymentMap } from '../merkle-tree';
+import { PaymentTree, MerkleDistributorInfo, PaymentsImport, parsePaymentMap, HashAlgorithm, LeafExtensions } from '../merkle-tree';
 import { PublicKey, Keypair } from '@solana/web3.js';
 import { BASE_PAYMENT_AMOUNT, FEES_WALLET_SECRET, NUM_SAMPLE_BALANCES, PY_USD_AUTH_SECRET, PY_USD_SECRET } from '../constants';
 import { getDistributionTreePDA, getTokenVaultAddress } from '../pdas';
@@ -26,6 +26,8 @@ export class TestEnvironment {
     tokenVault!: PublicKey;
     distributionStartTs!: number;
     distributionUniqueId!: string;
+    epoch: number = 0;
+    hashAlgorithm: HashAlgorithm = "keccak";
 
     civicConfig!: CivicConfig;
 
@@ -45,25 +47,20 @@ export class TestEnvironment {
     async newTree(params: {
         numPayments?: number,
         startOffset?: number,
+        hashAlgorithm?: HashAlgorithm,
+        // Leaf index => splits, schedule and/or installment plan, for extended leaves
+        leafExtensions?: Record<number, LeafExtensions>,
     } = {}): Promise<void> {
         const {
             numPayments = NUM_SAMPLE_BALANCES,
             startOffset = -1000,
+            hashAlgorithm = "keccak",
+            leafExtensions = {},
         } = params;
 
-        let samplePayments: PaymentsImport = Array.from({ length: numPayments }, (_, i) => ({
-            address: Keypair.generate(),
-            earnings: ((i + 1) * BASE_PAYMENT_AMOUNT).toString(),
-        }));
-
-        this.merkleDistributorInfo = parsePaymentMap(samplePayments);
-
-        this.balanceTree = new PaymentTree(
-            samplePayments.map(({ address, earnings }, index) => ({
-                account: address,
-                amount: new anchor.BN(earnings),
-            }))
-        );
+        this.epoch = 0;
+        this.hashAlgorithm = hashAlgorithm;
+        this.newPayments(numPayments, leafExtensions);
 
         const currentDate = new Date();
         this.distributionStartTs = Math.floor(currentDate.getTime() / 1000) + startOffset;
@@ -81,4 +78,38 @@ export class TestEnvironment {
             distributionTreePDA: this.distributionTreePda
         });
     }
+
+    // Generates a new cohort of payments for the next epoch of the current tree
+    async newEpoch(params: {
+        numPayments?: number,
+        startOffset?: number,
+    } = {}): Promise<void> {
+        const {
+            numPayments = NUM_SAMPLE_BALANCES,
+            startOffset = -1000,
+        } = params;
+
+        this.epoch += 1;
+        this.newPayments(numPayments);
+        this.distributionStartTs = Math.floor(Date.now() / 1000) + startOffset;
+    }
+
+    private newPayments(numPayments: number, leafExtensions: Record<number, LeafExtensions> = {}) {
+        let samplePayments: PaymentsImport = Array.from({ length: numPayments }, (_, i) => ({
+            address: Keypair.generate(),
+            earnings: ((i + 1) * BASE_PAYMENT_AMOUNT).toString(),
+            ...leafExtensions[i],
+        }));
+
+        this.merkleDistributorInfo = parsePaymentMap(samplePayments, { hashAlgorithm: this.hashAlgorithm });
+
+        this.balanceTree = new PaymentTree(
+            samplePayments.map(({ address, earnings, ...extensions }, index) => ({
+                account: address,
+                amount: new anchor.BN(earnings),
+                ...extensions,
+            })),
+            { hashAlgorithm: this.hashAlgorithm }
+        );
+    }
 }
\ No newline at end of file
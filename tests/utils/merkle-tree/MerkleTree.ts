@@ -1,13 +1,21 @@
-import { keccak_256 } from "js-sha3";
-import { MerkleDistributorInfo, PaymentInfo } from "./types";
+import { MerkleDistributorInfo, PaymentInfo, TreeOptions } from "./types";
+import { hashv, HashAlgorithm } from "./hash";
 import { BN } from "@coral-xyz/anchor";
 
+// Domain separation prefixes (DistributionTree version >= 2)
+export const LEAF_HASH_PREFIX = Buffer.from([0x00]);
+export const NODE_HASH_PREFIX = Buffer.from([0x01]);
+
 export class MerkleTree {
     private readonly elements: Buffer[];
     private readonly bufferElementPositionIndex: Map<string, number>;
     private readonly layers: Buffer[][];
+    private readonly domainSeparated: boolean;
+    private readonly hashAlgorithm: HashAlgorithm;
 
-    constructor(elements: Buffer[]) {
+    constructor(elements: Buffer[], { domainSeparated = true, hashAlgorithm = "keccak" }: TreeOptions = {}) {
+        this.domainSeparated = domainSeparated;
+        this.hashAlgorithm = hashAlgorithm;
         this.elements = [...elements].sort(Buffer.compare);
         this.elements = MerkleTree.bufDedup(this.elements);
 
@@ -35,23 +43,30 @@ export class MerkleTree {
     getNextLayer(elements: Buffer[]): Buffer[] {
         return elements.reduce<Buffer[]>((layer, el, idx, arr) => {
             if (idx % 2 === 0) {
-                layer.push(MerkleTree.combinedHash(el, arr[idx + 1]));
+                layer.push(MerkleTree.combinedHash(el, arr[idx + 1], {
+                    domainSeparated: this.domainSeparated,
+                    hashAlgorithm: this.hashAlgorithm
+                }));
             }
             return layer;
         }, []);
     }
 
-    static combinedHash(first: Buffer, second?: Buffer): Buffer {
+    static combinedHash(
+        first: Buffer,
+        second?: Buffer,
+        { domainSeparated = true, hashAlgorithm = "keccak" }: TreeOptions = {}
+    ): Buffer {
         if (!first) {
             return second!;
         }
         if (!second) {
             return first;
         }
-        return Buffer.from(
-            keccak_256(Buffer.concat([first, second].sort(Buffer.compare))),
-            "hex"
-        );
+        return hashv(hashAlgorithm, [
+            domainSeparated ? NODE_HASH_PREFIX : Buffer.alloc(0),
+            ...[first, second].sort(Buffer.compare)
+        ]);
     }
 
     getRoot(): Buffer {
@@ -1,17 +1,21 @@
 import { PublicKey } from "@solana/web3.js";
-import { keccak_256 } from "js-sha3";
 import { BN } from "@coral-xyz/anchor";     
-import { MerkleTree } from "./MerkleTree";
-import { Payments } from "./types";
+import { MerkleTree, LEAF_HASH_PREFIX } from "./MerkleTree";
+import { InstallmentPlan, LeafExtensions, LeafSchedule, Payments, PayoutSplit, TreeOptions } from "./types";
+import { hashv } from "./hash";
 
 export class PaymentTree {
     private readonly tree: MerkleTree;
+    private readonly options: TreeOptions;
 
-    constructor(payments: Payments) {
+    // Set `domainSeparated` to false to build a legacy (v1) tree
+    constructor(payments: Payments, options: TreeOptions = {}) {
+        this.options = options;
         this.tree = new MerkleTree(
-            payments.map(({ account, amount }, index) =>
-                PaymentTree.toNode(index, account.publicKey, amount)
-            )
+            payments.map(({ account, amount, ...extensions }, index) =>
+                PaymentTree.toLeaf(index, account.publicKey, amount, extensions, options)
+            ),
+            options
         );
     }
 
@@ -20,37 +24,150 @@ export class PaymentTree {
         account: PublicKey,
         amount: BN,
         proof: Buffer[],
-        root: Buffer
+        root: Buffer,
+        options: TreeOptions = {}
     ): boolean {
-        let pair = PaymentTree.toNode(index, account, amount);
+        let pair = PaymentTree.toNode(index, account, amount, options);
         for (const item of proof) {
-            pair = MerkleTree.combinedHash(pair, item);
+            pair = MerkleTree.combinedHash(pair, item, options);
         }
         return pair.equals(root);
     }
 
-    static toNode(index: number, account: PublicKey, amount: BN): Buffer {
-        const buf = Buffer.concat([
+    static toNode(
+        index: number,
+        account: PublicKey,
+        amount: BN,
+        { domainSeparated = true, hashAlgorithm = "keccak" }: TreeOptions = {}
+    ): Buffer {
+        const prefix = domainSeparated ? LEAF_HASH_PREFIX : Buffer.alloc(0);
+        if (hashAlgorithm === "poseidon") {
+            // Pubkey is split in two so each input is below the BN254 modulus
+            const accountBytes = account.toBuffer();
+            return hashv(hashAlgorithm, [
+                prefix,
+                Buffer.from(new BN(index).toArray("be", 8)),
+                accountBytes.subarray(0, 16),
+                accountBytes.subarray(16),
+                Buffer.from(amount.toArray("be", 8)),
+            ]);
+        }
+        return hashv(hashAlgorithm, [
+            prefix,
             Buffer.from(new BN(index).toArray("le", 8)),
             account.toBuffer(),
             Buffer.from(amount.toArray("le", 8)),
         ]);
-        return Buffer.from(keccak_256(buf), "hex");
+    }
+
+    // Standard leaf, or an extended leaf when the payment has splits, a schedule, installments or a mint index
+    static toLeaf(
+        index: number,
+        account: PublicKey,
+        amount: BN,
+        { splits, schedule, installments, mintIndex }: LeafExtensions,
+        options: TreeOptions = {}
+    ): Buffer {
+        const hashAlgorithm = options.hashAlgorithm ?? "keccak";
+        const extensions: Buffer[] = [];
+        if (splits) {
+            extensions.push(PaymentTree.hashSplits(splits, hashAlgorithm));
+        }
+        if (schedule) {
+            extensions.push(PaymentTree.hashSchedule(schedule, hashAlgorithm));
+        }
+        if (installments) {
+            extensions.push(PaymentTree.hashInstallments(installments, hashAlgorithm));
+        }
+        if (mintIndex !== undefined) {
+            extensions.push(PaymentTree.hashMintIndex(mintIndex, hashAlgorithm));
+        }
+        return extensions.length
+            ? PaymentTree.toExtendedNode(index, account, amount, extensions, options)
+            : PaymentTree.toNode(index, account, amount, options);
+    }
+
+    // An extended leaf appends the hash of each extension to the leaf pre-image, in order:
+    // the splits hash, the schedule hash, the installment plan hash, then the mint index hash
+    // Poseidon takes at most 12 inputs on-chain, so it appends the hash of the extension hashes instead
+    static toExtendedNode(
+        index: number,
+        account: PublicKey,
+        amount: BN,
+        extensions: Buffer[],
+        { domainSeparated = true, hashAlgorithm = "keccak" }: TreeOptions = {}
+    ): Buffer {
+        const prefix = domainSeparated ? LEAF_HASH_PREFIX : Buffer.alloc(0);
+        if (hashAlgorithm === "poseidon") {
+            const accountBytes = account.toBuffer();
+            return hashv(hashAlgorithm, [
+                prefix,
+                Buffer.from(new BN(index).toArray("be", 8)),
+                accountBytes.subarray(0, 16),
+                accountBytes.subarray(16),
+                Buffer.from(amount.toArray("be", 8)),
+                hashv(hashAlgorithm, extensions.flatMap(extension => [extension.subarray(0, 16), extension.subarray(16)])),
+            ]);
+        }
+        return hashv(hashAlgorithm, [
+            prefix,
+            Buffer.from(new BN(index).toArray("le", 8)),
+            account.toBuffer(),
+            Buffer.from(amount.toArray("le", 8)),
+            ...extensions,
+        ]);
+    }
+
+    // Chains each split onto the previous hash, starting from 32 zero bytes
+    static hashSplits(splits: PayoutSplit[], hashAlgorithm: TreeOptions["hashAlgorithm"] = "keccak"): Buffer {
+        return splits.reduce((hash, { destination, bps }) => {
+            const destinationBytes = destination.toBuffer();
+            if (hashAlgorithm === "poseidon") {
+                return hashv(hashAlgorithm, [
+                    hash,
+                    destinationBytes.subarray(0, 16),
+                    destinationBytes.subarray(16),
+                    Buffer.from(new BN(bps).toArray("be", 2)),
+                ]);
+            }
+            return hashv(hashAlgorithm, [hash, destinationBytes, Buffer.from(new BN(bps).toArray("le", 2))]);
+        }, Buffer.alloc(32));
+    }
+
+    static hashSchedule({ unlockTs, expiryTs }: LeafSchedule, hashAlgorithm: TreeOptions["hashAlgorithm"] = "keccak"): Buffer {
+        const endianness = hashAlgorithm === "poseidon" ? "be" : "le";
+        return hashv(hashAlgorithm, [
+            Buffer.from(unlockTs.toTwos(64).toArray(endianness, 8)),
+            Buffer.from(expiryTs.toTwos(64).toArray(endianness, 8)),
+        ]);
+    }
+
+    static hashInstallments({ amountPerInstallment, count, interval }: InstallmentPlan, hashAlgorithm: TreeOptions["hashAlgorithm"] = "keccak"): Buffer {
+        const endianness = hashAlgorithm === "poseidon" ? "be" : "le";
+        return hashv(hashAlgorithm, [
+            Buffer.from(amountPerInstallment.toArray(endianness, 8)),
+            Buffer.from(new BN(count).toArray(endianness, 2)),
+            Buffer.from(interval.toTwos(64).toArray(endianness, 8)),
+        ]);
+    }
+
+    static hashMintIndex(mintIndex: number, hashAlgorithm: TreeOptions["hashAlgorithm"] = "keccak"): Buffer {
+        return hashv(hashAlgorithm, [Buffer.from([mintIndex])]);
     }
 
     getHexRoot(): string {
         return this.tree.getHexRoot();
     }
 
-    getHexProof(index: number, account: PublicKey, amount: BN): string[] {
-        return this.tree.getHexProof(PaymentTree.toNode(index, account, amount));
+    getHexProof(index: number, account: PublicKey, amount: BN, extensions: LeafExtensions = {}): string[] {
+        return this.tree.getHexProof(PaymentTree.toLeaf(index, account, amount, extensions, this.options));
     }
 
     getRoot(): Buffer {
         return this.tree.getRoot();
     }
 
-    getProof(index: number, account: PublicKey, amount: BN): Buffer[] {
-        return this.tree.getProof(PaymentTree.toNode(index, account, amount));
+    getProof(index: number, account: PublicKey, amount: BN, extensions: LeafExtensions = {}): Buffer[] {
+        return this.tree.getProof(PaymentTree.toLeaf(index, account, amount, extensions, this.options));
     }
 }
\ No newline at end of file
@@ -0,0 +1,42 @@
+import { keccak_256 } from "js-sha3";
+import { createHash } from "crypto";
+import {
+    poseidon1, poseidon2, poseidon3, poseidon4, poseidon5, poseidon6,
+    poseidon7, poseidon8, poseidon9, poseidon10, poseidon11, poseidon12,
+} from "poseidon-lite";
+
+// Mirrors the on-chain `HashAlgorithm` enum
+export type HashAlgorithm = "keccak" | "sha256" | "poseidon";
+
+// Converts to the Anchor enum representation (e.g. { keccak: {} })
+export function toAnchorHashAlgorithm(hashAlgorithm: HashAlgorithm) {
+    return { [hashAlgorithm]: {} };
+}
+
+// Keccak and Sha256 hash the concatenated bytes,
+// Poseidon (circom, BN254) treats each value as a big-endian field element
+export function hashv(hashAlgorithm: HashAlgorithm, vals: Buffer[]): Buffer {
+    switch (hashAlgorithm) {
+        case "keccak":
+            return Buffer.from(keccak_256(Buffer.concat(vals)), "hex");
+        case "sha256":
+            return createHash("sha256").update(Buffer.concat(vals)).digest();
+        case "poseidon":
+            return poseidonHashv(vals);
+    }
+}
+
+// The on-chain Poseidon syscall takes 1 to 12 inputs
+const POSEIDON_BY_INPUTS = [
+    poseidon1, poseidon2, poseidon3, poseidon4, poseidon5, poseidon6,
+    poseidon7, poseidon8, poseidon9, poseidon10, poseidon11, poseidon12,
+];
+
+function poseidonHashv(vals: Buffer[]): Buffer {
+    const inputs = vals.map(val => BigInt(`0x${val.toString("hex")}`));
+    const poseidon = POSEIDON_BY_INPUTS[inputs.length - 1];
+    if (!poseidon) {
+        throw new Error(`Unsupported number of poseidon inputs: ${inputs.length}`);
+    }
+    return Buffer.from(poseidon(inputs).toString(16).padStart(64, "0"), "hex");
+}
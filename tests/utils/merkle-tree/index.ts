@@ -1,4 +1,5 @@
 export * from './PaymentTree';
 export * from './MerkleTree';
 export * from './types';
-export * from './parsePaymentMap';
\ No newline at end of file
+export * from './parsePaymentMap';
+export * from './hash';
\ No newline at end of file
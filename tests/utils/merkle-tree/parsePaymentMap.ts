@@ -1,26 +1,27 @@
 import { BN } from "@coral-xyz/anchor";
 import { PaymentTree } from "./PaymentTree";
-import { MerkleDistributorInfo, PaymentInfo, Payments, PaymentsImport } from "./types";
+import { MerkleDistributorInfo, PaymentInfo, Payments, PaymentsImport, TreeOptions } from "./types";
 
-export function parsePaymentMap(paymentsImport: PaymentsImport): MerkleDistributorInfo {
+export function parsePaymentMap(paymentsImport: PaymentsImport, options: TreeOptions = {}): MerkleDistributorInfo {
     const payments: PaymentInfo[] = [];
     const treePayments: Payments = [];
 
-    paymentsImport.forEach(({ address, earnings }, index) => {
+    paymentsImport.forEach(({ address, earnings, ...extensions }, index) => {
         const amount = new BN(earnings);
         if (amount.lte(new BN(0))) {
             throw new Error(`Invalid amount for account: ${address.publicKey.toBase58()}`);
         }
-        treePayments.push({ account: address, amount });
+        treePayments.push({ account: address, amount, ...extensions });
     });
 
-    const tree = new PaymentTree(treePayments);
+    const tree = new PaymentTree(treePayments, options);
 
-    payments.push(...treePayments.map(({ account, amount }, index) => ({
+    payments.push(...treePayments.map(({ account, amount, ...extensions }, index) => ({
         index,
         amount,
-        proof: tree.getProof(index, account.publicKey, amount),
+        proof: tree.getProof(index, account.publicKey, amount, extensions),
         keypair: account,
+        ...extensions,
     })));
 
     const tokenTotal = payments.reduce(
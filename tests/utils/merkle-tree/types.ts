@@ -1,6 +1,7 @@
 import { BN, web3 } from "@coral-xyz/anchor";
+import { HashAlgorithm } from "./hash";
 
-export interface PaymentInfo {
+export interface PaymentInfo extends LeafExtensions {
     index: number;
     keypair: web3.Keypair;
     amount: BN;
@@ -13,12 +14,45 @@ export interface MerkleDistributorInfo {
     payments: PaymentInfo[];
 }
 
+// A destination of a split leaf and its share of the payout in basis points
+export interface PayoutSplit {
+    destination: web3.PublicKey;
+    bps: number;
+}
+
+// Unlock and expiry timestamps of a scheduled leaf (expiryTs of i64::MAX never expires)
+export interface LeafSchedule {
+    unlockTs: BN;
+    expiryTs: BN;
+}
+
+// Installment terms of an installment leaf: `count` payments of `amountPerInstallment`, `interval` seconds apart
+export interface InstallmentPlan {
+    amountPerInstallment: BN;
+    count: number;
+    interval: BN;
+}
+
+// Optional terms committed in an extended leaf
+export interface LeafExtensions {
+    splits?: PayoutSplit[];
+    schedule?: LeafSchedule;
+    installments?: InstallmentPlan;
+    // Index of the registered mint paid by the leaf (the tree's mint when omitted)
+    mintIndex?: number;
+}
+
 // For Importing from JSON, CSV, TXT, etc.
-export type PaymentImport = { address: web3.Keypair; earnings: string };
+export type PaymentImport = { address: web3.Keypair; earnings: string } & LeafExtensions;
 export type PaymentsImport = PaymentImport[];
 
-interface Payment {
+interface Payment extends LeafExtensions {
     account: web3.Keypair;
     amount: BN;
 }
-export type Payments = Payment[];
\ No newline at end of file
+export type Payments = Payment[];
+
+export interface TreeOptions {
+    domainSeparated?: boolean;
+    hashAlgorithm?: HashAlgorithm;
+}
\ No newline at end of file
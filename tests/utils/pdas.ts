@@ -3,8 +3,22 @@ import { PublicKey } from '@solana/web3.js';
 
 const SEEDS: Record<string, string> = {
     DISTRIBUTOR: 'DISTRIBUTION_TREE',
+    CLAIM_RECORD: 'CLAIM_RECORD',
+    LEAF_REDIRECT: 'LEAF_REDIRECT',
+    DECLINE_RECORD: 'DECLINE_RECORD',
+    DISPUTE: 'DISPUTE',
+    DELEGATION: 'DELEGATION',
+    INSTALLMENT_RECORD: 'INSTALLMENT_RECORD',
+    MINT_REGISTRY: 'MINT_REGISTRY',
+    SOL_VAULT: 'SOL_VAULT',
 };
 
+function u64ToBuffer(value: number): Buffer {
+    const buffer = Buffer.alloc(8);
+    buffer.writeBigUInt64LE(BigInt(value));
+    return buffer;
+}
+
 export function getDistributionTreePDA({
     distributorProgram,
     authority,
@@ -25,6 +39,178 @@ export function getDistributionTreePDA({
     return distributionTreePDA;
 }
 
+export function getClaimRecordPDA({
+    distributorProgram,
+    distributionTree,
+    claimant,
+    epoch
+}: {
+    distributorProgram: PublicKey,
+    distributionTree: PublicKey,
+    claimant: PublicKey,
+    epoch: number
+}): PublicKey {
+    const [claimRecordPDA] = PublicKey.findProgramAddressSync(
+        [
+            Buffer.from(SEEDS.CLAIM_RECORD),
+            distributionTree.toBuffer(),
+            claimant.toBuffer(),
+            u64ToBuffer(epoch)
+        ],
+        distributorProgram
+    );
+    return claimRecordPDA;
+}
+
+export function getLeafRedirectPDA({
+    distributorProgram,
+    distributionTree,
+    epoch,
+    index
+}: {
+    distributorProgram: PublicKey,
+    distributionTree: PublicKey,
+    epoch: number,
+    index: number
+}): PublicKey {
+    const [leafRedirectPDA] = PublicKey.findProgramAddressSync(
+        [
+            Buffer.from(SEEDS.LEAF_REDIRECT),
+            distributionTree.toBuffer(),
+            u64ToBuffer(epoch),
+            u64ToBuffer(index)
+        ],
+        distributorProgram
+    );
+    return leafRedirectPDA;
+}
+
+export function getDeclineRecordPDA({
+    distributorProgram,
+    distributionTree,
+    epoch,
+    index
+}: {
+    distributorProgram: PublicKey,
+    distributionTree: PublicKey,
+    epoch: number,
+    index: number
+}): PublicKey {
+    const [declineRecordPDA] = PublicKey.findProgramAddressSync(
+        [
+            Buffer.from(SEEDS.DECLINE_RECORD),
+            distributionTree.toBuffer(),
+            u64ToBuffer(epoch),
+            u64ToBuffer(index)
+        ],
+        distributorProgram
+    );
+    return declineRecordPDA;
+}
+
+export function getDisputePDA({
+    distributorProgram,
+    distributionTree,
+    epoch,
+    index
+}: {
+    distributorProgram: PublicKey,
+    distributionTree: PublicKey,
+    epoch: number,
+    index: number
+}): PublicKey {
+    const [disputePDA] = PublicKey.findProgramAddressSync(
+        [
+            Buffer.from(SEEDS.DISPUTE),
+            distributionTree.toBuffer(),
+            u64ToBuffer(epoch),
+            u64ToBuffer(index)
+        ],
+        distributorProgram
+    );
+    return disputePDA;
+}
+
+export function getDelegationPDA({
+    distributorProgram,
+    distributionTree,
+    epoch,
+    index
+}: {
+    distributorProgram: PublicKey,
+    distributionTree: PublicKey,
+    epoch: number,
+    index: number
+}): PublicKey {
+    const [delegationPDA] = PublicKey.findProgramAddressSync(
+        [
+            Buffer.from(SEEDS.DELEGATION),
+            distributionTree.toBuffer(),
+            u64ToBuffer(epoch),
+            u64ToBuffer(index)
+        ],
+        distributorProgram
+    );
+    return delegationPDA;
+}
+
+export function getInstallmentRecordPDA({
+    distributorProgram,
+    distributionTree,
+    epoch,
+    index
+}: {
+    distributorProgram: PublicKey,
+    distributionTree: PublicKey,
+    epoch: number,
+    index: number
+}): PublicKey {
+    const [installmentRecordPDA] = PublicKey.findProgramAddressSync(
+        [
+            Buffer.from(SEEDS.INSTALLMENT_RECORD),
+            distributionTree.toBuffer(),
+            u64ToBuffer(epoch),
+            u64ToBuffer(index)
+        ],
+        distributorProgram
+    );
+    return installmentRecordPDA;
+}
+
+export function getMintRegistryPDA({
+    distributorProgram,
+    distributionTree
+}: {
+    distributorProgram: PublicKey,
+    distributionTree: PublicKey
+}): PublicKey {
+    const [mintRegistryPDA] = PublicKey.findProgramAddressSync(
+        [
+            Buffer.from(SEEDS.MINT_REGISTRY),
+            distributionTree.toBuffer()
+        ],
+        distributorProgram
+    );
+    return mintRegistryPDA;
+}
+
+export function getSolVaultPDA({
+    distributorProgram,
+    distributionTree
+}: {
+    distributorProgram: PublicKey,
+    distributionTree: PublicKey
+}): PublicKey {
+    const [solVaultPDA] = PublicKey.findProgramAddressSync(
+        [
+            Buffer.from(SEEDS.SOL_VAULT),
+            distributionTree.toBuffer()
+        ],
+        distributorProgram
+    );
+    return solVaultPDA;
+}
+
 export function getTokenVaultAddress({
     mint,
     distributionTreePDA,
@@ -17,6 +17,8 @@ import {
     TOKEN_2022_PROGRAM_ID,
     createInitializeMetadataPointerInstruction,
     createInitializeMintInstruction,
+    createInitializeTransferFeeConfigInstruction,
+    createInitializeTransferHookInstruction,
     ExtensionType,
     getMintLen,
     LENGTH_SIZE,
@@ -216,6 +218,63 @@ export const makeTokenMint = async ({
     return mint.publicKey;
 };
 
+interface MakeTransferFeeMintParams {
+    connection: Connection;
+    mintAuthority: Keypair;
+    decimals: number;
+    transferFeeBasisPoints: number;
+    maximumFee: bigint;
+    mint?: Keypair;
+}
+
+// Creates a Token-2022 mint with the TransferFee extension, withholding `transferFeeBasisPoints`
+// (capped at `maximumFee`) from every transfer
+export const makeTransferFeeMint = async ({
+    connection,
+    mintAuthority,
+    decimals,
+    transferFeeBasisPoints,
+    maximumFee,
+    mint = Keypair.generate(),
+}: MakeTransferFeeMintParams) => {
+    const mintLength = getMintLen([ExtensionType.TransferFeeConfig]);
+    const mintLamports = await connection.getMinimumBalanceForRentExemption(mintLength);
+
+    const mintTransaction = new Transaction().add(
+        SystemProgram.createAccount({
+            fromPubkey: mintAuthority.publicKey,
+            newAccountPubkey: mint.publicKey,
+            space: mintLength,
+            lamports: mintLamports,
+            programId: TOKEN_2022_PROGRAM_ID,
+        }),
+        createInitializeTransferFeeConfigInstruction(
+            mint.publicKey,
+            mintAuthority.publicKey,
+            mintAuthority.publicKey,
+            transferFeeBasisPoints,
+            maximumFee,
+            TOKEN_2022_PROGRAM_ID,
+        ),
+        createInitializeMintInstruction(
+            mint.publicKey,
+            decimals,
+            mintAuthority.publicKey,
+            null,
+            TOKEN_2022_PROGRAM_ID,
+        ),
+    );
+
+    await sendAndConfirmTransaction(
+        connection,
+        mintTransaction,
+        [mintAuthority, mint],
+        { commitment: 'processed', skipPreflight: true },
+    );
+
+    return mint.publicKey;
+};
+
 export async function airdropToMultiple(
     pubkeys: PublicKey[],
     connection: Connection,
@@ -258,4 +317,57 @@ export function logAccountKeys(
 ): void {
     console.log(Object.entries(accounts).map(([key, value]) => `${key}: ${value.toBase58()}`).join('\n'));
     return;
-}
\ No newline at end of file
+}
+
+interface MakeTransferHookMintParams {
+    connection: Connection;
+    mintAuthority: Keypair;
+    decimals: number;
+    transferHookProgramId: PublicKey;
+    mint?: Keypair;
+}
+
+// Creates a Token-2022 mint with the TransferHook extension, invoking `transferHookProgramId`
+// on every transfer
+export const makeTransferHookMint = async ({
+    connection,
+    mintAuthority,
+    decimals,
+    transferHookProgramId,
+    mint = Keypair.generate(),
+}: MakeTransferHookMintParams) => {
+    const mintLength = getMintLen([ExtensionType.TransferHook]);
+    const mintLamports = await connection.getMinimumBalanceForRentExemption(mintLength);
+
+    const mintTransaction = new Transaction().add(
+        SystemProgram.createAccount({
+            fromPubkey: mintAuthority.publicKey,
+            newAccountPubkey: mint.publicKey,
+            space: mintLength,
+            lamports: mintLamports,
+            programId: TOKEN_2022_PROGRAM_ID,
+        }),
+        createInitializeTransferHookInstruction(
+            mint.publicKey,
+            mintAuthority.publicKey,
+            transferHookProgramId,
+            TOKEN_2022_PROGRAM_ID,
+        ),
+        createInitializeMintInstruction(
+            mint.publicKey,
+            decimals,
+            mintAuthority.publicKey,
+            null,
+            TOKEN_2022_PROGRAM_ID,
+        ),
+    );
+
+    await sendAndConfirmTransaction(
+        connection,
+        mintTransaction,
+        [mintAuthority, mint],
+        { commitment: 'processed', skipPreflight: true },
+    );
+
+    return mint.publicKey;
+};